@@ -1,9 +1,11 @@
 package com.oppzippy.openscq30.lib.wrapper
 
+import com.oppzippy.openscq30.lib.protobuf.ButtonCapabilities as ProtobufButtonCapabilities
 import com.oppzippy.openscq30.lib.protobuf.DeviceFeatures as ProtobufDeviceFeatures
 import com.oppzippy.openscq30.lib.protobuf.NoiseCancelingModeType as ProtobufNoiseCancelingModeType
 import com.oppzippy.openscq30.lib.protobuf.SoundModeProfile as ProtobufSoundModeProfile
 import com.oppzippy.openscq30.lib.protobuf.TransparencyModeType as ProtobufTransparencyModeType
+import com.oppzippy.openscq30.lib.protobuf.buttonCapabilities
 import com.oppzippy.openscq30.lib.protobuf.deviceFeatures
 import com.oppzippy.openscq30.lib.protobuf.dynamicRangeCompressionMinFirmwareVersionOrNull
 import com.oppzippy.openscq30.lib.protobuf.soundModeOrNull
@@ -20,6 +22,7 @@ data class DeviceFeatures(
     val hasTouchTone: Boolean,
     val hasAutoPowerOff: Boolean,
     val dynamicRangeCompressionMinFirmwareVersion: FirmwareVersion?,
+    val buttonCapabilities: ButtonCapabilities = ButtonCapabilities(),
 ) {
     fun toProtobuf(): ProtobufDeviceFeatures = deviceFeatures {
         this@DeviceFeatures.soundMode?.let { soundMode = it.toProtobuf() }
@@ -34,6 +37,7 @@ data class DeviceFeatures(
         this@DeviceFeatures.dynamicRangeCompressionMinFirmwareVersion?.let {
             dynamicRangeCompressionMinFirmwareVersion = it.toProtobuf()
         }
+        buttonCapabilities = this@DeviceFeatures.buttonCapabilities.toProtobuf()
     }
 }
 
@@ -48,6 +52,30 @@ fun ProtobufDeviceFeatures.toKotlin(): DeviceFeatures = DeviceFeatures(
     hasTouchTone = hasTouchTone,
     hasAutoPowerOff = hasAutoPowerOff,
     dynamicRangeCompressionMinFirmwareVersion = dynamicRangeCompressionMinFirmwareVersionOrNull?.toKotlin(),
+    buttonCapabilities = buttonCapabilities.toKotlin(),
+)
+
+data class ButtonCapabilities(
+    val hasSingleClick: Boolean = false,
+    val hasDoubleClick: Boolean = false,
+    val hasLongPress: Boolean = false,
+    val supportedActions: Int = 0,
+) {
+    fun supportsAction(action: ButtonAction): Boolean = supportedActions and (1 shl action.ordinal) != 0
+
+    fun toProtobuf(): ProtobufButtonCapabilities = buttonCapabilities {
+        hasSingleClick = this@ButtonCapabilities.hasSingleClick
+        hasDoubleClick = this@ButtonCapabilities.hasDoubleClick
+        hasLongPress = this@ButtonCapabilities.hasLongPress
+        supportedActions = this@ButtonCapabilities.supportedActions
+    }
+}
+
+fun ProtobufButtonCapabilities.toKotlin(): ButtonCapabilities = ButtonCapabilities(
+    hasSingleClick = hasSingleClick,
+    hasDoubleClick = hasDoubleClick,
+    hasLongPress = hasLongPress,
+    supportedActions = supportedActions,
 )
 
 data class SoundModeProfile(
@@ -21,6 +21,7 @@ data class DeviceState(
     val firmwareVersion: FirmwareVersion?,
     val serialNumber: String?,
     val ambientSoundModeCycle: AmbientSoundModeCycle?,
+    val isDynamicRangeCompressionEnabled: Boolean = true,
 ) {
     companion object // used for static extension methods in tests
 
@@ -49,6 +50,7 @@ data class DeviceState(
         this@DeviceState.firmwareVersion?.let { firmwareVersion = it.toProtobuf() }
         this@DeviceState.serialNumber?.let { serialNumber = it }
         this@DeviceState.ambientSoundModeCycle?.let { ambientSoundModeCycle = it.toProtobuf() }
+        isDynamicRangeCompressionEnabled = this@DeviceState.isDynamicRangeCompressionEnabled
     }
 }
 
@@ -65,4 +67,5 @@ fun com.oppzippy.openscq30.lib.protobuf.DeviceState.toKotlin(): DeviceState = De
     firmwareVersion = firmwareVersionOrNull?.toKotlin(),
     serialNumber = if (hasSerialNumber()) serialNumber else null,
     ambientSoundModeCycle = ambientSoundModeCycleOrNull?.toKotlin(),
+    isDynamicRangeCompressionEnabled = isDynamicRangeCompressionEnabled,
 )
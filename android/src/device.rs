@@ -7,8 +7,8 @@ use openscq30_lib::{
     devices::standard::{
         state::DeviceState,
         structures::{
-            AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, HearId, SoundModes,
-            SoundModesTypeTwo,
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, Gender,
+            HearId, SoundModes, SoundModesTypeTwo,
         },
     },
     futures::TokioFutures,
@@ -21,14 +21,45 @@ use uuid::Uuid;
 use crate::connection::ManualConnection;
 
 #[derive(Error, Debug, uniffi::Error)]
-#[uniffi(flat_error)]
 pub enum DeviceError {
-    #[error(transparent)]
-    OpenSCQ30Native(#[from] openscq30_lib::Error),
-    #[error(transparent)]
-    Protobuf(#[from] prost::DecodeError),
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+    /// `code` is the stable [`openscq30_lib::ErrorCode`] string, and `details` is the protobuf
+    /// encoded error with any structured fields.
+    #[error("{message}")]
+    OpenSCQ30Native {
+        code: String,
+        message: String,
+        details: Vec<u8>,
+    },
+    #[error("{message}")]
+    Protobuf { message: String },
+    #[error("{message}")]
+    Io { message: String },
+}
+
+impl From<openscq30_lib::Error> for DeviceError {
+    fn from(error: openscq30_lib::Error) -> Self {
+        Self::OpenSCQ30Native {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            details: openscq30_lib_protobuf::serialize_error(&error),
+        }
+    }
+}
+
+impl From<prost::DecodeError> for DeviceError {
+    fn from(error: prost::DecodeError) -> Self {
+        Self::Protobuf {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for DeviceError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io {
+            message: error.to_string(),
+        }
+    }
 }
 
 #[uniffi::export(callback_interface)]
@@ -154,10 +185,28 @@ impl NativeSoundcoreDevice {
             .map_err(Into::into)
     }
 
+    pub async fn set_dynamic_range_compression(&self, is_enabled: bool) -> Result<(), DeviceError> {
+        self.device
+            .set_dynamic_range_compression(is_enabled)
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn set_hear_id(&self, hear_id: HearId) -> Result<(), DeviceError> {
         self.device.set_hear_id(hear_id).await.map_err(Into::into)
     }
 
+    pub async fn set_gender_and_age_range(
+        &self,
+        gender: u8,
+        age_range: u8,
+    ) -> Result<(), DeviceError> {
+        self.device
+            .set_gender_and_age_range(Gender::from(gender), AgeRange::from(age_range))
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
@@ -257,6 +306,20 @@ impl DeviceImplementation {
         }
     }
 
+    pub async fn set_dynamic_range_compression(
+        &self,
+        is_enabled: bool,
+    ) -> openscq30_lib::Result<()> {
+        match self {
+            DeviceImplementation::Manual(device) => {
+                device.set_dynamic_range_compression(is_enabled).await
+            }
+            DeviceImplementation::Demo(device) => {
+                device.set_dynamic_range_compression(is_enabled).await
+            }
+        }
+    }
+
     pub async fn set_hear_id(&self, hear_id: HearId) -> openscq30_lib::Result<()> {
         match self {
             DeviceImplementation::Manual(device) => device.set_hear_id(hear_id).await,
@@ -264,6 +327,21 @@ impl DeviceImplementation {
         }
     }
 
+    pub async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> openscq30_lib::Result<()> {
+        match self {
+            DeviceImplementation::Manual(device) => {
+                device.set_gender_and_age_range(gender, age_range).await
+            }
+            DeviceImplementation::Demo(device) => {
+                device.set_gender_and_age_range(gender, age_range).await
+            }
+        }
+    }
+
     pub async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
@@ -0,0 +1,410 @@
+use std::{error::Error, path::Path};
+
+use openscq30_lib::{
+    api::device::Device,
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AmbientSoundMode, AmbientSoundModeCycle, CustomButtonModel, CustomNoiseCanceling,
+            EqualizerConfiguration, ManualNoiseCanceling, NoiseCancelingAdaptiveSensitivityLevel,
+            NoiseCancelingMode, NoiseCancelingModeTypeTwo, PresetEqualizerProfile, SoundModes,
+            SoundModesTypeTwo, TransparencyMode, VolumeAdjustments,
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Settings to apply to the device. Anything left out is left as it is.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DesiredState {
+    sound_modes: Option<DesiredSoundModes>,
+    sound_modes_type_two: Option<DesiredSoundModesTypeTwo>,
+    equalizer: Option<DesiredEqualizer>,
+    custom_button_model: Option<CustomButtonModel>,
+    ambient_sound_mode_cycle: Option<AmbientSoundModeCycle>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DesiredSoundModes {
+    ambient_sound_mode: Option<AmbientSoundMode>,
+    noise_canceling_mode: Option<NoiseCancelingMode>,
+    transparency_mode: Option<TransparencyMode>,
+    custom_noise_canceling: Option<DesiredCustomNoiseCanceling>,
+}
+
+/// A level from 0 to 10, or "adaptive"
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DesiredCustomNoiseCanceling {
+    Level(u8),
+    Named(NamedCustomNoiseCanceling),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum NamedCustomNoiseCanceling {
+    Adaptive,
+}
+
+impl DesiredCustomNoiseCanceling {
+    fn to_custom_noise_canceling(&self) -> openscq30_lib::Result<CustomNoiseCanceling> {
+        match self {
+            DesiredCustomNoiseCanceling::Level(level) => CustomNoiseCanceling::try_new(*level),
+            DesiredCustomNoiseCanceling::Named(NamedCustomNoiseCanceling::Adaptive) => {
+                Ok(CustomNoiseCanceling::ADAPTIVE)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DesiredSoundModesTypeTwo {
+    ambient_sound_mode: Option<AmbientSoundMode>,
+    transparency_mode: Option<TransparencyMode>,
+    noise_canceling_mode: Option<NoiseCancelingModeTypeTwo>,
+    manual_noise_canceling: Option<ManualNoiseCanceling>,
+    wind_noise_suppression: Option<bool>,
+    noise_canceling_adaptive_sensitivity_level: Option<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+enum DesiredEqualizer {
+    Preset(PresetEqualizerProfile),
+    /// Volume adjustments in dB
+    Custom(Vec<f64>),
+}
+
+/// What needs to be sent to the device to get from the current state to the desired state. Only
+/// settings that differ are set.
+#[derive(Default)]
+struct Plan {
+    steps: Vec<Step>,
+}
+
+/// One write to the device, along with the settings it changes.
+struct Step {
+    write: Write,
+    changes: Vec<Change>,
+}
+
+enum Write {
+    SoundModes(SoundModes),
+    SoundModesTypeTwo(SoundModesTypeTwo),
+    EqualizerConfiguration(EqualizerConfiguration),
+    CustomButtonModel(CustomButtonModel),
+    AmbientSoundModeCycle(AmbientSoundModeCycle),
+}
+
+struct Change {
+    setting: &'static str,
+    from: String,
+    to: String,
+}
+
+impl Change {
+    /// Returns a change if `from` and `to` differ.
+    fn new<T: PartialEq + Serialize>(setting: &'static str, from: &T, to: &T) -> Option<Self> {
+        Self::described(setting, describe(from), describe(to))
+    }
+
+    /// Like [`Change::new`], but for values that are already described.
+    fn described(setting: &'static str, from: String, to: String) -> Option<Self> {
+        (from != to).then_some(Self { setting, from, to })
+    }
+}
+
+impl Plan {
+    fn new(state: &DeviceState, desired: DesiredState) -> openscq30_lib::Result<Self> {
+        let mut plan = Self::default();
+
+        if let Some(desired_sound_modes) = desired.sound_modes {
+            let (Some(sound_mode_profile), Some(current)) =
+                (state.device_features.sound_mode, state.sound_modes)
+            else {
+                return Err(openscq30_lib::Error::FeatureNotSupported {
+                    feature_name: "sound modes",
+                });
+            };
+            let sound_modes = SoundModes {
+                ambient_sound_mode: desired_sound_modes
+                    .ambient_sound_mode
+                    .unwrap_or(current.ambient_sound_mode),
+                noise_canceling_mode: desired_sound_modes
+                    .noise_canceling_mode
+                    .unwrap_or(current.noise_canceling_mode),
+                transparency_mode: desired_sound_modes
+                    .transparency_mode
+                    .unwrap_or(current.transparency_mode),
+                custom_noise_canceling: desired_sound_modes
+                    .custom_noise_canceling
+                    .map(|level| level.to_custom_noise_canceling())
+                    .transpose()?
+                    .unwrap_or(current.custom_noise_canceling),
+            };
+            sound_mode_profile.validate_sound_modes(&current, &sound_modes)?;
+            let changes = [
+                Change::new(
+                    "soundModes.ambientSoundMode",
+                    &current.ambient_sound_mode,
+                    &sound_modes.ambient_sound_mode,
+                ),
+                Change::new(
+                    "soundModes.noiseCancelingMode",
+                    &current.noise_canceling_mode,
+                    &sound_modes.noise_canceling_mode,
+                ),
+                Change::new(
+                    "soundModes.transparencyMode",
+                    &current.transparency_mode,
+                    &sound_modes.transparency_mode,
+                ),
+                Change::described(
+                    "soundModes.customNoiseCanceling",
+                    describe_custom_noise_canceling(current.custom_noise_canceling),
+                    describe_custom_noise_canceling(sound_modes.custom_noise_canceling),
+                ),
+            ];
+            plan.push_step(Write::SoundModes(sound_modes), changes);
+        }
+
+        if let Some(desired_sound_modes) = desired.sound_modes_type_two {
+            let current =
+                state
+                    .sound_modes_type_two
+                    .ok_or(openscq30_lib::Error::FeatureNotSupported {
+                        feature_name: "sound modes type two",
+                    })?;
+            let sound_modes = SoundModesTypeTwo {
+                ambient_sound_mode: desired_sound_modes
+                    .ambient_sound_mode
+                    .unwrap_or(current.ambient_sound_mode),
+                transparency_mode: desired_sound_modes
+                    .transparency_mode
+                    .unwrap_or(current.transparency_mode),
+                noise_canceling_mode: desired_sound_modes
+                    .noise_canceling_mode
+                    .unwrap_or(current.noise_canceling_mode),
+                manual_noise_canceling: desired_sound_modes
+                    .manual_noise_canceling
+                    .unwrap_or(current.manual_noise_canceling),
+                wind_noise_suppression: desired_sound_modes
+                    .wind_noise_suppression
+                    .unwrap_or(current.wind_noise_suppression),
+                noise_canceling_adaptive_sensitivity_level: desired_sound_modes
+                    .noise_canceling_adaptive_sensitivity_level
+                    .map(clamp_adaptive_sensitivity_level)
+                    .unwrap_or(current.noise_canceling_adaptive_sensitivity_level),
+                ..current
+            };
+            let changes = [
+                Change::new(
+                    "soundModesTypeTwo.ambientSoundMode",
+                    &current.ambient_sound_mode,
+                    &sound_modes.ambient_sound_mode,
+                ),
+                Change::new(
+                    "soundModesTypeTwo.transparencyMode",
+                    &current.transparency_mode,
+                    &sound_modes.transparency_mode,
+                ),
+                Change::new(
+                    "soundModesTypeTwo.noiseCancelingMode",
+                    &current.noise_canceling_mode,
+                    &sound_modes.noise_canceling_mode,
+                ),
+                Change::new(
+                    "soundModesTypeTwo.manualNoiseCanceling",
+                    &current.manual_noise_canceling,
+                    &sound_modes.manual_noise_canceling,
+                ),
+                Change::new(
+                    "soundModesTypeTwo.windNoiseSuppression",
+                    &current.wind_noise_suppression,
+                    &sound_modes.wind_noise_suppression,
+                ),
+                Change::new(
+                    "soundModesTypeTwo.noiseCancelingAdaptiveSensitivityLevel",
+                    &current.noise_canceling_adaptive_sensitivity_level,
+                    &sound_modes.noise_canceling_adaptive_sensitivity_level,
+                ),
+            ];
+            plan.push_step(Write::SoundModesTypeTwo(sound_modes), changes);
+        }
+
+        if let Some(desired_equalizer) = desired.equalizer {
+            let num_bands = state.device_features.num_equalizer_bands;
+            let equalizer_configuration = match desired_equalizer {
+                DesiredEqualizer::Preset(profile) => {
+                    EqualizerConfiguration::new_from_preset_profile(profile)
+                }
+                DesiredEqualizer::Custom(volume_adjustments) => {
+                    if volume_adjustments.len() != num_bands {
+                        return Err(openscq30_lib::Error::InvalidValue {
+                            name: "number of volume adjustments",
+                            value: format!(
+                                "the device has {num_bands} bands, got {}",
+                                volume_adjustments.len(),
+                            ),
+                        });
+                    }
+                    EqualizerConfiguration::new_custom_profile(
+                        VolumeAdjustments::new(volume_adjustments).map_err(|err| {
+                            openscq30_lib::Error::InvalidValue {
+                                name: "volume adjustments",
+                                value: err.to_string(),
+                            }
+                        })?,
+                    )
+                }
+            };
+            let change = Change::described(
+                "equalizer",
+                describe_equalizer(&state.equalizer_configuration),
+                describe_equalizer(&equalizer_configuration),
+            );
+            plan.push_step(
+                Write::EqualizerConfiguration(equalizer_configuration),
+                [change],
+            );
+        }
+
+        if let Some(custom_button_model) = desired.custom_button_model {
+            let current =
+                state
+                    .custom_button_model
+                    .ok_or(openscq30_lib::Error::FeatureNotSupported {
+                        feature_name: "custom button model",
+                    })?;
+            plan.push_step(
+                Write::CustomButtonModel(custom_button_model),
+                [Change::new(
+                    "customButtonModel",
+                    &current,
+                    &custom_button_model,
+                )],
+            );
+        }
+
+        if let Some(cycle) = desired.ambient_sound_mode_cycle {
+            let current = state.ambient_sound_mode_cycle.ok_or(
+                openscq30_lib::Error::FeatureNotSupported {
+                    feature_name: "ambient sound mode cycle",
+                },
+            )?;
+            plan.push_step(
+                Write::AmbientSoundModeCycle(cycle),
+                [Change::new("ambientSoundModeCycle", &current, &cycle)],
+            );
+        }
+
+        Ok(plan)
+    }
+
+    /// Adds a step if anything it writes differs from the current state.
+    fn push_step(&mut self, write: Write, changes: impl IntoIterator<Item = Option<Change>>) {
+        let changes = changes.into_iter().flatten().collect::<Vec<_>>();
+        if !changes.is_empty() {
+            self.steps.push(Step { write, changes });
+        }
+    }
+
+    fn changes(&self) -> impl Iterator<Item = &Change> {
+        self.steps.iter().flat_map(|step| step.changes.iter())
+    }
+
+    /// Prints each change once the device has accepted it, so that if a write fails partway
+    /// through, the output shows what was already applied.
+    async fn execute(self, device: &impl Device) -> openscq30_lib::Result<()> {
+        for step in self.steps {
+            match step.write {
+                Write::SoundModes(sound_modes) => device.set_sound_modes(sound_modes).await?,
+                Write::SoundModesTypeTwo(sound_modes) => {
+                    device.set_sound_modes_type_two(sound_modes).await?
+                }
+                Write::EqualizerConfiguration(equalizer_configuration) => {
+                    device
+                        .set_equalizer_configuration(equalizer_configuration)
+                        .await?
+                }
+                Write::CustomButtonModel(custom_button_model) => {
+                    device.set_custom_button_model(custom_button_model).await?
+                }
+                Write::AmbientSoundModeCycle(cycle) => {
+                    device.set_ambient_sound_mode_cycle(cycle).await?
+                }
+            }
+            for change in &step.changes {
+                print_change(change);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Out of range levels are clamped rather than rejected, so that the rest of the file still applies
+fn clamp_adaptive_sensitivity_level(level: u8) -> NoiseCancelingAdaptiveSensitivityLevel {
+    let clamped = NoiseCancelingAdaptiveSensitivityLevel::clamped(level);
+    if clamped.value() != level {
+        eprintln!(
+            "Warning: noiseCancelingAdaptiveSensitivityLevel {level} is out of range, so {} will be used instead.",
+            clamped.value(),
+        );
+    }
+    clamped
+}
+
+fn print_change(change: &Change) {
+    println!("{}: {} -> {}", change.setting, change.from, change.to);
+}
+
+/// Uses the same representation as the input file so that values can be copied back into it.
+fn describe(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("settings should always be serializable")
+}
+
+/// Matches the input file, where adaptive is written by name rather than as its raw value.
+fn describe_custom_noise_canceling(custom_noise_canceling: CustomNoiseCanceling) -> String {
+    match custom_noise_canceling.level() {
+        Some(level) => level.to_string(),
+        None => "\"adaptive\"".to_owned(),
+    }
+}
+
+fn describe_equalizer(equalizer_configuration: &EqualizerConfiguration) -> String {
+    match equalizer_configuration.preset_profile() {
+        Some(profile) => format!("{{\"preset\":{}}}", describe(&profile)),
+        None => format!(
+            "{{\"custom\":{}}}",
+            describe(equalizer_configuration.volume_adjustments())
+        ),
+    }
+}
+
+pub async fn apply(path: &Path, dry_run: bool, device: &impl Device) -> Result<(), Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let desired: DesiredState = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        serde_json::from_str(&contents)?
+    } else {
+        toml::from_str(&contents)?
+    };
+
+    let plan = Plan::new(&device.state().await, desired)?;
+    if plan.steps.is_empty() {
+        println!("Nothing to change.");
+        return Ok(());
+    }
+    if dry_run {
+        plan.changes().for_each(print_change);
+        println!("Dry run, nothing was sent to the device.");
+    } else {
+        plan.execute(device).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use std::{error::Error, path::Path};
+
+use openscq30_lib::api::device::{DeviceDescriptor, DeviceRegistry};
+
+pub async fn bug_report<T>(
+    registry: &T,
+    descriptor: &T::DescriptorType,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn Error>>
+where
+    T: DeviceRegistry,
+{
+    let report = registry
+        .bug_report(descriptor.mac_address())
+        .await?
+        .ok_or("No device found.")?;
+    match output {
+        Some(path) => std::fs::write(path, report.to_string())?,
+        None => print!("{report}"),
+    }
+    Ok(())
+}
@@ -1,15 +1,44 @@
-use clap::{command, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use clap::{builder::RangedI64ValueParser, command, Parser, Subcommand, ValueEnum};
 use macaddr::MacAddr6;
-use openscq30_lib::devices::standard::structures::VolumeAdjustments;
+use openscq30_lib::{
+    devices::standard::structures::{
+        CustomNoiseCanceling, NoiseCancelingAdaptiveSensitivityLevel, VolumeAdjustments,
+    },
+    soundcore_device::device_model::DeviceModel,
+};
+use strum::VariantArray;
 use tracing::Level;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub mac_address: Option<MacAddr6>,
+    /// Select the device whose name contains this, ignoring case
+    #[arg(short, long, global = true)]
+    pub name: Option<String>,
+    /// Select the device by model number, such as A3028. Requires connecting to each device.
+    #[arg(long, global = true, value_parser = parse_device_model)]
+    pub model: Option<DeviceModel>,
+    /// Select the device by its index in the output of list-devices
+    #[arg(short, long, global = true)]
+    pub index: Option<usize>,
     #[arg(short, long, default_value_t = Level::WARN)]
     pub logging_level: Level,
+    /// Where devices come from. Defaults to the value of OPENSCQ30_BACKEND if it is set, otherwise
+    /// bluetooth. Implies --no-daemon when either is given.
+    #[arg(long, value_enum)]
+    pub backend: Option<Backend>,
+    /// Connect to the device directly even if openscq30d is running
+    #[cfg(unix)]
+    #[arg(long)]
+    pub no_daemon: bool,
+    /// Config file holding custom equalizer profiles and quick presets. Defaults to the GUI's, so
+    /// that both share them.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
     #[command(subcommand)]
     pub command: Command,
 }
@@ -18,9 +47,75 @@ pub struct Cli {
 pub enum Command {
     #[command(subcommand)]
     Set(SetCommand),
+    Get {
+        /// Defaults to text, except for `state`, which defaults to json
+        #[arg(short, long, global = true, value_enum)]
+        format: Option<OutputFormat>,
+        #[command(subcommand)]
+        command: GetCommand,
+    },
+    /// List nearby devices with their index, name, and MAC address
+    ListDevices {
+        #[arg(short, long, value_enum, default_value_t = ListDevicesFormat::Table)]
+        format: ListDevicesFormat,
+        /// Also show each device's model. Requires connecting to each device.
+        #[arg(long)]
+        show_model: bool,
+    },
+    /// Switch between two equalizer curves for A/B listening. Reads commands from stdin.
+    CompareEqualizer {
+        #[arg(
+            long,
+            required = true,
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            allow_negative_numbers = true,
+            value_parser = volume_adjustment_parser(),
+        )]
+        a: Vec<i16>,
+        #[arg(
+            long,
+            required = true,
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            allow_negative_numbers = true,
+            value_parser = volume_adjustment_parser(),
+        )]
+        b: Vec<i16>,
+        /// Hide which curve is active and pick one at random for each trial
+        #[arg(long)]
+        blind: bool,
+    },
+    /// Apply the settings in a toml or json file, changing only what differs from the device's
+    /// current state
+    Apply {
+        file: PathBuf,
+        /// Show what would change without sending anything to the device
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print changes to the device's state as they happen, until it disconnects
+    Watch {
+        #[arg(short, long, value_enum, default_value_t = WatchFormat::Text)]
+        format: WatchFormat,
+    },
+    /// Interactive terminal interface showing live state, with keyboard control of sound modes,
+    /// the equalizer, and buttons
+    Tui,
+    /// Manage custom equalizer profiles, which are shared with the GUI
+    #[command(subcommand)]
+    EqualizerProfile(EqualizerProfileCommand),
+    /// Manage the selected device's quick presets, which are shared with the GUI
     #[command(subcommand)]
-    Get(GetCommand),
-    ListDevices,
+    Preset(PresetCommand),
+    /// Browse and restore HearID profiles previously seen on the selected device
+    #[command(subcommand)]
+    HearIdHistory(HearIdHistoryCommand),
+    /// Collect the raw packets, parse errors, and features of a device into a single file with its
+    /// serial number redacted, for attaching to bug reports.
+    BugReport {
+        /// Where to write the report. Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     Completions {
         #[arg(required = true)]
         shell: Shell,
@@ -59,23 +154,326 @@ pub enum SetCommand {
         #[arg(value_enum)]
         mode: NoiseCancelingMode,
     },
+    /// Only available on devices with custom transparency
+    TransparencyMode {
+        #[arg(value_enum)]
+        mode: TransparencyMode,
+    },
+    /// Strength of noise canceling when the noise canceling mode is custom, from 0 to 10, or
+    /// "adaptive" to let the device decide
+    CustomNoiseCanceling {
+        #[arg(value_parser = parse_custom_noise_canceling)]
+        level: CustomNoiseCanceling,
+    },
+    /// Choose between adaptive and manual noise canceling on devices with adaptive noise canceling
+    NoiseCancelingModeTypeTwo {
+        #[arg(value_enum)]
+        mode: NoiseCancelingModeTypeTwo,
+    },
+    /// Strength of noise canceling when the type two noise canceling mode is manual
+    ManualNoiseCanceling {
+        #[arg(value_enum)]
+        level: ManualNoiseCanceling,
+    },
+    /// How strongly adaptive noise canceling reacts to changes in ambient noise
+    AdaptiveSensitivity {
+        #[arg(value_parser = clap::value_parser!(u8).range(
+            NoiseCancelingAdaptiveSensitivityLevel::MIN as i64
+                ..=NoiseCancelingAdaptiveSensitivityLevel::MAX as i64,
+        ))]
+        level: u8,
+    },
+    /// Reduces wind noise while noise canceling is on
+    WindNoiseSuppression {
+        #[arg(action = clap::ArgAction::Set)]
+        is_enabled: bool,
+    },
+    /// Volume adjustments in tenths of a dB, one for each of the device's equalizer bands
     Equalizer {
         #[arg(
-            required=true,
-            num_args = 8,
-            value_parser = clap::value_parser!(i16).range((VolumeAdjustments::MIN_VOLUME * 10.0).round() as i64..(VolumeAdjustments::MAX_VOLUME * 10.0).round() as i64 + 1),
+            required = true,
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            value_parser = volume_adjustment_parser(),
+        )]
+        volume_adjustments: Vec<i16>,
+        /// Lower the curve so that no band is boosted, avoiding clipping
+        #[arg(long)]
+        normalize: bool,
+    },
+    EqualizerPreset {
+        #[arg(value_enum)]
+        profile: PresetEqualizerProfile,
+    },
+    DynamicRangeCompression {
+        #[arg(action = clap::ArgAction::Set)]
+        is_enabled: bool,
+    },
+    /// Which ambient sound modes the button on the device cycles through
+    AmbientSoundModeCycle {
+        #[arg(required = true, value_enum)]
+        modes: Vec<AmbientSoundMode>,
+    },
+    /// Change what a button gesture does. Options that are left out stay as they are.
+    Button {
+        #[arg(value_enum)]
+        side: ButtonSide,
+        #[arg(value_enum)]
+        gesture: ButtonGesture,
+        /// Action when both earbuds are connected. Single click has only this action.
+        #[arg(long, value_enum)]
+        action: Option<ButtonAction>,
+        /// Action when only one earbud is connected
+        #[arg(long, value_enum)]
+        disconnected_action: Option<ButtonAction>,
+        #[arg(long, action = clap::ArgAction::Set)]
+        enabled: Option<bool>,
+    },
+    /// Change the HearID profile. Options that are left out stay as they are.
+    HearId {
+        #[arg(long, action = clap::ArgAction::Set)]
+        enabled: Option<bool>,
+        /// Volume adjustments for the left ear in tenths of a dB, one for each band
+        #[arg(
+            long,
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            allow_negative_numbers = true,
+            value_parser = volume_adjustment_parser(),
+        )]
+        left: Option<Vec<i16>>,
+        /// Volume adjustments for the right ear in tenths of a dB, one for each band
+        #[arg(
+            long,
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            allow_negative_numbers = true,
+            value_parser = volume_adjustment_parser(),
+        )]
+        right: Option<Vec<i16>>,
+        /// Only custom HearID profiles have a type
+        #[arg(long, value_enum)]
+        hear_id_type: Option<HearIdType>,
+    },
+    /// Change the gender and age range that are sent along with the HearID profile. Options that
+    /// are left out stay as they are.
+    GenderAndAgeRange {
+        #[arg(long, value_enum)]
+        gender: Option<Gender>,
+        #[arg(long, value_enum)]
+        age_range: Option<AgeRange>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EqualizerProfileCommand {
+    /// Print each profile's name and volume adjustments in dB
+    List,
+    /// Create a profile, replacing any profile with the same name or volume adjustments
+    Create {
+        profile: String,
+        /// Volume adjustments in tenths of a dB. Defaults to the device's current equalizer.
+        #[arg(
+            num_args = VolumeAdjustments::VALID_NUMBER_OF_BANDS,
+            allow_negative_numbers = true,
+            value_parser = volume_adjustment_parser(),
         )]
         volume_adjustments: Vec<i16>,
     },
+    Delete {
+        profile: String,
+    },
+    /// Write profiles as json in the same format as the GUI's export
+    Export {
+        /// Profiles to export. Defaults to all of them.
+        profiles: Vec<String>,
+        /// Defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Read profiles exported by the GUI or the export command. Profiles with the same volume
+    /// adjustments as an existing profile are skipped, and conflicting names are numbered.
+    Import {
+        /// Defaults to stdin
+        file: Option<PathBuf>,
+        /// Replace existing profiles with the same name or volume adjustments instead
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Set the device's equalizer to a profile
+    Activate {
+        profile: String,
+    },
+}
+
+impl EqualizerProfileCommand {
+    /// Most commands only touch the config file
+    pub fn needs_device(&self) -> bool {
+        match self {
+            EqualizerProfileCommand::Create {
+                volume_adjustments, ..
+            } => volume_adjustments.is_empty(),
+            EqualizerProfileCommand::Activate { .. } => true,
+            EqualizerProfileCommand::List
+            | EqualizerProfileCommand::Delete { .. }
+            | EqualizerProfileCommand::Export { .. }
+            | EqualizerProfileCommand::Import { .. } => false,
+        }
+    }
+}
+
+/// Quick presets belong to a type of device, so the device is needed even for commands that only
+/// touch the config file.
+#[derive(Subcommand)]
+pub enum PresetCommand {
+    List,
+    /// Create a quick preset, replacing any with the same name. Settings that are left out are not
+    /// changed when it is activated.
+    Create {
+        preset: String,
+        #[arg(long, value_enum)]
+        ambient_sound_mode: Option<AmbientSoundMode>,
+        #[arg(long, value_enum)]
+        transparency_mode: Option<TransparencyMode>,
+        #[arg(long, value_enum)]
+        noise_canceling_mode: Option<NoiseCancelingMode>,
+        #[arg(long, value_parser = parse_custom_noise_canceling)]
+        custom_noise_canceling: Option<CustomNoiseCanceling>,
+        #[arg(long, value_enum, conflicts_with = "equalizer_profile")]
+        equalizer_preset: Option<PresetEqualizerProfile>,
+        /// Name of a custom equalizer profile
+        #[arg(long)]
+        equalizer_profile: Option<String>,
+    },
+    Delete {
+        preset: String,
+    },
+    /// Write quick presets as json
+    Export {
+        /// Quick presets to export. Defaults to all of them.
+        presets: Vec<String>,
+        /// Defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Read quick presets written by the export command
+    Import {
+        /// Defaults to stdin
+        file: Option<PathBuf>,
+        /// Replace quick presets with the same name instead of failing
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Apply a quick preset's settings to the device the same way the GUI does, skipping settings
+    /// the device doesn't have
+    Activate {
+        preset: String,
+    },
+}
+
+/// Profiles are numbered from oldest to newest, as printed by the list command.
+#[derive(Subcommand)]
+pub enum HearIdHistoryCommand {
+    /// Print recorded profiles as json
+    List,
+    /// Print the change in volume adjustments from one profile to another as json
+    Diff { from: usize, to: usize },
+    /// Write a previous profile back to the device
+    Restore { entry: usize },
+}
+
+fn parse_device_model(value: &str) -> Result<DeviceModel, String> {
+    DeviceModel::VARIANTS
+        .iter()
+        .find(|model| model.as_ref().eq_ignore_ascii_case(value))
+        .copied()
+        .ok_or_else(|| {
+            format!(
+                "expected one of {}",
+                DeviceModel::VARIANTS
+                    .iter()
+                    .map(AsRef::as_ref)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        })
+}
+
+fn parse_custom_noise_canceling(value: &str) -> Result<CustomNoiseCanceling, String> {
+    if value.eq_ignore_ascii_case("adaptive") {
+        return Ok(CustomNoiseCanceling::ADAPTIVE);
+    }
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|level| *level <= CustomNoiseCanceling::MAX_LEVEL)
+        .map(CustomNoiseCanceling::new)
+        .ok_or_else(|| {
+            format!(
+                "expected a level from {} to {} or \"adaptive\"",
+                CustomNoiseCanceling::MIN_LEVEL,
+                CustomNoiseCanceling::MAX_LEVEL,
+            )
+        })
+}
+
+fn volume_adjustment_parser() -> RangedI64ValueParser<i16> {
+    clap::value_parser!(i16).range(
+        (VolumeAdjustments::MIN_VOLUME * 10.0).round() as i64
+            ..(VolumeAdjustments::MAX_VOLUME * 10.0).round() as i64 + 1,
+    )
 }
 
 #[derive(Subcommand)]
 pub enum GetCommand {
+    /// Everything known about the device
+    State,
     AmbientSoundMode,
     NoiseCancelingMode,
+    TransparencyMode,
+    CustomNoiseCanceling,
+    NoiseCancelingModeTypeTwo,
+    ManualNoiseCanceling,
+    AdaptiveSensitivity,
+    WindNoiseSuppression,
     Equalizer,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+    Toml,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Backend {
+    Bluetooth,
+    /// A simulated device, for trying things out without any hardware
+    Demo,
+}
+
+impl From<Backend> for openscq30_lib::api::backend::Backend {
+    fn from(backend: Backend) -> Self {
+        match backend {
+            Backend::Bluetooth => openscq30_lib::api::backend::Backend::Bluetooth,
+            Backend::Demo => openscq30_lib::api::backend::Backend::Demo,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ListDevicesFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum WatchFormat {
+    /// One line for each changed value
+    Text,
+    /// One json object per line containing the full state, for status bars and scripts
+    JsonLines,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum AmbientSoundMode {
     Normal,
@@ -104,6 +502,8 @@ pub enum NoiseCancelingMode {
     Transport,
     Indoor,
     Outdoor,
+    /// Only available on devices with custom noise canceling
+    Custom,
 }
 
 impl From<NoiseCancelingMode> for openscq30_lib::devices::standard::structures::NoiseCancelingMode {
@@ -118,6 +518,291 @@ impl From<NoiseCancelingMode> for openscq30_lib::devices::standard::structures::
             NoiseCancelingMode::Outdoor => {
                 openscq30_lib::devices::standard::structures::NoiseCancelingMode::Outdoor
             }
+            NoiseCancelingMode::Custom => {
+                openscq30_lib::devices::standard::structures::NoiseCancelingMode::Custom
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum TransparencyMode {
+    FullyTransparent,
+    VocalMode,
+}
+
+impl From<TransparencyMode> for openscq30_lib::devices::standard::structures::TransparencyMode {
+    fn from(mode: TransparencyMode) -> Self {
+        match mode {
+            TransparencyMode::FullyTransparent => {
+                openscq30_lib::devices::standard::structures::TransparencyMode::FullyTransparent
+            }
+            TransparencyMode::VocalMode => {
+                openscq30_lib::devices::standard::structures::TransparencyMode::VocalMode
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum NoiseCancelingModeTypeTwo {
+    Adaptive,
+    Manual,
+}
+
+impl From<NoiseCancelingModeTypeTwo>
+    for openscq30_lib::devices::standard::structures::NoiseCancelingModeTypeTwo
+{
+    fn from(mode: NoiseCancelingModeTypeTwo) -> Self {
+        match mode {
+            NoiseCancelingModeTypeTwo::Adaptive => {
+                openscq30_lib::devices::standard::structures::NoiseCancelingModeTypeTwo::Adaptive
+            }
+            NoiseCancelingModeTypeTwo::Manual => {
+                openscq30_lib::devices::standard::structures::NoiseCancelingModeTypeTwo::Manual
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ManualNoiseCanceling {
+    Weak,
+    Moderate,
+    Strong,
+}
+
+impl From<ManualNoiseCanceling>
+    for openscq30_lib::devices::standard::structures::ManualNoiseCanceling
+{
+    fn from(level: ManualNoiseCanceling) -> Self {
+        match level {
+            ManualNoiseCanceling::Weak => {
+                openscq30_lib::devices::standard::structures::ManualNoiseCanceling::Weak
+            }
+            ManualNoiseCanceling::Moderate => {
+                openscq30_lib::devices::standard::structures::ManualNoiseCanceling::Moderate
+            }
+            ManualNoiseCanceling::Strong => {
+                openscq30_lib::devices::standard::structures::ManualNoiseCanceling::Strong
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum PresetEqualizerProfile {
+    SoundcoreSignature,
+    Acoustic,
+    BassBooster,
+    BassReducer,
+    Classical,
+    Podcast,
+    Dance,
+    Deep,
+    Electronic,
+    Flat,
+    HipHop,
+    Jazz,
+    Latin,
+    Lounge,
+    Piano,
+    Pop,
+    RnB,
+    Rock,
+    SmallSpeakers,
+    SpokenWord,
+    TrebleBooster,
+    TrebleReducer,
+}
+
+impl From<PresetEqualizerProfile>
+    for openscq30_lib::devices::standard::structures::PresetEqualizerProfile
+{
+    fn from(profile: PresetEqualizerProfile) -> Self {
+        match profile {
+            PresetEqualizerProfile::SoundcoreSignature => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::SoundcoreSignature
+            }
+            PresetEqualizerProfile::Acoustic => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Acoustic
+            }
+            PresetEqualizerProfile::BassBooster => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::BassBooster
+            }
+            PresetEqualizerProfile::BassReducer => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::BassReducer
+            }
+            PresetEqualizerProfile::Classical => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Classical
+            }
+            PresetEqualizerProfile::Podcast => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Podcast
+            }
+            PresetEqualizerProfile::Dance => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Dance
+            }
+            PresetEqualizerProfile::Deep => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Deep
+            }
+            PresetEqualizerProfile::Electronic => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Electronic
+            }
+            PresetEqualizerProfile::Flat => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Flat
+            }
+            PresetEqualizerProfile::HipHop => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::HipHop
+            }
+            PresetEqualizerProfile::Jazz => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Jazz
+            }
+            PresetEqualizerProfile::Latin => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Latin
+            }
+            PresetEqualizerProfile::Lounge => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Lounge
+            }
+            PresetEqualizerProfile::Piano => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Piano
+            }
+            PresetEqualizerProfile::Pop => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Pop
+            }
+            PresetEqualizerProfile::RnB => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::RnB
+            }
+            PresetEqualizerProfile::Rock => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::Rock
+            }
+            PresetEqualizerProfile::SmallSpeakers => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::SmallSpeakers
+            }
+            PresetEqualizerProfile::SpokenWord => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::SpokenWord
+            }
+            PresetEqualizerProfile::TrebleBooster => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::TrebleBooster
+            }
+            PresetEqualizerProfile::TrebleReducer => {
+                openscq30_lib::devices::standard::structures::PresetEqualizerProfile::TrebleReducer
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ButtonAction {
+    VolumeUp,
+    VolumeDown,
+    PreviousSong,
+    NextSong,
+    AmbientSoundMode,
+    VoiceAssistant,
+    PlayPause,
+}
+
+impl From<ButtonAction> for openscq30_lib::devices::standard::structures::ButtonAction {
+    fn from(action: ButtonAction) -> Self {
+        match action {
+            ButtonAction::VolumeUp => {
+                openscq30_lib::devices::standard::structures::ButtonAction::VolumeUp
+            }
+            ButtonAction::VolumeDown => {
+                openscq30_lib::devices::standard::structures::ButtonAction::VolumeDown
+            }
+            ButtonAction::PreviousSong => {
+                openscq30_lib::devices::standard::structures::ButtonAction::PreviousSong
+            }
+            ButtonAction::NextSong => {
+                openscq30_lib::devices::standard::structures::ButtonAction::NextSong
+            }
+            ButtonAction::AmbientSoundMode => {
+                openscq30_lib::devices::standard::structures::ButtonAction::AmbientSoundMode
+            }
+            ButtonAction::VoiceAssistant => {
+                openscq30_lib::devices::standard::structures::ButtonAction::VoiceAssistant
+            }
+            ButtonAction::PlayPause => {
+                openscq30_lib::devices::standard::structures::ButtonAction::PlayPause
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ButtonSide {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ButtonGesture {
+    SingleClick,
+    DoubleClick,
+    LongPress,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Gender {
+    Female,
+    Male,
+}
+
+impl From<Gender> for openscq30_lib::devices::standard::structures::Gender {
+    fn from(gender: Gender) -> Self {
+        match gender {
+            Gender::Female => openscq30_lib::devices::standard::structures::Gender::Female,
+            Gender::Male => openscq30_lib::devices::standard::structures::Gender::Male,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum AgeRange {
+    Under20,
+    #[value(name = "20-to-29")]
+    From20To29,
+    #[value(name = "30-to-39")]
+    From30To39,
+    #[value(name = "40-to-49")]
+    From40To49,
+    Over50,
+}
+
+impl From<AgeRange> for openscq30_lib::devices::standard::structures::AgeRange {
+    fn from(age_range: AgeRange) -> Self {
+        match age_range {
+            AgeRange::Under20 => openscq30_lib::devices::standard::structures::AgeRange::Under20,
+            AgeRange::From20To29 => {
+                openscq30_lib::devices::standard::structures::AgeRange::From20To29
+            }
+            AgeRange::From30To39 => {
+                openscq30_lib::devices::standard::structures::AgeRange::From30To39
+            }
+            AgeRange::From40To49 => {
+                openscq30_lib::devices::standard::structures::AgeRange::From40To49
+            }
+            AgeRange::Over50 => openscq30_lib::devices::standard::structures::AgeRange::Over50,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum HearIdType {
+    Basic,
+    Custom,
+    HearingTest,
+}
+
+impl From<HearIdType> for openscq30_lib::devices::standard::structures::HearIdType {
+    fn from(hear_id_type: HearIdType) -> Self {
+        match hear_id_type {
+            HearIdType::Basic => openscq30_lib::devices::standard::structures::HearIdType::Basic,
+            HearIdType::Custom => openscq30_lib::devices::standard::structures::HearIdType::Custom,
+            HearIdType::HearingTest => {
+                openscq30_lib::devices::standard::structures::HearIdType::HearingTest
+            }
         }
     }
 }
@@ -0,0 +1,70 @@
+use std::{io::BufRead, rc::Rc};
+
+use openscq30_lib::{
+    api::device::Device,
+    devices::standard::structures::EqualizerConfiguration,
+    equalizer_comparison::{ComparisonSide, EqualizerComparison},
+    futures::TokioFutures,
+};
+
+use crate::set::volume_adjustments_from_tenths;
+
+pub async fn compare_equalizer(
+    device: Rc<impl Device>,
+    a: &[i16],
+    b: &[i16],
+    blind: bool,
+) -> openscq30_lib::Result<()> {
+    let num_bands = device.state().await.device_features.num_equalizer_bands;
+    let comparison = EqualizerComparison::<_, TokioFutures>::new(
+        device,
+        EqualizerConfiguration::new_custom_profile(volume_adjustments_from_tenths(a, num_bands)?),
+        EqualizerConfiguration::new_custom_profile(volume_adjustments_from_tenths(b, num_bands)?),
+    );
+    comparison.set_blind(blind);
+    if blind {
+        comparison.shuffle().await?;
+    } else {
+        comparison.select(ComparisonSide::A).await?;
+    }
+    print_active_side(&comparison);
+    println!("Commands: t = toggle, n = next blind trial, p = prefer current, q = quit");
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line.expect("failed to read stdin");
+        match line.trim() {
+            "t" => {
+                comparison.toggle().await?;
+                print_active_side(&comparison);
+            }
+            "n" => {
+                comparison.shuffle().await?;
+                print_active_side(&comparison);
+            }
+            "p" => {
+                comparison.prefer_active();
+                if blind {
+                    comparison.shuffle().await?;
+                    print_active_side(&comparison);
+                }
+            }
+            "q" => break,
+            "" => (),
+            other => eprintln!("Unknown command: {other}"),
+        }
+    }
+
+    let tally = comparison.tally();
+    println!(
+        "A preferred {} times, B preferred {} times",
+        tally.a, tally.b
+    );
+    Ok(())
+}
+
+fn print_active_side(comparison: &EqualizerComparison<impl Device, TokioFutures>) {
+    match comparison.active_side() {
+        Some(side) => println!("Active: {side}"),
+        None => println!("Active: ?"),
+    }
+}
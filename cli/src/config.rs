@@ -0,0 +1,61 @@
+//! Where the config file shared with the GUI is, and importing and exporting its contents. The
+//! config itself comes from `openscq30_config` so that both frontends read and write the same
+//! format.
+
+use std::{
+    error::Error,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+pub use openscq30_config::{
+    activate_quick_preset, Config, CustomEqualizerProfile, PresetOrCustomEqualizerProfile,
+    QuickPreset,
+};
+use serde::{Deserialize, Serialize};
+
+/// A custom equalizer profile as exported and imported by the GUI
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedCustomEqualizerProfile {
+    pub name: String,
+    pub volume_adjustments: Vec<f64>,
+}
+
+/// `path` if it is set, otherwise where the GUI keeps its config file
+pub fn path(path: Option<&Path>) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(path) = path {
+        return Ok(path.to_owned());
+    }
+    #[cfg(target_os = "linux")]
+    let subdir_name = "openscq30";
+    #[cfg(not(target_os = "linux"))]
+    let subdir_name = "OpenSCQ30";
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to find config directory. Set one with --config.")?
+        .join(subdir_name);
+    Ok(config_dir.join("config.toml"))
+}
+
+/// Reads `file`, or stdin if it's not set
+pub fn read_import(file: Option<&Path>) -> Result<String, Box<dyn Error>> {
+    match file {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| format!("Error reading {}: {err}", path.display()).into()),
+        None => {
+            let mut buffer = String::new();
+            std::io::stdin().read_to_string(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+}
+
+/// Writes to `output`, or stdout if it's not set
+pub fn write_export(output: Option<&Path>, json: &str) -> Result<(), Box<dyn Error>> {
+    match output {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
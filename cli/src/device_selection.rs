@@ -0,0 +1,238 @@
+use std::{error::Error, rc::Rc};
+
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    api::device::{Device, DeviceDescriptor, DeviceRegistry},
+    soundcore_device::device_model::DeviceModel,
+};
+
+/// Criteria for picking one of the available devices. Every criterion that is set must match.
+#[derive(Default)]
+pub struct DeviceSelector {
+    pub mac_address: Option<MacAddr6>,
+    /// Case insensitive substring of the device's name
+    pub name: Option<String>,
+    pub model: Option<DeviceModel>,
+    /// Position in the output of `list-devices`
+    pub index: Option<usize>,
+}
+
+/// Descriptors in the order `list-devices` shows them and `--index` refers to, which doesn't
+/// depend on the order the backend happened to discover them in
+pub fn sorted<D: DeviceDescriptor>(descriptors: &[D]) -> Vec<&D> {
+    let mut sorted = descriptors.iter().collect::<Vec<_>>();
+    sorted.sort_by(|left, right| {
+        left.name()
+            .cmp(right.name())
+            .then_with(|| left.mac_address().cmp(&right.mac_address()))
+    });
+    sorted
+}
+
+/// A device that matched the selector. Devices that had to be connected to in order to find their
+/// model keep their connection.
+pub struct SelectedDevice<'a, T: DeviceRegistry> {
+    pub descriptor: &'a T::DescriptorType,
+    device: Option<Rc<T::DeviceType>>,
+}
+
+impl<T: DeviceRegistry> SelectedDevice<'_, T> {
+    pub async fn connect(self, registry: &T) -> Result<Rc<T::DeviceType>, Box<dyn Error>> {
+        if let Some(device) = self.device {
+            return Ok(device);
+        }
+        match registry.device(self.descriptor.mac_address()).await {
+            Ok(Some(device)) => Ok(device),
+            Err(err) => Err(format!("Error fetching device: {err}").into()),
+            Ok(None) => Err("No device found.".into()),
+        }
+    }
+}
+
+/// Returns the only device matching `selector`, or an error if there are none or several, rather
+/// than guessing which one was meant.
+pub async fn select_device<'a, T>(
+    registry: &T,
+    descriptors: &'a [T::DescriptorType],
+    selector: &DeviceSelector,
+) -> Result<SelectedDevice<'a, T>, Box<dyn Error>>
+where
+    T: DeviceRegistry,
+{
+    let name = selector.name.as_ref().map(|name| name.to_lowercase());
+    let candidates = sorted(descriptors)
+        .into_iter()
+        .enumerate()
+        .filter(|(index, descriptor)| {
+            selector.index.is_none_or(|selected| selected == *index)
+                && selector
+                    .mac_address
+                    .is_none_or(|mac_address| mac_address == descriptor.mac_address())
+                && name
+                    .as_ref()
+                    .is_none_or(|name| descriptor.name().to_lowercase().contains(name))
+        })
+        .map(|(_, descriptor)| descriptor);
+
+    let mut matches: Vec<SelectedDevice<T>> = Vec::new();
+    let mut connection_errors = Vec::new();
+    match selector.model {
+        // The model is only known once connected, so only connect to devices that match
+        // everything else
+        Some(selected_model) => {
+            for descriptor in candidates {
+                let device = match registry.device(descriptor.mac_address()).await {
+                    Ok(Some(device)) => device,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        tracing::warn!("failed to connect to {}: {err}", descriptor.mac_address());
+                        connection_errors.push(format!(
+                            "{} ({}): {err}",
+                            descriptor.name(),
+                            descriptor.mac_address(),
+                        ));
+                        continue;
+                    }
+                };
+                if model(device.as_ref()).await == Some(selected_model) {
+                    matches.push(SelectedDevice {
+                        descriptor,
+                        device: Some(device),
+                    });
+                }
+            }
+        }
+        None => matches.extend(candidates.map(|descriptor| SelectedDevice {
+            descriptor,
+            device: None,
+        })),
+    }
+
+    if matches.len() > 1 {
+        let devices = matches
+            .iter()
+            .map(|selected| {
+                format!(
+                    "{} ({})",
+                    selected.descriptor.name(),
+                    selected.descriptor.mac_address(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!(
+            "Multiple devices found: {devices}. Select one with --mac-address, --name, --model, \
+            or --index."
+        )
+        .into());
+    }
+    matches.pop().ok_or_else(|| {
+        if connection_errors.is_empty() {
+            "No device found.".into()
+        } else {
+            // Any of the devices that couldn't be connected to may have been the one asked for
+            format!(
+                "No device found. Failed to connect to {}.",
+                connection_errors.join(", ")
+            )
+            .into()
+        }
+    })
+}
+
+/// Determined from the serial number, so it is only available once connected
+pub async fn model(device: &impl Device) -> Option<DeviceModel> {
+    device
+        .state()
+        .await
+        .serial_number
+        .as_ref()
+        .and_then(DeviceModel::from_serial_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use macaddr::MacAddr6;
+    use openscq30_lib::{
+        api::device::{DeviceDescriptor, GenericDeviceDescriptor},
+        demo::device::DemoDeviceRegistry,
+        futures::TokioFutures,
+    };
+
+    use super::{select_device, DeviceSelector};
+
+    fn descriptors() -> Vec<GenericDeviceDescriptor> {
+        vec![
+            GenericDeviceDescriptor::new("Soundcore Life Q30", MacAddr6::new(0, 0, 0, 0, 0, 1)),
+            GenericDeviceDescriptor::new("Soundcore Life Q35", MacAddr6::new(0, 0, 0, 0, 0, 2)),
+        ]
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_ambiguous_selection_is_an_error() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let descriptors = descriptors();
+        let selector = DeviceSelector {
+            name: Some("life".to_string()),
+            ..Default::default()
+        };
+        let err = select_device(&registry, &descriptors, &selector)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Multiple devices found"), "{err}");
+
+        let err = select_device(&registry, &descriptors, &DeviceSelector::default())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Multiple devices found"), "{err}");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_index_follows_sorted_order() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let mut descriptors = descriptors();
+        descriptors.reverse();
+        let selector = DeviceSelector {
+            index: Some(0),
+            ..Default::default()
+        };
+        let selected = select_device(&registry, &descriptors, &selector)
+            .await
+            .unwrap();
+        assert_eq!(
+            MacAddr6::new(0, 0, 0, 0, 0, 1),
+            selected.descriptor.mac_address()
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_select_by_name_and_index() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let descriptors = descriptors();
+        let selector = DeviceSelector {
+            name: Some("q35".to_string()),
+            ..Default::default()
+        };
+        let selected = select_device(&registry, &descriptors, &selector)
+            .await
+            .unwrap();
+        assert_eq!(
+            MacAddr6::new(0, 0, 0, 0, 0, 2),
+            selected.descriptor.mac_address()
+        );
+
+        let selector = DeviceSelector {
+            index: Some(0),
+            ..Default::default()
+        };
+        let selected = select_device(&registry, &descriptors, &selector)
+            .await
+            .unwrap();
+        assert_eq!(
+            MacAddr6::new(0, 0, 0, 0, 0, 1),
+            selected.descriptor.mac_address()
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::{error::Error, path::Path};
+
+use openscq30_lib::{api::device::Device, devices::standard::structures::VolumeAdjustments};
+
+use crate::{
+    cli::EqualizerProfileCommand,
+    config::{self, Config, CustomEqualizerProfile, ExportedCustomEqualizerProfile},
+};
+
+/// Runs the commands that only touch the config file
+pub fn equalizer_profile(
+    command: &EqualizerProfileCommand,
+    config_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = Config::load(config_path)?;
+    match command {
+        EqualizerProfileCommand::List => {
+            let mut profiles = config.custom_profiles().iter().collect::<Vec<_>>();
+            profiles.sort_by_key(|(name, _)| *name);
+            for (name, profile) in profiles {
+                let volume_adjustments = profile
+                    .volume_adjustments()
+                    .iter()
+                    .map(|adjustment| format!("{adjustment:.1}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                println!("{name}: {volume_adjustments}");
+            }
+        }
+        EqualizerProfileCommand::Create {
+            profile,
+            volume_adjustments,
+        } => {
+            let volume_adjustments = volume_adjustments
+                .iter()
+                .map(|tenths| f64::from(*tenths) / 10.0)
+                .collect::<Vec<_>>();
+            config.set_custom_profile(
+                profile.to_owned(),
+                CustomEqualizerProfile::new(&volume_adjustments),
+            );
+            config.save(config_path)?;
+        }
+        EqualizerProfileCommand::Delete { profile } => {
+            config
+                .remove_custom_profile(profile)
+                .ok_or_else(|| not_found(profile))?;
+            config.save(config_path)?;
+        }
+        EqualizerProfileCommand::Export { profiles, output } => {
+            let mut exported = if profiles.is_empty() {
+                config
+                    .custom_profiles()
+                    .iter()
+                    .map(|(name, profile)| export(name, profile))
+                    .collect::<Vec<_>>()
+            } else {
+                profiles
+                    .iter()
+                    .map(|name| {
+                        config
+                            .custom_profiles()
+                            .get(name)
+                            .map(|profile| export(name, profile))
+                            .ok_or_else(|| not_found(name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            exported.sort_by(|a, b| a.name.cmp(&b.name));
+            config::write_export(output.as_deref(), &serde_json::to_string(&exported)?)?;
+        }
+        EqualizerProfileCommand::Import { file, overwrite } => {
+            let imported: Vec<ExportedCustomEqualizerProfile> =
+                serde_json::from_str(&config::read_import(file.as_deref())?)
+                    .map_err(|err| format!("Error parsing equalizer profiles: {err}"))?;
+            for profile in &imported {
+                VolumeAdjustments::new(profile.volume_adjustments.iter().copied()).map_err(
+                    |err| format!("Invalid volume adjustments for {}: {err}", profile.name),
+                )?;
+            }
+            config.insert_custom_profiles(
+                imported.into_iter().map(|profile| {
+                    (
+                        profile.name,
+                        CustomEqualizerProfile::new(&profile.volume_adjustments),
+                    )
+                }),
+                *overwrite,
+            );
+            config.save(config_path)?;
+        }
+        EqualizerProfileCommand::Activate { .. } => unreachable!("activating needs a device"),
+    }
+    Ok(())
+}
+
+/// Runs the commands that need a device, as determined by
+/// [`EqualizerProfileCommand::needs_device`]
+pub async fn equalizer_profile_with_device(
+    command: &EqualizerProfileCommand,
+    config_path: &Path,
+    device: &impl Device,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = Config::load(config_path)?;
+    match command {
+        EqualizerProfileCommand::Create { profile, .. } => {
+            let equalizer_configuration = device.state().await.equalizer_configuration;
+            config.set_custom_profile(
+                profile.to_owned(),
+                CustomEqualizerProfile::new(
+                    &equalizer_configuration.volume_adjustments().adjustments(),
+                ),
+            );
+            config.save(config_path)?;
+        }
+        EqualizerProfileCommand::Activate { profile: name } => {
+            let profile = config
+                .custom_profiles()
+                .get(name)
+                .ok_or_else(|| not_found(name))?;
+            let num_bands = device.state().await.device_features.num_equalizer_bands;
+            device
+                .set_equalizer_configuration(profile.equalizer_configuration(num_bands)?)
+                .await?;
+        }
+        _ => equalizer_profile(command, config_path)?,
+    }
+    Ok(())
+}
+
+fn export(name: &str, profile: &CustomEqualizerProfile) -> ExportedCustomEqualizerProfile {
+    ExportedCustomEqualizerProfile {
+        name: name.to_owned(),
+        volume_adjustments: profile.volume_adjustments().to_vec(),
+    }
+}
+
+fn not_found(name: &str) -> Box<dyn Error> {
+    format!("No equalizer profile named {name}.").into()
+}
@@ -1,36 +1,151 @@
+use std::{collections::BTreeMap, error::Error};
+
 use heck::AsKebabCase;
 use itertools::Itertools;
 use openscq30_lib::{api::device::Device, devices::standard::structures::VolumeAdjustments};
+use serde::Serialize;
 
-use crate::cli::GetCommand;
+use crate::cli::{GetCommand, OutputFormat};
 
-pub async fn get(get_command: GetCommand, device: &impl Device) {
+pub async fn get(
+    get_command: GetCommand,
+    format: Option<OutputFormat>,
+    device: &impl Device,
+) -> Result<(), Box<dyn Error>> {
     let device_state = device.state().await;
+    let format = format.unwrap_or(match get_command {
+        GetCommand::State => OutputFormat::Json,
+        _ => OutputFormat::Text,
+    });
     match get_command {
+        GetCommand::State => match format {
+            // There's no sensible plain text form for the whole state, so debug print it
+            OutputFormat::Text => println!("{device_state:#?}"),
+            _ => print_serialized(format, &device_state)?,
+        },
         GetCommand::AmbientSoundMode => {
-            if let Some(sound_modes) = device_state.sound_modes {
-                let cli_case = AsKebabCase(sound_modes.ambient_sound_mode.to_string());
-                println!("{}", cli_case)
-            }
+            let ambient_sound_mode = device_state
+                .sound_modes
+                .map(|sound_modes| sound_modes.ambient_sound_mode)
+                .or(device_state
+                    .sound_modes_type_two
+                    .map(|sound_modes| sound_modes.ambient_sound_mode));
+            print_value(format, "ambientSoundMode", ambient_sound_mode, |mode| {
+                AsKebabCase(mode.to_string()).to_string()
+            })?
         }
-        GetCommand::NoiseCancelingMode => {
-            if let Some(sound_modes) = device_state.sound_modes {
-                let cli_case = AsKebabCase(sound_modes.noise_canceling_mode.to_string());
-                println!("{}", cli_case)
+        GetCommand::NoiseCancelingMode => print_value(
+            format,
+            "noiseCancelingMode",
+            device_state
+                .sound_modes
+                .map(|sound_modes| sound_modes.noise_canceling_mode),
+            |mode| AsKebabCase(mode.to_string()).to_string(),
+        )?,
+        GetCommand::TransparencyMode => print_value(
+            format,
+            "transparencyMode",
+            device_state
+                .sound_modes
+                .map(|sound_modes| sound_modes.transparency_mode),
+            |mode| AsKebabCase(mode.to_string()).to_string(),
+        )?,
+        GetCommand::CustomNoiseCanceling => print_value(
+            format,
+            "customNoiseCanceling",
+            device_state
+                .sound_modes
+                .map(|sound_modes| sound_modes.custom_noise_canceling),
+            ToString::to_string,
+        )?,
+        GetCommand::NoiseCancelingModeTypeTwo => print_value(
+            format,
+            "noiseCancelingModeTypeTwo",
+            device_state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.noise_canceling_mode),
+            |mode| AsKebabCase(mode.to_string()).to_string(),
+        )?,
+        GetCommand::ManualNoiseCanceling => print_value(
+            format,
+            "manualNoiseCanceling",
+            device_state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.manual_noise_canceling),
+            |level| AsKebabCase(level.to_string()).to_string(),
+        )?,
+        GetCommand::AdaptiveSensitivity => print_value(
+            format,
+            "noiseCancelingAdaptiveSensitivityLevel",
+            device_state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.noise_canceling_adaptive_sensitivity_level),
+            |level| level.value().to_string(),
+        )?,
+        GetCommand::WindNoiseSuppression => print_value(
+            format,
+            "windNoiseSuppression",
+            device_state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.wind_noise_suppression),
+            ToString::to_string,
+        )?,
+        GetCommand::Equalizer => print_value(
+            format,
+            "volumeAdjustments",
+            Some(
+                device_state
+                    .equalizer_configuration
+                    .volume_adjustments()
+                    .to_owned(),
+            ),
+            format_volume_adjustments,
+        )?,
+    };
+    Ok(())
+}
+
+/// Prints nothing if the device doesn't have the value. In structured formats, the value is
+/// wrapped in an object so that every format, including toml, has a valid document to output.
+fn print_value<T: Serialize>(
+    format: OutputFormat,
+    key: &str,
+    value: Option<T>,
+    to_text: impl FnOnce(&T) -> String,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        OutputFormat::Text => {
+            if let Some(value) = value {
+                println!("{}", to_text(&value));
             }
         }
-        GetCommand::Equalizer => {
-            print_volume_adjustments(device_state.equalizer_configuration.volume_adjustments())
+        _ => {
+            let object = value
+                .into_iter()
+                .map(|value| (key, value))
+                .collect::<BTreeMap<_, _>>();
+            print_serialized(format, &object)?;
         }
+    }
+    Ok(())
+}
+
+fn print_serialized(format: OutputFormat, value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    let serialized = match format {
+        OutputFormat::Text => unreachable!("text is not a serialization format"),
+        OutputFormat::Json => serde_json::to_string_pretty(value)?,
+        OutputFormat::Yaml => serde_yaml_ng::to_string(value)?,
+        OutputFormat::Toml => toml::to_string_pretty(value)?,
     };
+    println!("{}", serialized.trim_end());
+    Ok(())
 }
 
-fn print_volume_adjustments(volume_adjustments: &VolumeAdjustments) {
-    let separated_volume_adjustments = volume_adjustments
+fn format_volume_adjustments(volume_adjustments: &VolumeAdjustments) -> String {
+    volume_adjustments
         .adjustments()
         .iter()
         .cloned()
         .map(|adjustment| format!("{:.0}", adjustment * 10.0))
-        .join(" ");
-    println!("{separated_volume_adjustments}");
+        .join(" ")
 }
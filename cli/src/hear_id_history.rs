@@ -0,0 +1,69 @@
+//! HearID profiles recorded by the library are kept in `hear_id_history.toml` next to the config
+//! file, the same place the GUI keeps them, so that profiles seen by either can be restored.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use openscq30_lib::{
+    api::device::{Device, DeviceRegistry},
+    hear_id_history::HearIdHistory,
+};
+
+use crate::cli::HearIdHistoryCommand;
+
+pub fn path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("hear_id_history.toml")
+}
+
+/// A missing file is treated as an empty history.
+pub fn load(path: &Path) -> Result<HearIdHistory, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents)
+            .map_err(|err| format!("Error parsing {}: {err}", path.display()).into()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(HearIdHistory::default()),
+        Err(err) => Err(format!("Error reading {}: {err}", path.display()).into()),
+    }
+}
+
+/// Saves the registry's history if it recorded anything since `loaded` was loaded into it.
+pub async fn save_if_changed(
+    registry: &impl DeviceRegistry,
+    loaded: &HearIdHistory,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let history = registry.hear_id_history().await?;
+    if &history != loaded {
+        openscq30_config::write_atomically(path, &toml::to_string(&history)?)
+            .map_err(|err| format!("Error writing {}: {err}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub async fn hear_id_history(
+    command: &HearIdHistoryCommand,
+    history: &HearIdHistory,
+    device: &impl Device,
+) -> Result<(), Box<dyn Error>> {
+    let serial_number = device
+        .state()
+        .await
+        .serial_number
+        .ok_or("The device didn't report its serial number.")?;
+    match command {
+        HearIdHistoryCommand::List => {
+            let entries = history.list(&serial_number);
+            println!("{}", serde_json::to_string_pretty(entries)?);
+        }
+        HearIdHistoryCommand::Diff { from, to } => {
+            let diff = history
+                .diff(&serial_number, *from, *to)
+                .ok_or("No profile recorded at that index.")?;
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+        }
+        HearIdHistoryCommand::Restore { entry } => history.restore(device, *entry).await?,
+    }
+    Ok(())
+}
@@ -1,12 +1,104 @@
-use openscq30_lib::api::device::DeviceDescriptor;
+use std::error::Error;
 
-pub fn list_devices(descriptors: &[impl DeviceDescriptor]) {
-    println!(
-        "{}",
-        descriptors
+use openscq30_lib::api::device::{DeviceDescriptor, DeviceRegistry};
+use serde::Serialize;
+
+use crate::{cli::ListDevicesFormat, device_selection};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListedDevice {
+    index: usize,
+    name: String,
+    mac_address: String,
+    /// Only looked up when asked for, and null if it couldn't be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<Option<String>>,
+}
+
+/// Only connects to the devices when `show_model` is set, since descriptors don't include the
+/// model.
+pub async fn list_devices<T>(
+    registry: &T,
+    descriptors: &[T::DescriptorType],
+    format: ListDevicesFormat,
+    show_model: bool,
+) -> Result<(), Box<dyn Error>>
+where
+    T: DeviceRegistry,
+{
+    let mut devices = Vec::with_capacity(descriptors.len());
+    for (index, descriptor) in device_selection::sorted(descriptors)
+        .into_iter()
+        .enumerate()
+    {
+        let model = if show_model {
+            let model = match registry.device(descriptor.mac_address()).await {
+                Ok(Some(device)) => device_selection::model(device.as_ref()).await,
+                Ok(None) => None,
+                Err(err) => {
+                    tracing::warn!("failed to connect to {}: {err}", descriptor.mac_address());
+                    None
+                }
+            };
+            Some(model.map(|model| model.to_string()))
+        } else {
+            None
+        };
+        devices.push(ListedDevice {
+            index,
+            name: descriptor.name().to_owned(),
+            mac_address: descriptor.mac_address().to_string(),
+            model,
+        });
+    }
+
+    match format {
+        ListDevicesFormat::Table => print_table(&devices, show_model),
+        ListDevicesFormat::Json => println!("{}", serde_json::to_string_pretty(&devices)?),
+    }
+    Ok(())
+}
+
+fn print_table(devices: &[ListedDevice], show_model: bool) {
+    let mut header = vec!["INDEX", "NAME", "MAC ADDRESS"];
+    if show_model {
+        header.push("MODEL");
+    }
+    let header = header
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    let rows = devices
+        .iter()
+        .map(|device| {
+            let mut row = vec![
+                device.index.to_string(),
+                device.name.to_owned(),
+                device.mac_address.to_owned(),
+            ];
+            if let Some(model) = &device.model {
+                row.push(model.to_owned().unwrap_or_else(|| "unknown".to_string()));
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    let widths =
+        std::iter::once(&header)
+            .chain(&rows)
+            .fold(vec![0; header.len()], |mut widths, row| {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+                widths
+            });
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row
             .iter()
-            .map(|descriptor| descriptor.mac_address().to_string())
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
             .collect::<Vec<_>>()
-            .join("\n")
-    );
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
 }
@@ -1,14 +1,32 @@
-use std::{error::Error, rc::Rc};
+use std::{error::Error, path::Path, rc::Rc};
 
 use clap::{CommandFactory, Parser};
 use clap_complete::Shell;
-use cli::{Cli, Command};
-use openscq30_lib::api::device::{DeviceDescriptor, DeviceRegistry};
+use cli::{Cli, Command, SetCommand};
+use config::Config;
+use device_selection::{DeviceSelector, SelectedDevice};
+use openscq30_lib::{
+    api::{
+        backend::{Backend, BackendDeviceRegistry},
+        device::{Device, DeviceRegistry},
+    },
+    futures::TokioFutures,
+};
 
+mod apply;
+mod bug_report;
 mod cli;
+mod compare_equalizer;
+mod config;
+mod device_selection;
+mod equalizer_profile;
 mod get;
+mod hear_id_history;
 mod list_devices;
+mod preset;
 mod set;
+mod tui;
+mod watch;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Cli::parse();
@@ -24,6 +42,13 @@ fn main() -> Result<(), Box<dyn Error>> {
         );
         return Ok(());
     }
+    // Doesn't need a device, so it should work even without bluetooth
+    if let Command::EqualizerProfile(command) = &args.command {
+        if !command.needs_device() {
+            let config_path = config::path(args.config.as_deref())?;
+            return equalizer_profile::equalizer_profile(command, &config_path);
+        }
+    }
 
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -39,49 +64,158 @@ fn main() -> Result<(), Box<dyn Error>> {
             .pretty()
             .init();
 
+        // A backend chosen explicitly, whether by flag or by environment variable, can't be
+        // honored by a daemon that has its own
+        #[cfg(unix)]
+        if !args.no_daemon && args.backend.is_none() && std::env::var_os(Backend::ENV_VAR).is_none()
+        {
+            let socket_path = openscq30_daemon::default_socket_path();
+            match openscq30_daemon::client::DaemonDeviceRegistry::connect(&socket_path).await {
+                Ok(registry) => {
+                    tracing::debug!("using daemon at {}", socket_path.display());
+                    return run(&registry, args).await;
+                }
+                Err(err) => tracing::debug!("not using daemon: {err}"),
+            }
+        }
+
+        let backend = match args.backend {
+            Some(backend) => backend.into(),
+            None => Backend::from_env()?,
+        };
         let registry =
-            openscq30_lib::api::new_soundcore_device_registry(runtime.handle().to_owned())
+            BackendDeviceRegistry::<TokioFutures>::new(backend, Some(runtime.handle().to_owned()))
                 .await
                 .unwrap_or_else(|err| panic!("failed to initialize device registry: {err}"));
+        run(&registry, args).await
+    })
+}
 
-        let descriptors = registry.device_descriptors().await?;
-        let selected_descriptor = args
-            .mac_address
-            .map(|mac_address| {
-                descriptors
-                    .iter()
-                    .find(|descriptor| descriptor.mac_address() == mac_address)
-            })
-            .or_else(|| Some(descriptors.first()))
-            .flatten();
+async fn run<T>(registry: &T, args: Cli) -> Result<(), Box<dyn Error>>
+where
+    T: DeviceRegistry,
+{
+    let descriptors = registry.device_descriptors().await?;
+    if let Command::ListDevices { format, show_model } = args.command {
+        return list_devices::list_devices(registry, &descriptors, format, show_model).await;
+    }
+    let selector = DeviceSelector {
+        mac_address: args.mac_address,
+        name: args.name,
+        model: args.model,
+        index: args.index,
+    };
+    let selected = device_selection::select_device(registry, &descriptors, &selector).await?;
 
-        match (args.command, selected_descriptor) {
-            (Command::ListDevices, _) => list_devices::list_devices(&descriptors),
-            (Command::Set(set_command), Some(descriptor)) => {
-                let device = get_device_or_err(&registry, descriptor).await?;
-                set::set(set_command, device.as_ref()).await?;
-            }
-            (Command::Get(get_command), Some(descriptor)) => {
-                let device = get_device_or_err(&registry, descriptor).await?;
-                get::get(get_command, device.as_ref()).await;
-            }
-            (Command::Completions { .. }, Some(_)) => unreachable!(),
-            (_, None) => eprintln!("No device found."),
-        };
-        Ok(())
-    })
+    let hear_id_history_path = hear_id_history::path(&config::path(args.config.as_deref())?);
+    let loaded_hear_id_history = hear_id_history::load(&hear_id_history_path)?;
+    registry
+        .load_hear_id_history(loaded_hear_id_history.to_owned())
+        .await?;
+    // Profiles recorded before a failure are still worth keeping
+    let result = run_command(registry, selected, args.command, args.config.as_deref()).await;
+    hear_id_history::save_if_changed(registry, &loaded_hear_id_history, &hear_id_history_path)
+        .await?;
+    result
 }
 
-async fn get_device_or_err<T>(
+async fn run_command<T>(
     registry: &T,
-    descriptor: &T::DescriptorType,
-) -> Result<Rc<T::DeviceType>, String>
+    selected: SelectedDevice<'_, T>,
+    command: Command,
+    config: Option<&Path>,
+) -> Result<(), Box<dyn Error>>
 where
     T: DeviceRegistry,
 {
-    match registry.device(descriptor.mac_address()).await {
-        Ok(Some(device)) => Ok(device),
-        Err(err) => Err(format!("Error fetching device: {err}")),
-        Ok(None) => Err("No device found.".to_string()),
+    match command {
+        Command::Set(set_command) => {
+            let device = connect(selected, registry, config).await?;
+            let remembered_dynamic_range_compression = match set_command {
+                SetCommand::DynamicRangeCompression { is_enabled } => Some(is_enabled),
+                _ => None,
+            };
+            set::set(set_command, device.as_ref()).await?;
+            if let Some(is_enabled) = remembered_dynamic_range_compression {
+                let config_path = config::path(config)?;
+                let mut config = Config::load(&config_path)?;
+                config.set_dynamic_range_compression(device.service_uuid(), is_enabled);
+                config.save(&config_path)?;
+            }
+        }
+        Command::Get { format, command } => {
+            let device = connect(selected, registry, config).await?;
+            get::get(command, format, device.as_ref()).await?;
+        }
+        Command::CompareEqualizer { a, b, blind } => {
+            let device = connect(selected, registry, config).await?;
+            compare_equalizer::compare_equalizer(device, &a, &b, blind).await?;
+        }
+        Command::Apply { file, dry_run } => {
+            let device = connect(selected, registry, config).await?;
+            apply::apply(&file, dry_run, device.as_ref()).await?;
+        }
+        Command::Watch { format } => {
+            let device = connect(selected, registry, config).await?;
+            watch::watch(format, device.as_ref()).await?;
+        }
+        Command::Tui => {
+            let device = connect(selected, registry, config).await?;
+            tui::tui(device.as_ref()).await?;
+        }
+        Command::EqualizerProfile(command) => {
+            let config_path = config::path(config)?;
+            let device = connect(selected, registry, config).await?;
+            equalizer_profile::equalizer_profile_with_device(
+                &command,
+                &config_path,
+                device.as_ref(),
+            )
+            .await?;
+        }
+        Command::Preset(command) => {
+            let config_path = config::path(config)?;
+            let device = connect(selected, registry, config).await?;
+            preset::preset(&command, &config_path, device.as_ref()).await?;
+        }
+        Command::HearIdHistory(command) => {
+            let device = connect(selected, registry, config).await?;
+            let history = registry.hear_id_history().await?;
+            hear_id_history::hear_id_history(&command, &history, device.as_ref()).await?;
+        }
+        Command::BugReport { output } => {
+            bug_report::bug_report(registry, selected.descriptor, output.as_deref()).await?;
+        }
+        Command::ListDevices { .. } | Command::Completions { .. } => unreachable!(),
+    };
+    Ok(())
+}
+
+/// Devices don't report whether dynamic range compression is enabled, so the last choice made with
+/// `set dynamic-range-compression` is restored from the config file after connecting. Also warns
+/// when the device's state could only be partially parsed.
+async fn connect<T: DeviceRegistry>(
+    selected: SelectedDevice<'_, T>,
+    registry: &T,
+    config_path: Option<&Path>,
+) -> Result<Rc<T::DeviceType>, Box<dyn Error>> {
+    let device = selected.connect(registry).await?;
+    if let Some(degraded_mode) = device.degraded_mode().await {
+        eprintln!(
+            "Warning: the {} sent {} bytes that aren't understood yet, so some settings may be \
+            missing.",
+            degraded_mode.model,
+            degraded_mode.trailing_bytes.len(),
+        );
+    }
+    let config = Config::load(&config::path(config_path)?)?;
+    if let Some(is_enabled) = config.dynamic_range_compression(device.service_uuid()) {
+        let state = device.state().await;
+        if state.supports_dynamic_range_compression()
+            && state.is_dynamic_range_compression_enabled != is_enabled
+        {
+            device.set_dynamic_range_compression(is_enabled).await?;
+        }
     }
+    Ok(device)
 }
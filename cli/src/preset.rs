@@ -0,0 +1,111 @@
+use std::{collections::HashMap, error::Error, path::Path, sync::Arc};
+
+use openscq30_lib::api::device::Device;
+
+use crate::{
+    cli::PresetCommand,
+    config::{self, Config, PresetOrCustomEqualizerProfile, QuickPreset},
+};
+
+pub async fn preset(
+    command: &PresetCommand,
+    config_path: &Path,
+    device: &impl Device,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = Config::load(config_path)?;
+    let service_uuid = device.service_uuid();
+    let quick_presets = config.quick_presets(service_uuid);
+    match command {
+        PresetCommand::List => {
+            let mut names = quick_presets.keys().collect::<Vec<_>>();
+            names.sort();
+            for name in names {
+                println!("{name}");
+            }
+        }
+        PresetCommand::Create {
+            preset,
+            ambient_sound_mode,
+            transparency_mode,
+            noise_canceling_mode,
+            custom_noise_canceling,
+            equalizer_preset,
+            equalizer_profile,
+        } => {
+            if let Some(profile) = equalizer_profile {
+                if !config.custom_profiles().contains_key(profile) {
+                    return Err(format!("No equalizer profile named {profile}.").into());
+                }
+            }
+            let quick_preset = QuickPreset {
+                ambient_sound_mode: ambient_sound_mode.map(Into::into),
+                transparency_mode: transparency_mode.map(Into::into),
+                noise_canceling_mode: noise_canceling_mode.map(Into::into),
+                custom_noise_canceling: *custom_noise_canceling,
+                equalizer_profile: equalizer_preset
+                    .map(|profile| PresetOrCustomEqualizerProfile::Preset(profile.into()))
+                    .or_else(|| {
+                        equalizer_profile.as_deref().map(|profile| {
+                            PresetOrCustomEqualizerProfile::Custom(Arc::from(profile))
+                        })
+                    }),
+            };
+            config.set_quick_preset(service_uuid, preset.to_owned(), quick_preset);
+            config.save(config_path)?;
+        }
+        PresetCommand::Delete { preset } => {
+            config
+                .remove_quick_preset(service_uuid, preset)
+                .ok_or_else(|| not_found(preset))?;
+            config.save(config_path)?;
+        }
+        PresetCommand::Export { presets, output } => {
+            let exported = if presets.is_empty() {
+                quick_presets.iter().collect::<HashMap<_, _>>()
+            } else {
+                presets
+                    .iter()
+                    .map(|name| {
+                        quick_presets
+                            .get_key_value(name)
+                            .ok_or_else(|| not_found(name))
+                    })
+                    .collect::<Result<HashMap<_, _>, _>>()?
+            };
+            config::write_export(output.as_deref(), &serde_json::to_string(&exported)?)?;
+        }
+        PresetCommand::Import { file, overwrite } => {
+            let imported: HashMap<String, QuickPreset> =
+                serde_json::from_str(&config::read_import(file.as_deref())?)
+                    .map_err(|err| format!("Error parsing quick presets: {err}"))?;
+            if !overwrite {
+                let mut existing = imported
+                    .keys()
+                    .filter(|name| quick_presets.contains_key(*name))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !existing.is_empty() {
+                    existing.sort();
+                    return Err(format!(
+                        "Quick presets already exist: {}. Use --overwrite to replace them.",
+                        existing.join(", "),
+                    )
+                    .into());
+                }
+            }
+            for (name, quick_preset) in imported {
+                config.set_quick_preset(service_uuid, name, quick_preset);
+            }
+            config.save(config_path)?;
+        }
+        PresetCommand::Activate { preset } => {
+            let quick_preset = quick_presets.get(preset).ok_or_else(|| not_found(preset))?;
+            config::activate_quick_preset(device, &config, quick_preset).await?;
+        }
+    }
+    Ok(())
+}
+
+fn not_found(name: &str) -> Box<dyn Error> {
+    format!("No quick preset named {name}.").into()
+}
@@ -1,9 +1,12 @@
 use openscq30_lib::{
     api::device::Device,
-    devices::standard::structures::{EqualizerConfiguration, SoundModes, VolumeAdjustments},
+    devices::standard::structures::{
+        AmbientSoundModeCycle, EqualizerConfiguration, HearId,
+        NoiseCancelingAdaptiveSensitivityLevel, SoundModes, SoundModesTypeTwo, VolumeAdjustments,
+    },
 };
 
-use crate::cli::SetCommand;
+use crate::cli::{AmbientSoundMode, ButtonGesture, ButtonSide, SetCommand};
 
 pub async fn set(set_command: SetCommand, device: &impl Device) -> openscq30_lib::Result<()> {
     let device_state = device.state().await;
@@ -16,6 +19,13 @@ pub async fn set(set_command: SetCommand, device: &impl Device) -> openscq30_lib
                         ..sound_modes
                     })
                     .await?
+            } else if let Some(sound_modes) = device_state.sound_modes_type_two {
+                device
+                    .set_sound_modes_type_two(SoundModesTypeTwo {
+                        ambient_sound_mode: mode.into(),
+                        ..sound_modes
+                    })
+                    .await?
             }
         }
         SetCommand::NoiseCancelingMode { mode } => {
@@ -28,25 +38,201 @@ pub async fn set(set_command: SetCommand, device: &impl Device) -> openscq30_lib
                     .await?
             }
         }
-        SetCommand::Equalizer { volume_adjustments } => {
-            let adjustment_array: [i16; 8] =
-                volume_adjustments
-                    .try_into()
-                    .unwrap_or_else(|values: Vec<_>| {
-                        panic!(
-                            "error converting vec of volume adjustments to array: expected len 8, got {}",
-                            values.len(),
-                        )
-                    });
-            let float_adjustments = adjustment_array.map(|adjustment| (adjustment as f64) / 10.0);
+        SetCommand::TransparencyMode { mode } => device.set_transparency_mode(mode.into()).await?,
+        SetCommand::CustomNoiseCanceling { level } => {
+            device.set_custom_noise_canceling(level).await?
+        }
+        SetCommand::NoiseCancelingModeTypeTwo { mode } => {
+            device
+                .set_noise_canceling_mode_type_two(mode.into())
+                .await?
+        }
+        SetCommand::ManualNoiseCanceling { level } => {
+            device.set_manual_noise_canceling(level.into()).await?
+        }
+        SetCommand::AdaptiveSensitivity { level } => {
+            device
+                .set_noise_canceling_adaptive_sensitivity_level(
+                    NoiseCancelingAdaptiveSensitivityLevel::new(level)?,
+                )
+                .await?
+        }
+        SetCommand::WindNoiseSuppression { is_enabled } => {
+            device.set_wind_noise_suppression(is_enabled).await?
+        }
+        SetCommand::Equalizer {
+            volume_adjustments,
+            normalize,
+        } => {
+            let mut volume_adjustments = volume_adjustments_from_tenths(
+                &volume_adjustments,
+                device_state.device_features.num_equalizer_bands,
+            )?;
+            if normalize {
+                volume_adjustments = volume_adjustments.normalize();
+            }
+            let analysis = volume_adjustments.analyze();
+            if analysis.is_clipping_likely() {
+                eprintln!(
+                    "Warning: peak gain of {:+.1} dB may cause clipping. Use --normalize to avoid it.",
+                    analysis.peak_gain,
+                );
+            }
 
             device
                 .set_equalizer_configuration(EqualizerConfiguration::new_custom_profile(
-                    VolumeAdjustments::new(float_adjustments)
-                        .expect("we already checked for valid length"),
+                    volume_adjustments,
                 ))
                 .await?
         }
+        SetCommand::EqualizerPreset { profile } => {
+            device
+                .set_equalizer_configuration(EqualizerConfiguration::new_from_preset_profile(
+                    profile.into(),
+                ))
+                .await?
+        }
+        SetCommand::DynamicRangeCompression { is_enabled } => {
+            device.set_dynamic_range_compression(is_enabled).await?
+        }
+        SetCommand::AmbientSoundModeCycle { modes } => {
+            device
+                .set_ambient_sound_mode_cycle(AmbientSoundModeCycle {
+                    noise_canceling_mode: modes.contains(&AmbientSoundMode::NoiseCanceling),
+                    transparency_mode: modes.contains(&AmbientSoundMode::Transparency),
+                    normal_mode: modes.contains(&AmbientSoundMode::Normal),
+                })
+                .await?
+        }
+        SetCommand::Button {
+            side,
+            gesture,
+            action,
+            disconnected_action,
+            enabled,
+        } => {
+            let mut button_model = device_state.custom_button_model.ok_or(
+                openscq30_lib::Error::FeatureNotSupported {
+                    feature_name: "custom button model",
+                },
+            )?;
+            match gesture {
+                ButtonGesture::SingleClick => {
+                    if disconnected_action.is_some() {
+                        return Err(openscq30_lib::Error::InvalidValue {
+                            name: "disconnected action",
+                            value: "single click has only one action".to_string(),
+                        });
+                    }
+                    let button = match side {
+                        ButtonSide::Left => &mut button_model.left_single_click,
+                        ButtonSide::Right => &mut button_model.right_single_click,
+                    };
+                    if let Some(action) = action {
+                        button.action = action.into();
+                    }
+                    if let Some(is_enabled) = enabled {
+                        button.is_enabled = is_enabled;
+                    }
+                }
+                ButtonGesture::DoubleClick | ButtonGesture::LongPress => {
+                    let button = match (side, gesture) {
+                        (ButtonSide::Left, ButtonGesture::DoubleClick) => {
+                            &mut button_model.left_double_click
+                        }
+                        (ButtonSide::Left, _) => &mut button_model.left_long_press,
+                        (ButtonSide::Right, ButtonGesture::DoubleClick) => {
+                            &mut button_model.right_double_click
+                        }
+                        (ButtonSide::Right, _) => &mut button_model.right_long_press,
+                    };
+                    if let Some(action) = action {
+                        button.tws_connected_action = action.into();
+                    }
+                    if let Some(action) = disconnected_action {
+                        button.tws_disconnected_action = action.into();
+                    }
+                    if let Some(is_enabled) = enabled {
+                        button.is_enabled = is_enabled;
+                    }
+                }
+            }
+            device.set_custom_button_model(button_model).await?
+        }
+        SetCommand::HearId {
+            enabled,
+            left,
+            right,
+            hear_id_type,
+        } => {
+            let num_bands = device_state.device_features.num_equalizer_bands;
+            let mut hear_id =
+                device_state
+                    .hear_id
+                    .ok_or(openscq30_lib::Error::FeatureNotSupported {
+                        feature_name: "hear id",
+                    })?;
+            let (is_enabled, volume_adjustments) = match &mut hear_id {
+                HearId::Basic(hear_id) => {
+                    (&mut hear_id.is_enabled, &mut hear_id.volume_adjustments)
+                }
+                HearId::Custom(hear_id) => {
+                    (&mut hear_id.is_enabled, &mut hear_id.volume_adjustments)
+                }
+            };
+            if let Some(enabled) = enabled {
+                *is_enabled = enabled;
+            }
+            if let Some(left) = left {
+                volume_adjustments.left = volume_adjustments_from_tenths(&left, num_bands)?;
+            }
+            if let Some(right) = right {
+                volume_adjustments.right = volume_adjustments_from_tenths(&right, num_bands)?;
+            }
+            if let Some(hear_id_type) = hear_id_type {
+                match &mut hear_id {
+                    HearId::Custom(hear_id) => hear_id.hear_id_type = hear_id_type.into(),
+                    HearId::Basic(_) => {
+                        return Err(openscq30_lib::Error::FeatureNotSupported {
+                            feature_name: "hear id type",
+                        })
+                    }
+                }
+            }
+            device.set_hear_id(hear_id).await?
+        }
+        SetCommand::GenderAndAgeRange { gender, age_range } => {
+            device
+                .set_gender_and_age_range(
+                    gender
+                        .map(Into::into)
+                        .or(device_state.gender)
+                        .ok_or(openscq30_lib::Error::MissingData { name: "gender" })?,
+                    age_range
+                        .map(Into::into)
+                        .or(device_state.age_range)
+                        .ok_or(openscq30_lib::Error::MissingData { name: "age range" })?,
+                )
+                .await?
+        }
     };
     Ok(())
 }
+
+pub fn volume_adjustments_from_tenths(
+    tenths: &[i16],
+    num_bands: usize,
+) -> openscq30_lib::Result<VolumeAdjustments> {
+    if tenths.len() != num_bands {
+        return Err(openscq30_lib::Error::InvalidValue {
+            name: "number of volume adjustments",
+            value: format!("the device has {num_bands} bands, got {}", tenths.len()),
+        });
+    }
+    VolumeAdjustments::new(tenths.iter().map(|tenths| f64::from(*tenths) / 10.0)).map_err(|err| {
+        openscq30_lib::Error::InvalidValue {
+            name: "volume adjustments",
+            value: err.to_string(),
+        }
+    })
+}
@@ -0,0 +1,197 @@
+use std::{
+    error::Error,
+    io::{self, IsTerminal},
+};
+
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::StreamExt;
+use openscq30_lib::api::device::Device;
+use ratatui::{backend::Backend, Terminal};
+
+use self::app::App;
+
+mod app;
+mod ui;
+
+/// Shows the device's state and lets it be changed with the keyboard until the user quits.
+pub async fn tui(device: &impl Device) -> Result<(), Box<dyn Error>> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err("tui must be run in an interactive terminal".into());
+    }
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, device).await;
+    ratatui::try_restore()?;
+    result
+}
+
+async fn run(
+    terminal: &mut Terminal<impl Backend>,
+    device: &impl Device,
+) -> Result<(), Box<dyn Error>> {
+    let mut state_receiver = device.subscribe_to_state_updates().await;
+    let mut connection_status_receiver = device.connection_status();
+    let mut app = App::new(
+        device.name().await?,
+        state_receiver.borrow_and_update().to_owned(),
+        *connection_status_receiver.borrow_and_update(),
+    );
+    let mut events = EventStream::new();
+
+    while !app.should_quit {
+        terminal.draw(|frame| ui::render(&app, frame))?;
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    app.handle_key(key, device).await;
+                }
+                Some(Ok(_)) => (),
+                Some(Err(err)) => return Err(err.into()),
+                None => break,
+            },
+            result = state_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                app.state = state_receiver.borrow_and_update().to_owned();
+            }
+            result = connection_status_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                app.connection_status = *connection_status_receiver.borrow_and_update();
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent};
+    use macaddr::MacAddr6;
+    use openscq30_lib::{
+        api::device::{Device, DeviceRegistry},
+        demo::device::{DemoDevice, DemoDeviceRegistry},
+        devices::standard::structures::{AmbientSoundMode, PresetEqualizerProfile},
+        futures::TokioFutures,
+    };
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::{app::App, ui};
+
+    async fn demo_app() -> (std::rc::Rc<DemoDevice<TokioFutures>>, App) {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let device = registry.device(MacAddr6::nil()).await.unwrap().unwrap();
+        let app = App::new(
+            device.name().await.unwrap(),
+            device.state().await,
+            *device.connection_status().borrow(),
+        );
+        (device, app)
+    }
+
+    async fn press(app: &mut App, device: &impl Device, codes: &[KeyCode]) {
+        for code in codes {
+            app.handle_key(KeyEvent::from(*code), device).await;
+        }
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| ui::render(app, frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_ambient_sound_mode_shortcut() {
+        let (device, mut app) = demo_app().await;
+        press(&mut app, device.as_ref(), &[KeyCode::Char('a')]).await;
+        let sound_modes = device.state().await.sound_modes.unwrap();
+        assert_eq!(
+            AmbientSoundMode::Transparency,
+            sound_modes.ambient_sound_mode
+        );
+        assert!(render(&app).contains("Ambient sound mode: < Transparency >"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_equalizer_band_stays_within_limits() {
+        let (device, mut app) = demo_app().await;
+        press(
+            &mut app,
+            device.as_ref(),
+            &[KeyCode::Tab, KeyCode::Right, KeyCode::Up, KeyCode::Up],
+        )
+        .await;
+        let adjustments = device
+            .state()
+            .await
+            .equalizer_configuration
+            .volume_adjustments()
+            .adjustments();
+        assert_eq!(0.2, adjustments[1]);
+        assert_eq!(None, app.error);
+        assert!(render(&app).contains("Equalizer (Custom)"));
+
+        press(&mut app, device.as_ref(), &[KeyCode::PageDown; 30]).await;
+        let adjustments = device
+            .state()
+            .await
+            .equalizer_configuration
+            .volume_adjustments()
+            .adjustments();
+        assert_eq!(-12.0, adjustments[1]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_preset_selection() {
+        let (device, mut app) = demo_app().await;
+        press(
+            &mut app,
+            device.as_ref(),
+            &[KeyCode::Tab, KeyCode::Char(']')],
+        )
+        .await;
+        let preset = device
+            .state()
+            .await
+            .equalizer_configuration
+            .preset_profile();
+        assert_eq!(Some(PresetEqualizerProfile::Acoustic), preset);
+        assert!(render(&app).contains("Equalizer (Acoustic)"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_button_editing() {
+        let (device, mut app) = demo_app().await;
+        let before = device.state().await.custom_button_model.unwrap();
+        press(
+            &mut app,
+            device.as_ref(),
+            &[
+                KeyCode::BackTab,
+                KeyCode::Down,
+                KeyCode::Right,
+                KeyCode::Char(' '),
+            ],
+        )
+        .await;
+        let after = device.state().await.custom_button_model.unwrap();
+        assert_ne!(
+            before.left_double_click.tws_connected_action,
+            after.left_double_click.tws_connected_action,
+        );
+        assert_eq!(
+            !before.left_double_click.is_enabled,
+            after.left_double_click.is_enabled,
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use openscq30_lib::{
+    api::{connection::ConnectionStatus, device::Device},
+    device_profile::NoiseCancelingModeType,
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AmbientSoundMode, ButtonAction, CustomButtonModel, EqualizerConfiguration,
+            NoiseCancelingMode, NoiseCancelingModeTypeTwo, PresetEqualizerProfile, SoundModes,
+            SoundModesTypeTwo, VolumeAdjustments,
+        },
+    },
+};
+use strum::IntoEnumIterator;
+
+use crate::cli::{ButtonGesture, ButtonSide};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Focus {
+    SoundModes,
+    Equalizer,
+    Buttons,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::SoundModes => Focus::Equalizer,
+            Focus::Equalizer => Focus::Buttons,
+            Focus::Buttons => Focus::SoundModes,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Focus::SoundModes => Focus::Buttons,
+            Focus::Equalizer => Focus::SoundModes,
+            Focus::Buttons => Focus::Equalizer,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundModeRow {
+    AmbientSoundMode,
+    NoiseCancelingMode,
+}
+
+pub const SOUND_MODE_ROWS: [SoundModeRow; 2] = [
+    SoundModeRow::AmbientSoundMode,
+    SoundModeRow::NoiseCancelingMode,
+];
+
+/// Everything shown by the tui. Changes made with the keyboard are sent to the device right away,
+/// and the displayed state is only updated once the device accepts them.
+pub struct App {
+    pub name: String,
+    pub state: DeviceState,
+    pub connection_status: ConnectionStatus,
+    pub focus: Focus,
+    pub sound_mode_row: usize,
+    pub selected_band: usize,
+    pub selected_button: usize,
+    /// The result of the last failed change, cleared by the next key press
+    pub error: Option<String>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new(name: String, state: DeviceState, connection_status: ConnectionStatus) -> Self {
+        Self {
+            name,
+            state,
+            connection_status,
+            focus: Focus::SoundModes,
+            sound_mode_row: 0,
+            selected_band: 0,
+            selected_button: 0,
+            error: None,
+            should_quit: false,
+        }
+    }
+
+    pub async fn handle_key(&mut self, key: KeyEvent, device: &impl Device) {
+        self.error = None;
+        if let Err(err) = self.handle_key_inner(key, device).await {
+            self.error = Some(err.to_string());
+        }
+    }
+
+    async fn handle_key_inner(
+        &mut self,
+        key: KeyEvent,
+        device: &impl Device,
+    ) -> openscq30_lib::Result<()> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Char('a') => self.cycle_ambient_sound_mode(device, 1).await?,
+            KeyCode::Char('n') => self.cycle_noise_canceling_mode(device, 1).await?,
+            _ => match self.focus {
+                Focus::SoundModes => self.handle_sound_modes_key(key, device).await?,
+                Focus::Equalizer => self.handle_equalizer_key(key, device).await?,
+                Focus::Buttons => self.handle_buttons_key(key, device).await?,
+            },
+        }
+        Ok(())
+    }
+
+    async fn handle_sound_modes_key(
+        &mut self,
+        key: KeyEvent,
+        device: &impl Device,
+    ) -> openscq30_lib::Result<()> {
+        let offset = match key.code {
+            KeyCode::Up => {
+                self.sound_mode_row = self.sound_mode_row.saturating_sub(1);
+                return Ok(());
+            }
+            KeyCode::Down => {
+                self.sound_mode_row = (self.sound_mode_row + 1).min(SOUND_MODE_ROWS.len() - 1);
+                return Ok(());
+            }
+            KeyCode::Left => -1,
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => 1,
+            _ => return Ok(()),
+        };
+        match SOUND_MODE_ROWS[self.sound_mode_row] {
+            SoundModeRow::AmbientSoundMode => self.cycle_ambient_sound_mode(device, offset).await,
+            SoundModeRow::NoiseCancelingMode => {
+                self.cycle_noise_canceling_mode(device, offset).await
+            }
+        }
+    }
+
+    async fn handle_equalizer_key(
+        &mut self,
+        key: KeyEvent,
+        device: &impl Device,
+    ) -> openscq30_lib::Result<()> {
+        let num_bands = self.volume_adjustments().adjustments().len();
+        match key.code {
+            KeyCode::Left => self.selected_band = self.selected_band.saturating_sub(1),
+            KeyCode::Right => self.selected_band = (self.selected_band + 1).min(num_bands - 1),
+            KeyCode::Up => self.adjust_selected_band(device, 1).await?,
+            KeyCode::Down => self.adjust_selected_band(device, -1).await?,
+            KeyCode::PageUp => self.adjust_selected_band(device, steps(1.0)).await?,
+            KeyCode::PageDown => self.adjust_selected_band(device, -steps(1.0)).await?,
+            KeyCode::Char('0') => {
+                let current = steps(self.volume_adjustments().adjustments()[self.selected_band]);
+                self.adjust_selected_band(device, -current).await?
+            }
+            KeyCode::Char(']') => self.cycle_preset(device, 1).await?,
+            KeyCode::Char('[') => self.cycle_preset(device, -1).await?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    async fn handle_buttons_key(
+        &mut self,
+        key: KeyEvent,
+        device: &impl Device,
+    ) -> openscq30_lib::Result<()> {
+        let Some(mut button_model) = self.state.custom_button_model else {
+            return Ok(());
+        };
+        let buttons = self.buttons();
+        let Some(&(side, gesture)) = buttons.get(self.selected_button) else {
+            return Ok(());
+        };
+        let actions = self.supported_button_actions();
+        match key.code {
+            KeyCode::Up => self.selected_button = self.selected_button.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected_button = (self.selected_button + 1).min(buttons.len() - 1)
+            }
+            KeyCode::Left | KeyCode::Right => {
+                let offset = if key.code == KeyCode::Left { -1 } else { 1 };
+                let button = button_mut(&mut button_model, side, gesture);
+                *button.action = cycle(&actions, *button.action, offset);
+                self.set_custom_button_model(device, button_model).await?;
+            }
+            KeyCode::Char('d') => {
+                let button = button_mut(&mut button_model, side, gesture);
+                if let Some(disconnected_action) = button.disconnected_action {
+                    *disconnected_action = cycle(&actions, *disconnected_action, 1);
+                    self.set_custom_button_model(device, button_model).await?;
+                }
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let button = button_mut(&mut button_model, side, gesture);
+                *button.is_enabled = !*button.is_enabled;
+                self.set_custom_button_model(device, button_model).await?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    pub fn ambient_sound_mode(&self) -> Option<AmbientSoundMode> {
+        self.state
+            .sound_modes
+            .map(|sound_modes| sound_modes.ambient_sound_mode)
+            .or(self
+                .state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.ambient_sound_mode))
+    }
+
+    /// The noise canceling mode formatted for display, since the two types of sound modes have
+    /// different noise canceling modes
+    pub fn noise_canceling_mode(&self) -> Option<String> {
+        if let Some(sound_modes) = self.state.sound_modes {
+            self.noise_canceling_modes()
+                .contains(&sound_modes.noise_canceling_mode)
+                .then(|| sound_modes.noise_canceling_mode.to_string())
+        } else {
+            self.state
+                .sound_modes_type_two
+                .map(|sound_modes| sound_modes.noise_canceling_mode.to_string())
+        }
+    }
+
+    pub fn volume_adjustments(&self) -> &VolumeAdjustments {
+        self.state.equalizer_configuration.volume_adjustments()
+    }
+
+    /// Gestures that the device lets you change, in display order
+    pub fn buttons(&self) -> Vec<(ButtonSide, ButtonGesture)> {
+        let capabilities = self.state.device_features.button_capabilities;
+        [ButtonSide::Left, ButtonSide::Right]
+            .into_iter()
+            .flat_map(|side| {
+                [
+                    (ButtonGesture::SingleClick, capabilities.has_single_click),
+                    (ButtonGesture::DoubleClick, capabilities.has_double_click),
+                    (ButtonGesture::LongPress, capabilities.has_long_press),
+                ]
+                .into_iter()
+                .filter(|(_, is_supported)| *is_supported)
+                .map(move |(gesture, _)| (side, gesture))
+            })
+            .collect()
+    }
+
+    fn supported_button_actions(&self) -> Vec<ButtonAction> {
+        let capabilities = self.state.device_features.button_capabilities;
+        ButtonAction::iter()
+            .filter(|action| capabilities.supports_action(*action))
+            .collect()
+    }
+
+    fn noise_canceling_modes(&self) -> Vec<NoiseCancelingMode> {
+        let noise_canceling_mode_type = self
+            .state
+            .device_features
+            .sound_mode
+            .map(|profile| profile.noise_canceling_mode_type)
+            .unwrap_or_default();
+        match noise_canceling_mode_type {
+            NoiseCancelingModeType::None => Vec::new(),
+            NoiseCancelingModeType::Basic => vec![
+                NoiseCancelingMode::Transport,
+                NoiseCancelingMode::Indoor,
+                NoiseCancelingMode::Outdoor,
+            ],
+            NoiseCancelingModeType::Custom => vec![
+                NoiseCancelingMode::Transport,
+                NoiseCancelingMode::Indoor,
+                NoiseCancelingMode::Outdoor,
+                NoiseCancelingMode::Custom,
+            ],
+        }
+    }
+
+    async fn cycle_ambient_sound_mode(
+        &mut self,
+        device: &impl Device,
+        offset: isize,
+    ) -> openscq30_lib::Result<()> {
+        let modes = [
+            AmbientSoundMode::Normal,
+            AmbientSoundMode::Transparency,
+            AmbientSoundMode::NoiseCanceling,
+        ];
+        if let Some(sound_modes) = self.state.sound_modes {
+            device
+                .set_sound_modes(SoundModes {
+                    ambient_sound_mode: cycle(&modes, sound_modes.ambient_sound_mode, offset),
+                    ..sound_modes
+                })
+                .await?;
+        } else if let Some(sound_modes) = self.state.sound_modes_type_two {
+            device
+                .set_sound_modes_type_two(SoundModesTypeTwo {
+                    ambient_sound_mode: cycle(&modes, sound_modes.ambient_sound_mode, offset),
+                    ..sound_modes
+                })
+                .await?;
+        }
+        self.state = device.state().await;
+        Ok(())
+    }
+
+    async fn cycle_noise_canceling_mode(
+        &mut self,
+        device: &impl Device,
+        offset: isize,
+    ) -> openscq30_lib::Result<()> {
+        if let Some(sound_modes) = self.state.sound_modes {
+            let modes = self.noise_canceling_modes();
+            if modes.is_empty() {
+                return Err(openscq30_lib::Error::FeatureNotSupported {
+                    feature_name: "noise canceling",
+                });
+            }
+            device
+                .set_sound_modes(SoundModes {
+                    noise_canceling_mode: cycle(&modes, sound_modes.noise_canceling_mode, offset),
+                    ..sound_modes
+                })
+                .await?;
+        } else if let Some(sound_modes) = self.state.sound_modes_type_two {
+            let modes = [
+                NoiseCancelingModeTypeTwo::Adaptive,
+                NoiseCancelingModeTypeTwo::Manual,
+            ];
+            device
+                .set_noise_canceling_mode_type_two(cycle(
+                    &modes,
+                    sound_modes.noise_canceling_mode,
+                    offset,
+                ))
+                .await?;
+        }
+        self.state = device.state().await;
+        Ok(())
+    }
+
+    /// Moves the selected band by `offset` steps of [`VolumeAdjustments::STEP`], staying within
+    /// [`VolumeAdjustments::MIN_VOLUME`] and [`VolumeAdjustments::MAX_VOLUME`].
+    async fn adjust_selected_band(
+        &mut self,
+        device: &impl Device,
+        offset: i16,
+    ) -> openscq30_lib::Result<()> {
+        let mut adjustments = self.volume_adjustments().adjustments().to_vec();
+        let band = &mut adjustments[self.selected_band];
+        let new_steps = (steps(*band) + offset).clamp(
+            steps(VolumeAdjustments::MIN_VOLUME),
+            steps(VolumeAdjustments::MAX_VOLUME),
+        );
+        *band = new_steps as f64 * VolumeAdjustments::STEP;
+        let volume_adjustments = VolumeAdjustments::new(adjustments).map_err(|err| {
+            openscq30_lib::Error::InvalidValue {
+                name: "volume adjustments",
+                value: err.to_string(),
+            }
+        })?;
+        device
+            .set_equalizer_configuration(EqualizerConfiguration::new_custom_profile(
+                volume_adjustments,
+            ))
+            .await?;
+        self.state = device.state().await;
+        Ok(())
+    }
+
+    /// Switches to the next or previous preset. From a custom profile, this starts at the first
+    /// or last preset.
+    async fn cycle_preset(
+        &mut self,
+        device: &impl Device,
+        offset: isize,
+    ) -> openscq30_lib::Result<()> {
+        let presets = PresetEqualizerProfile::iter().collect::<Vec<_>>();
+        let preset = match self.state.equalizer_configuration.preset_profile() {
+            Some(preset) => cycle(&presets, preset, offset),
+            None if offset > 0 => presets[0],
+            None => presets[presets.len() - 1],
+        };
+        device
+            .set_equalizer_configuration(EqualizerConfiguration::new_from_preset_profile(preset))
+            .await?;
+        self.state = device.state().await;
+        Ok(())
+    }
+
+    async fn set_custom_button_model(
+        &mut self,
+        device: &impl Device,
+        button_model: CustomButtonModel,
+    ) -> openscq30_lib::Result<()> {
+        device.set_custom_button_model(button_model).await?;
+        self.state = device.state().await;
+        Ok(())
+    }
+}
+
+/// The settings of a single gesture. Single clicks don't have a separate action for when only one
+/// earbud is connected.
+pub struct ButtonMut<'a> {
+    pub action: &'a mut ButtonAction,
+    pub disconnected_action: Option<&'a mut ButtonAction>,
+    pub is_enabled: &'a mut bool,
+}
+
+pub fn button_mut(
+    button_model: &mut CustomButtonModel,
+    side: ButtonSide,
+    gesture: ButtonGesture,
+) -> ButtonMut<'_> {
+    let tws_button = match (side, gesture) {
+        (ButtonSide::Left, ButtonGesture::SingleClick) => {
+            let button = &mut button_model.left_single_click;
+            return ButtonMut {
+                action: &mut button.action,
+                disconnected_action: None,
+                is_enabled: &mut button.is_enabled,
+            };
+        }
+        (ButtonSide::Right, ButtonGesture::SingleClick) => {
+            let button = &mut button_model.right_single_click;
+            return ButtonMut {
+                action: &mut button.action,
+                disconnected_action: None,
+                is_enabled: &mut button.is_enabled,
+            };
+        }
+        (ButtonSide::Left, ButtonGesture::DoubleClick) => &mut button_model.left_double_click,
+        (ButtonSide::Left, ButtonGesture::LongPress) => &mut button_model.left_long_press,
+        (ButtonSide::Right, ButtonGesture::DoubleClick) => &mut button_model.right_double_click,
+        (ButtonSide::Right, ButtonGesture::LongPress) => &mut button_model.right_long_press,
+    };
+    ButtonMut {
+        action: &mut tws_button.tws_connected_action,
+        disconnected_action: Some(&mut tws_button.tws_disconnected_action),
+        is_enabled: &mut tws_button.is_enabled,
+    }
+}
+
+/// Number of [`VolumeAdjustments::STEP`]s in `volume`
+pub fn steps(volume: f64) -> i16 {
+    (volume / VolumeAdjustments::STEP).round() as i16
+}
+
+/// Returns the item `offset` positions away from `current`, wrapping around. If `current` isn't in
+/// `items`, the first item is returned.
+fn cycle<T: Copy + PartialEq>(items: &[T], current: T, offset: isize) -> T {
+    match items.iter().position(|item| *item == current) {
+        Some(index) => items[(index as isize + offset).rem_euclid(items.len() as isize) as usize],
+        None => items[0],
+    }
+}
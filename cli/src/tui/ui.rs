@@ -0,0 +1,235 @@
+use heck::AsTitleCase;
+use openscq30_lib::{
+    api::connection::ConnectionStatus,
+    devices::standard::structures::{
+        Battery, ButtonAction, IsBatteryCharging, SingleBattery, VolumeAdjustments,
+    },
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+    Frame,
+};
+
+use super::app::{button_mut, steps, App, Focus, SoundModeRow, SOUND_MODE_ROWS};
+use crate::cli::{ButtonGesture, ButtonSide};
+
+pub fn render(app: &App, frame: &mut Frame) {
+    let [header, settings, equalizer, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(8),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [sound_modes, buttons] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .areas(settings);
+
+    render_header(app, frame, header);
+    render_sound_modes(app, frame, sound_modes);
+    render_buttons(app, frame, buttons);
+    render_equalizer(app, frame, equalizer);
+    render_footer(app, frame, footer);
+}
+
+fn render_header(app: &App, frame: &mut Frame, area: Rect) {
+    let connection_status = match app.connection_status {
+        ConnectionStatus::Connected => Span::styled("connected", Style::new().fg(Color::Green)),
+        ConnectionStatus::Disconnected => Span::styled("disconnected", Style::new().fg(Color::Red)),
+    };
+    let battery = match app.state.battery {
+        Battery::SingleBattery(battery) => format!("Battery {}", format_battery(battery)),
+        Battery::DualBattery(battery) => format!(
+            "Battery L {} R {}",
+            format_battery(battery.left),
+            format_battery(battery.right),
+        ),
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            app.name.to_owned(),
+            Style::new().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" ("),
+        connection_status,
+        Span::raw(")  "),
+        Span::raw(battery),
+    ]);
+    frame.render_widget(
+        Paragraph::new(line).block(Block::default().borders(Borders::ALL)),
+        area,
+    );
+}
+
+fn format_battery(battery: SingleBattery) -> String {
+    let charging = match battery.is_charging {
+        IsBatteryCharging::Yes => " (charging)",
+        IsBatteryCharging::No => "",
+    };
+    format!("{}/5{charging}", battery.level.0)
+}
+
+fn render_sound_modes(app: &App, frame: &mut Frame, area: Rect) {
+    let lines = SOUND_MODE_ROWS
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let (label, value) = match row {
+                SoundModeRow::AmbientSoundMode => (
+                    "Ambient sound mode",
+                    app.ambient_sound_mode().map(|mode| mode.to_string()),
+                ),
+                SoundModeRow::NoiseCancelingMode => {
+                    ("Noise canceling mode", app.noise_canceling_mode())
+                }
+            };
+            let value = value
+                .map(|value| AsTitleCase(value).to_string())
+                .unwrap_or_else(|| "Unsupported".to_string());
+            let line = Line::from(format!("{label}: < {value} >"));
+            if app.focus == Focus::SoundModes && index == app.sound_mode_row {
+                line.style(selected_style())
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        Paragraph::new(lines).block(block("Sound Modes", app.focus == Focus::SoundModes)),
+        area,
+    );
+}
+
+fn render_buttons(app: &App, frame: &mut Frame, area: Rect) {
+    let block = block("Buttons", app.focus == Focus::Buttons);
+    let Some(mut button_model) = app.state.custom_button_model else {
+        frame.render_widget(Paragraph::new("Unsupported").block(block), area);
+        return;
+    };
+    let lines = app
+        .buttons()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (side, gesture))| {
+            let button = button_mut(&mut button_model, side, gesture);
+            let side = match side {
+                ButtonSide::Left => "Left",
+                ButtonSide::Right => "Right",
+            };
+            let gesture = match gesture {
+                ButtonGesture::SingleClick => "single click",
+                ButtonGesture::DoubleClick => "double click",
+                ButtonGesture::LongPress => "long press",
+            };
+            let mut text = format!(
+                "[{}] {side} {gesture}: {}",
+                if *button.is_enabled { "x" } else { " " },
+                format_button_action(*button.action),
+            );
+            if let Some(disconnected_action) = button.disconnected_action {
+                text.push_str(&format!(
+                    " / {}",
+                    format_button_action(*disconnected_action)
+                ));
+            }
+            let line = Line::from(text);
+            if app.focus == Focus::Buttons && index == app.selected_button {
+                line.style(selected_style())
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn format_button_action(action: ButtonAction) -> String {
+    match action {
+        ButtonAction::Unknown(id) => format!("Unknown ({id})"),
+        _ => AsTitleCase(format!("{action:?}")).to_string(),
+    }
+}
+
+/// Bars start at [`VolumeAdjustments::MIN_VOLUME`] since bar charts can't show negative values.
+fn render_equalizer(app: &App, frame: &mut Frame, area: Rect) {
+    let volume_adjustments = app.volume_adjustments();
+    let title = match app.state.equalizer_configuration.preset_profile() {
+        Some(preset) => format!("Equalizer ({})", AsTitleCase(preset.to_string())),
+        None => "Equalizer (Custom)".to_string(),
+    };
+    let min_steps = steps(VolumeAdjustments::MIN_VOLUME);
+    let max_steps = steps(VolumeAdjustments::MAX_VOLUME);
+    let num_bands = volume_adjustments.adjustments().len();
+    let bars = volume_adjustments
+        .adjustments()
+        .iter()
+        .zip(VolumeAdjustments::band_frequencies(num_bands))
+        .enumerate()
+        .map(|(index, (adjustment, frequency))| {
+            let bar = Bar::default()
+                .value((steps(*adjustment) - min_steps) as u64)
+                .text_value(format!("{adjustment:+.1}"))
+                .label(Line::from(format_frequency(frequency)));
+            if app.focus == Focus::Equalizer && index == app.selected_band {
+                bar.style(Style::new().fg(Color::Yellow))
+                    .value_style(selected_style())
+            } else {
+                bar
+            }
+        })
+        .collect::<Vec<_>>();
+    let inner_width = area.width.saturating_sub(2);
+    let bar_width = (inner_width / num_bands as u16).saturating_sub(1).max(1);
+    frame.render_widget(
+        BarChart::default()
+            .block(block(&title, app.focus == Focus::Equalizer))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .max((max_steps - min_steps) as u64),
+        area,
+    );
+}
+
+fn format_frequency(frequency: f64) -> String {
+    if frequency < 1000.0 {
+        format!("{frequency:.0}")
+    } else {
+        format!("{:.1}k", frequency / 1000.0)
+    }
+}
+
+fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
+    let line = match &app.error {
+        Some(error) => Line::styled(error.to_owned(), Style::new().fg(Color::Red)),
+        None => Line::from(match app.focus {
+            Focus::SoundModes => "q quit  tab next  a ambient  n anc  ↑↓ select  ←→ change",
+            Focus::Equalizer => {
+                "q quit  tab next  ←→ band  ↑↓ ±0.1 dB  pgup/pgdn ±1 dB  0 reset  [ ] preset"
+            }
+            Focus::Buttons => {
+                "q quit  tab next  ↑↓ select  ←→ action  d disconnected action  space toggle"
+            }
+        }),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn block(title: &str, is_focused: bool) -> Block<'static> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_owned());
+    if is_focused {
+        block.border_style(Style::new().fg(Color::Yellow))
+    } else {
+        block
+    }
+}
+
+fn selected_style() -> Style {
+    Style::new().add_modifier(Modifier::REVERSED)
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+
+use openscq30_lib::api::{connection::ConnectionStatus, device::Device};
+use serde_json::{json, Value};
+
+use crate::cli::WatchFormat;
+
+/// Prints state changes until the device disconnects.
+pub async fn watch(format: WatchFormat, device: &impl Device) -> Result<(), Box<dyn Error>> {
+    let mut state_receiver = device.subscribe_to_state_updates().await;
+    let mut connection_status_receiver = device.connection_status();
+
+    let mut previous_state = serde_json::to_value(&*state_receiver.borrow_and_update())?;
+    let connection_status = *connection_status_receiver.borrow_and_update();
+    print_connection_status(format, connection_status);
+    match format {
+        WatchFormat::Text => {
+            let mut values = Vec::new();
+            flatten(String::new(), &previous_state, &mut values);
+            for (path, value) in values {
+                println!("{path}: {value}");
+            }
+        }
+        WatchFormat::JsonLines => {
+            println!("{}", json!({ "type": "state", "state": previous_state }));
+        }
+    }
+    if connection_status == ConnectionStatus::Disconnected {
+        return Ok(());
+    }
+
+    loop {
+        tokio::select! {
+            result = state_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                let state = serde_json::to_value(&*state_receiver.borrow_and_update())?;
+                let mut changes = Vec::new();
+                diff(String::new(), &previous_state, &state, &mut changes);
+                match format {
+                    WatchFormat::Text => {
+                        for change in &changes {
+                            println!("{}: {} -> {}", change.path, change.from, change.to);
+                        }
+                    }
+                    WatchFormat::JsonLines if !changes.is_empty() => {
+                        let paths = changes.iter().map(|change| &change.path).collect::<Vec<_>>();
+                        println!(
+                            "{}",
+                            json!({ "type": "state", "state": state, "changed": paths }),
+                        );
+                    }
+                    WatchFormat::JsonLines => (),
+                }
+                previous_state = state;
+            }
+            result = connection_status_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                let connection_status = *connection_status_receiver.borrow_and_update();
+                print_connection_status(format, connection_status);
+                if connection_status == ConnectionStatus::Disconnected {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_connection_status(format: WatchFormat, connection_status: ConnectionStatus) {
+    let connection_status = match connection_status {
+        ConnectionStatus::Connected => "connected",
+        ConnectionStatus::Disconnected => "disconnected",
+    };
+    match format {
+        WatchFormat::Text => println!("connection status: {connection_status}"),
+        WatchFormat::JsonLines => println!(
+            "{}",
+            json!({ "type": "connectionStatus", "connectionStatus": connection_status }),
+        ),
+    }
+}
+
+struct Change {
+    path: String,
+    from: Value,
+    to: Value,
+}
+
+/// Collects the paths of every value that differs, descending into objects so that, for example,
+/// a battery level change is reported as `battery.level` rather than the whole battery.
+fn diff(path: String, from: &Value, to: &Value, changes: &mut Vec<Change>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let added_keys = to.keys().filter(|key| !from.contains_key(*key));
+            for key in from.keys().chain(added_keys) {
+                diff(
+                    child_path(&path, key),
+                    from.get(key).unwrap_or(&Value::Null),
+                    to.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if from != to => changes.push(Change {
+            path,
+            from: from.to_owned(),
+            to: to.to_owned(),
+        }),
+        _ => (),
+    }
+}
+
+/// Collects every value that isn't an object, with the same paths as [`diff`].
+fn flatten<'a>(path: String, value: &'a Value, values: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, child) in object {
+                flatten(child_path(&path, key), child, values);
+            }
+        }
+        _ => values.push((path, value)),
+    }
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{diff, flatten};
+
+    fn changed_paths(from: Value, to: Value) -> Vec<(String, Value, Value)> {
+        let mut changes = Vec::new();
+        diff(String::new(), &from, &to, &mut changes);
+        changes
+            .into_iter()
+            .map(|change| (change.path, change.from, change.to))
+            .collect()
+    }
+
+    #[test]
+    fn it_reports_nothing_when_unchanged() {
+        let value = json!({ "battery": { "level": 3 }, "serialNumber": "a" });
+        assert!(changed_paths(value.to_owned(), value).is_empty());
+    }
+
+    #[test]
+    fn it_reports_nested_changes_by_path() {
+        assert_eq!(
+            vec![("battery.level".to_owned(), json!(3), json!(4))],
+            changed_paths(
+                json!({ "battery": { "level": 3, "isCharging": false } }),
+                json!({ "battery": { "level": 4, "isCharging": false } }),
+            ),
+        );
+    }
+
+    #[test]
+    fn it_reports_added_and_removed_keys_as_null() {
+        assert_eq!(
+            vec![
+                ("removed".to_owned(), json!(1), Value::Null),
+                ("added".to_owned(), Value::Null, json!(2)),
+            ],
+            changed_paths(json!({ "removed": 1 }), json!({ "added": 2 })),
+        );
+    }
+
+    #[test]
+    fn it_reports_arrays_as_a_whole() {
+        assert_eq!(
+            vec![("bands".to_owned(), json!([1, 2]), json!([1, 3]))],
+            changed_paths(json!({ "bands": [1, 2] }), json!({ "bands": [1, 3] })),
+        );
+    }
+
+    #[test]
+    fn it_reports_objects_replaced_by_other_values() {
+        assert_eq!(
+            vec![(
+                "hearId".to_owned(),
+                json!({ "isEnabled": true }),
+                Value::Null
+            )],
+            changed_paths(
+                json!({ "hearId": { "isEnabled": true } }),
+                json!({ "hearId": null }),
+            ),
+        );
+    }
+
+    #[test]
+    fn it_flattens_with_the_same_paths_as_diff() {
+        let value = json!({ "battery": { "level": 3 }, "serialNumber": "a" });
+        let mut values = Vec::new();
+        flatten(String::new(), &value, &mut values);
+        assert_eq!(
+            vec![
+                ("battery.level".to_owned(), &json!(3)),
+                ("serialNumber".to_owned(), &json!("a")),
+            ],
+            values,
+        );
+    }
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_file(dir: &TempDir, name: &str, contents: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_apply_dry_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "commute.toml",
+        r#"
+[soundModes]
+ambientSoundMode = "noiseCanceling"
+noiseCancelingMode = "indoor"
+
+[equalizer]
+preset = "BassBooster"
+"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path).arg("--dry-run");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "soundModes.ambientSoundMode: \"normal\" -> \"noiseCanceling\"\n",
+            "equalizer: {\"preset\":\"SoundcoreSignature\"} -> {\"preset\":\"BassBooster\"}\n",
+            "Dry run, nothing was sent to the device.\n",
+        )))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_apply_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "commute.json",
+        r#"{
+            "soundModes": { "transparencyMode": "fullyTransparent", "customNoiseCanceling": "adaptive" },
+            "equalizer": { "custom": [1, 2, 3, 4, 5, 6, 7, 8] },
+            "ambientSoundModeCycle": {
+                "noiseCancelingMode": true,
+                "transparencyMode": false,
+                "normalMode": true
+            }
+        }"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "soundModes.transparencyMode: \"vocalMode\" -> \"fullyTransparent\"\n",
+        ))
+        .stdout(predicate::str::contains(
+            "soundModes.customNoiseCanceling: 0 -> \"adaptive\"\n",
+        ))
+        .stdout(predicate::str::contains(
+            "-> {\"custom\":[1.0,2.0,3.0,4.0,5.0,6.0,7.0,8.0]}\n",
+        ))
+        .stdout(predicate::str::contains("ambientSoundModeCycle: "))
+        .stdout(predicate::str::contains("Dry run").not())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_apply_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "unchanged.toml",
+        r#"
+[soundModes]
+ambientSoundMode = "normal"
+"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("Nothing to change.\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_apply_wrong_number_of_bands() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "eq.toml",
+        r#"
+[equalizer]
+custom = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path).arg("--dry-run");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("the device has 8 bands, got 10"));
+}
+
+#[test]
+fn test_apply_unknown_setting() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "typo.toml",
+        "[soundMode]\nambientSoundMode = \"normal\"\n",
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown field `soundMode`"));
+}
+
+#[test]
+fn test_apply_sound_modes_type_two() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "type_two.toml",
+        r#"
+[soundModesTypeTwo]
+noiseCancelingMode = "manual"
+manualNoiseCanceling = "strong"
+windNoiseSuppression = true
+noiseCancelingAdaptiveSensitivityLevel = 3
+"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "soundModesTypeTwo.noiseCancelingMode: \"adaptive\" -> \"manual\"\n",
+            "soundModesTypeTwo.manualNoiseCanceling: \"weak\" -> \"strong\"\n",
+            "soundModesTypeTwo.windNoiseSuppression: false -> true\n",
+            "soundModesTypeTwo.noiseCancelingAdaptiveSensitivityLevel: 0 -> 3\n",
+        )))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_apply_clamps_adaptive_sensitivity_level() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_file(
+        &dir,
+        "type_two.toml",
+        r#"
+[soundModesTypeTwo]
+noiseCancelingAdaptiveSensitivityLevel = 10
+"#,
+    );
+    let mut cmd = common::openscq30();
+    cmd.arg("apply").arg(path).arg("--dry-run");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "soundModesTypeTwo.noiseCancelingAdaptiveSensitivityLevel: 0 -> 5\n",
+            "Dry run, nothing was sent to the device.\n",
+        )))
+        .stderr(predicate::str::contains(
+            "noiseCancelingAdaptiveSensitivityLevel 10 is out of range, so 5 will be used instead",
+        ));
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use predicates::prelude::*;
+
+#[test]
+fn test_demo_backend_flag() {
+    let mut cmd = common::openscq30();
+    cmd.arg("--backend")
+        .arg("demo")
+        .arg("get")
+        .arg("ambient-sound-mode");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("normal\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_invalid_backend_env_var() {
+    // Like --backend, the environment variable bypasses the daemon, so the backend is always
+    // validated
+    let mut cmd = common::openscq30();
+    cmd.env("OPENSCQ30_BACKEND", "replay").arg("list-devices");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("replay"));
+}
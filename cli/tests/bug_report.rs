@@ -0,0 +1,27 @@
+mod common;
+
+use predicates::prelude::*;
+
+#[test]
+fn test_bug_report_redacts_serial_number() {
+    let mut cmd = common::openscq30();
+    cmd.arg("bug-report");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Serial number: XXXXXXXXXXXXXXXX"))
+        .stdout(predicate::str::contains("0123456789ABCDEF").not())
+        .stdout(predicate::str::contains("num_equalizer_bands: 8"));
+}
+
+#[test]
+fn test_bug_report_writes_to_file() {
+    let path =
+        std::env::temp_dir().join(format!("openscq30-bug-report-{}.txt", std::process::id()));
+    let mut cmd = common::openscq30();
+    cmd.arg("bug-report").arg("--output").arg(&path);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(report.starts_with("OpenSCQ30 bug report"));
+}
@@ -0,0 +1,46 @@
+//! Shared by the integration tests, each of which includes it with `mod common;`.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use assert_cmd::{cargo::CommandCargoExt, Command};
+
+/// Runs the CLI without picking up a daemon that happens to be running on the machine, by pointing
+/// it at a socket that doesn't exist. Tests that want a daemon set the socket path again.
+pub fn openscq30() -> Command {
+    Command::from_std(openscq30_std())
+}
+
+/// Like [`openscq30`], for tests that need to read output while the process is still running.
+pub fn openscq30_std() -> std::process::Command {
+    let mut command = std::process::Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    #[cfg(unix)]
+    command.env(
+        openscq30_daemon::SOCKET_PATH_ENV_VAR,
+        no_daemon_socket_path(),
+    );
+    command
+}
+
+/// Runs the CLI through the daemon listening on `socket_path`
+#[cfg(unix)]
+pub fn openscq30_with_daemon(socket_path: &Path) -> Command {
+    let mut command = openscq30_std();
+    use_daemon(&mut command, socket_path);
+    Command::from_std(command)
+}
+
+/// The backend environment variable, which the tests may be run with, is removed since it makes
+/// the CLI bypass the daemon.
+#[cfg(unix)]
+pub fn use_daemon(command: &mut std::process::Command, socket_path: &Path) {
+    command
+        .env(openscq30_daemon::SOCKET_PATH_ENV_VAR, socket_path)
+        .env_remove(openscq30_lib::api::backend::Backend::ENV_VAR);
+}
+
+fn no_daemon_socket_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("no-daemon")
+        .join("openscq30d.sock")
+}
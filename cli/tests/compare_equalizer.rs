@@ -0,0 +1,54 @@
+mod common;
+
+use predicates::prelude::*;
+
+#[test]
+fn test_compare_equalizer() {
+    let mut cmd = common::openscq30();
+    cmd.arg("compare-equalizer")
+        .arg("--a")
+        .args(["0", "0", "0", "0", "0", "0", "0", "0"])
+        .arg("--b")
+        .args(["-10", "-20", "0", "0", "0", "0", "20", "10"])
+        .write_stdin("t\np\nq\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Active: A\n"))
+        .stdout(predicate::str::contains("Active: B\n"))
+        .stdout(predicate::str::contains(
+            "A preferred 0 times, B preferred 1 times\n",
+        ))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_compare_equalizer_blind() {
+    let mut cmd = common::openscq30();
+    cmd.arg("compare-equalizer")
+        .arg("--blind")
+        .arg("--a")
+        .args(["0", "0", "0", "0", "0", "0", "0", "0"])
+        .arg("--b")
+        .args(["-10", "-20", "0", "0", "0", "0", "20", "10"])
+        .write_stdin("p\np\nq\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Active: ?\n"))
+        .stdout(predicate::str::contains("Active: A\n").not())
+        .stdout(predicate::str::contains("preferred"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_compare_equalizer_wrong_number_of_bands() {
+    let mut cmd = common::openscq30();
+    cmd.arg("compare-equalizer")
+        .arg("--a")
+        .args(["0", "0", "0", "0", "0", "0", "0", "0", "0", "0"])
+        .arg("--b")
+        .args(["-10", "-20", "0", "0", "0", "0", "20", "10", "0", "0"])
+        .write_stdin("q\n");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("number of volume adjustments"));
+}
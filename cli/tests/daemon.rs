@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::path::Path;
+
+use openscq30_daemon::server::Server;
+use openscq30_lib::{demo::device::DemoDeviceRegistry, futures::TokioFutures};
+use predicates::prelude::*;
+use tokio::{net::UnixListener, task::LocalSet};
+
+fn start_daemon(socket_path: &Path) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let listener = runtime.block_on(async { UnixListener::bind(socket_path).unwrap() });
+    std::thread::spawn(move || {
+        LocalSet::new().block_on(
+            &runtime,
+            Server::new(DemoDeviceRegistry::<TokioFutures>::new()).serve(listener),
+        )
+    });
+}
+
+#[test]
+fn test_changes_persist_between_runs_when_using_daemon() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("openscq30d.sock");
+    start_daemon(&socket_path);
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.arg("set").arg("ambient-sound-mode").arg("transparency");
+    cmd.assert().success().stderr(predicate::str::is_empty());
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.arg("get").arg("ambient-sound-mode");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("transparency\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_no_daemon_connects_directly() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("openscq30d.sock");
+    start_daemon(&socket_path);
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.arg("set").arg("ambient-sound-mode").arg("transparency");
+    cmd.assert().success();
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.arg("--no-daemon").arg("get").arg("ambient-sound-mode");
+    cmd.assert().success().stdout(predicate::eq("normal\n"));
+}
+
+#[test]
+fn test_backend_env_var_connects_directly() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("openscq30d.sock");
+    start_daemon(&socket_path);
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.arg("set").arg("ambient-sound-mode").arg("transparency");
+    cmd.assert().success();
+
+    let mut cmd = common::openscq30_with_daemon(&socket_path);
+    cmd.env("OPENSCQ30_BACKEND", "demo")
+        .arg("get")
+        .arg("ambient-sound-mode");
+    cmd.assert().success().stdout(predicate::eq("normal\n"));
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn command(config: &std::path::Path) -> Command {
+    let mut cmd = common::openscq30();
+    cmd.arg("--config").arg(config);
+    cmd
+}
+
+#[test]
+fn test_create_list_and_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args(["equalizer-profile", "create", "Bassy"])
+        .args(["60", "40", "20", "0", "0", "0", "0", "-10"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&config)
+        .unwrap()
+        .contains("[equalizer_custom_profiles.Bassy]"));
+
+    command(&config)
+        .args(["equalizer-profile", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("Bassy: 6.0 4.0 2.0 0.0 0.0 0.0 0.0 -1.0\n"));
+
+    command(&config)
+        .args(["equalizer-profile", "delete", "Bassy"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["equalizer-profile", "delete", "Bassy"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No equalizer profile named Bassy"));
+}
+
+#[test]
+fn test_create_from_device() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args(["equalizer-profile", "create", "Current"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["equalizer-profile", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Current: "));
+}
+
+#[test]
+fn test_export_and_import() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(
+        &config,
+        "[equalizer_custom_profiles.Flat]\nvolume_offsets = [0, 0, 0, 0, 0, 0, 0, 0]\n",
+    )
+    .unwrap();
+    command(&config)
+        .args(["equalizer-profile", "export"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "[{\"name\":\"Flat\",\"volumeAdjustments\":[0.0,0.0,0.0,0.0,0.0,0.0,0.0,0.0]}]\n",
+        ));
+
+    command(&config)
+        .args(["equalizer-profile", "import"])
+        .write_stdin(r#"[{"name":"Flat","volumeAdjustments":[1,1,1,1,1,1,1,1]}]"#)
+        .assert()
+        .success();
+    command(&config)
+        .args(["equalizer-profile", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(concat!(
+            "Flat: 0.0 0.0 0.0 0.0 0.0 0.0 0.0 0.0\n",
+            "Flat (2): 1.0 1.0 1.0 1.0 1.0 1.0 1.0 1.0\n",
+        )));
+}
+
+#[test]
+fn test_activate() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args(["equalizer-profile", "activate", "Missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No equalizer profile named Missing",
+        ));
+    fs::write(
+        &config,
+        "[equalizer_custom_profiles.Flat]\nvolume_offsets = [0, 0, 0, 0, 0, 0, 0, 0]\n",
+    )
+    .unwrap();
+    command(&config)
+        .args(["equalizer-profile", "activate", "Flat"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
@@ -1,9 +1,10 @@
-use assert_cmd::Command;
+mod common;
+
 use predicates::prelude::*;
 
 #[test]
 fn test_get_ambient_sound_mode() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("get").arg("ambient-sound-mode");
     cmd.assert()
         .success()
@@ -13,7 +14,7 @@ fn test_get_ambient_sound_mode() {
 
 #[test]
 fn test_get_noise_canceling_mode() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("get").arg("noise-canceling-mode");
     cmd.assert()
         .success()
@@ -23,10 +24,134 @@ fn test_get_noise_canceling_mode() {
 
 #[test]
 fn test_get_equalizer() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("get").arg("equalizer");
     cmd.assert()
         .success()
         .stdout(predicate::eq("0 0 0 0 0 0 0 0\n"))
         .stderr(predicate::str::is_empty());
 }
+
+#[test]
+fn test_get_adaptive_sensitivity() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("adaptive-sensitivity");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("0\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_wind_noise_suppression() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("wind-noise-suppression");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("false\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_noise_canceling_mode_type_two() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("noise-canceling-mode-type-two");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("adaptive\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_transparency_mode() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("transparency-mode");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("vocal-mode\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_custom_noise_canceling() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("custom-noise-canceling");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("0\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_state_defaults_to_json() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("state");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"))
+        .stdout(predicate::str::contains(
+            r#""serialNumber": "0123456789ABCDEF""#,
+        ))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_state_yaml() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("state").arg("--format").arg("yaml");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("serialNumber: 0123456789ABCDEF\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_state_toml() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("state").arg("--format").arg("toml");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "serialNumber = \"0123456789ABCDEF\"\n",
+        ))
+        .stdout(predicate::str::contains("[battery]\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_ambient_sound_mode_json() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get")
+        .arg("ambient-sound-mode")
+        .arg("--format")
+        .arg("json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("{\n  \"ambientSoundMode\": \"normal\"\n}\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_equalizer_toml() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get").arg("equalizer").arg("--format").arg("toml");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("volumeAdjustments = ["))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_get_noise_canceling_mode_type_two_json() {
+    let mut cmd = common::openscq30();
+    cmd.arg("get")
+        .arg("noise-canceling-mode-type-two")
+        .arg("--format")
+        .arg("json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(
+            "{\n  \"noiseCancelingModeTypeTwo\": \"adaptive\"\n}\n",
+        ))
+        .stderr(predicate::str::is_empty());
+}
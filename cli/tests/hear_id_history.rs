@@ -0,0 +1,68 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const HISTORY: &str = r#"
+[[entries.0123456789ABCDEF]]
+recordedAt = 1000
+
+[entries.0123456789ABCDEF.hearId]
+type = "basic"
+isEnabled = true
+time = 0
+
+[entries.0123456789ABCDEF.hearId.volumeAdjustments]
+left = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+right = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+
+[[entries.0123456789ABCDEF]]
+recordedAt = 2000
+
+[entries.0123456789ABCDEF.hearId]
+type = "basic"
+isEnabled = true
+time = 0
+
+[entries.0123456789ABCDEF.hearId.volumeAdjustments]
+left = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+right = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]
+"#;
+
+fn command(config: &std::path::Path) -> Command {
+    let mut cmd = common::openscq30();
+    cmd.arg("--config").arg(config);
+    cmd
+}
+
+#[test]
+fn test_list_diff_and_restore_saved_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    let history = dir.path().join("hear_id_history.toml");
+    fs::write(&history, HISTORY).unwrap();
+
+    command(&config)
+        .args(["hear-id-history", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"recordedAt\": 1000"))
+        .stdout(predicate::str::contains("\"recordedAt\": 2000"));
+    command(&config)
+        .args(["hear-id-history", "diff", "0", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"isEnabledChanged\": false"));
+    command(&config)
+        .args(["hear-id-history", "restore", "1"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["hear-id-history", "restore", "2"])
+        .assert()
+        .failure();
+    // Nothing new was recorded, so the file is left alone
+    assert_eq!(HISTORY, fs::read_to_string(&history).unwrap());
+}
@@ -1,12 +1,92 @@
-use assert_cmd::Command;
+mod common;
+
 use predicates::prelude::*;
 
 #[test]
 fn test_list_devices() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("list-devices");
     cmd.assert()
         .success()
-        .stdout(predicate::eq("00:00:00:00:00:00\n"))
+        .stdout(predicate::eq(
+            "INDEX  NAME      MAC ADDRESS\n0      Demo Q30  00:00:00:00:00:00\n",
+        ))
         .stderr(predicate::str::is_empty());
 }
+
+#[test]
+fn test_list_devices_with_model() {
+    let mut cmd = common::openscq30();
+    cmd.arg("list-devices").arg("--show-model");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(
+            "INDEX  NAME      MAC ADDRESS        MODEL\n0      Demo Q30  00:00:00:00:00:00  unknown\n",
+        ))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_list_devices_json() {
+    let mut cmd = common::openscq30();
+    cmd.arg("list-devices").arg("--format").arg("json");
+    let output = cmd.output().unwrap();
+    let devices: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        serde_json::json!([{
+            "index": 0,
+            "name": "Demo Q30",
+            "macAddress": "00:00:00:00:00:00",
+        }]),
+        devices,
+    );
+
+    let mut cmd = common::openscq30();
+    cmd.arg("list-devices")
+        .arg("--format")
+        .arg("json")
+        .arg("--show-model");
+    let output = cmd.output().unwrap();
+    let devices: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(serde_json::Value::Null, devices[0]["model"]);
+    assert!(devices[0].as_object().unwrap().contains_key("model"));
+}
+
+#[test]
+fn test_select_by_name() {
+    let mut cmd = common::openscq30();
+    cmd.arg("--name")
+        .arg("demo")
+        .arg("get")
+        .arg("ambient-sound-mode");
+    cmd.assert().success().stdout(predicate::eq("normal\n"));
+}
+
+#[test]
+fn test_selection_without_match_is_an_error() {
+    for args in [
+        ["--name", "Life Q35"],
+        ["--index", "1"],
+        ["--model", "A3028"],
+        ["--mac-address", "00:00:00:00:00:01"],
+    ] {
+        let mut cmd = common::openscq30();
+        cmd.args(args).arg("get").arg("ambient-sound-mode");
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::contains("No device found."));
+    }
+}
+
+#[test]
+fn test_unknown_model_is_rejected() {
+    let mut cmd = common::openscq30();
+    cmd.arg("--model")
+        .arg("Q30")
+        .arg("get")
+        .arg("ambient-sound-mode");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected one of A3027"));
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn command(config: &std::path::Path) -> Command {
+    let mut cmd = common::openscq30();
+    cmd.arg("--config").arg(config);
+    cmd
+}
+
+#[test]
+fn test_create_list_and_activate() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args(["preset", "create", "Commute"])
+        .args(["--ambient-sound-mode", "noise-canceling"])
+        .args(["--equalizer-preset", "bass-booster"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["preset", "create", "Home"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["preset", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("Commute\nHome\n"));
+    command(&config)
+        .args(["preset", "activate", "Commute"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_create_with_missing_equalizer_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args([
+            "preset",
+            "create",
+            "Commute",
+            "--equalizer-profile",
+            "Bassy",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No equalizer profile named Bassy"));
+    assert!(!config.exists());
+}
+
+#[test]
+fn test_activate_preset_created_by_gui() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(
+        &config,
+        r#"
+[quick_presets.00000000-0000-0000-0000-000000000000.Commute]
+ambient_sound_mode = "noiseCanceling"
+"#,
+    )
+    .unwrap();
+    command(&config)
+        .args(["preset", "activate", "Commute"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["preset", "activate", "Gym"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No quick preset named Gym"));
+}
+
+#[test]
+fn test_export_import_and_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    command(&config)
+        .args([
+            "preset",
+            "create",
+            "Commute",
+            "--noise-canceling-mode",
+            "transport",
+        ])
+        .assert()
+        .success();
+    let output = command(&config)
+        .args(["preset", "export"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let exported = String::from_utf8(output.stdout).unwrap();
+    assert!(exported.contains("\"Commute\""), "{exported}");
+
+    command(&config)
+        .args(["preset", "import"])
+        .write_stdin(exported.to_owned())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Use --overwrite"));
+    command(&config)
+        .args(["preset", "delete", "Commute"])
+        .assert()
+        .success();
+    command(&config)
+        .args(["preset", "import"])
+        .write_stdin(exported)
+        .assert()
+        .success();
+    command(&config)
+        .args(["preset", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("Commute\n"));
+}
@@ -1,9 +1,10 @@
-use assert_cmd::Command;
+mod common;
+
 use predicates::prelude::*;
 
 #[test]
 fn test_set_ambient_sound_mode() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("set")
         .arg("ambient-sound-mode")
         .arg("noise-canceling");
@@ -15,7 +16,7 @@ fn test_set_ambient_sound_mode() {
 
 #[test]
 fn test_set_noise_canceling_mode() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
     cmd.arg("set").arg("noise-canceling-mode").arg("transport");
     cmd.assert()
         .success()
@@ -25,13 +26,280 @@ fn test_set_noise_canceling_mode() {
 
 #[test]
 fn test_set_equalizer() {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("equalizer")
+        .arg("--")
+        .args(["-120", "-60", "0", "0", "0", "60", "120", "135"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("may cause clipping"));
+}
+
+#[test]
+fn test_set_equalizer_without_boost() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("equalizer")
+        .arg("--")
+        .args(["-120", "-60", "0", "0", "0", "10", "20", "30"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_equalizer_normalized() {
+    let mut cmd = common::openscq30();
     cmd.arg("set")
         .arg("equalizer")
+        .arg("--normalize")
         .arg("--")
-        .args(&["-120", "-60", "0", "0", "0", "60", "120", "135"]);
+        .args(["-120", "-60", "0", "0", "0", "60", "120", "135"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_adaptive_sensitivity() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("adaptive-sensitivity").arg("3");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_adaptive_sensitivity_out_of_range() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("adaptive-sensitivity").arg("6");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_set_wind_noise_suppression() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("wind-noise-suppression").arg("true");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_manual_noise_canceling() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("manual-noise-canceling").arg("strong");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_transparency_mode() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("transparency-mode")
+        .arg("fully-transparent");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_custom_noise_canceling() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("custom-noise-canceling").arg("7");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_custom_noise_canceling_adaptive() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("custom-noise-canceling").arg("adaptive");
     cmd.assert()
         .success()
         .stdout(predicate::str::is_empty())
         .stderr(predicate::str::is_empty());
 }
+
+#[test]
+fn test_set_custom_noise_canceling_out_of_range() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("custom-noise-canceling").arg("11");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_set_equalizer_with_wrong_number_of_bands() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("equalizer")
+        .args(["0", "0", "0", "0", "0", "0", "0", "0", "0", "0"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("the device has 8 bands, got 10"));
+}
+
+#[test]
+fn test_set_equalizer_preset() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set").arg("equalizer-preset").arg("bass-booster");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_dynamic_range_compression() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    let mut cmd = common::openscq30();
+    cmd.arg("--config")
+        .arg(&config)
+        .arg("set")
+        .arg("dynamic-range-compression")
+        .arg("false");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    // The device doesn't report it, so the choice has to be remembered
+    assert!(std::fs::read_to_string(&config)
+        .unwrap()
+        .contains("[dynamic_range_compression]"));
+    let mut cmd = common::openscq30();
+    cmd.arg("--config")
+        .arg(&config)
+        .arg("get")
+        .arg("state")
+        .arg("--format")
+        .arg("json");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"isDynamicRangeCompressionEnabled\": false",
+    ));
+}
+
+#[test]
+fn test_set_ambient_sound_mode_cycle() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("ambient-sound-mode-cycle")
+        .arg("normal")
+        .arg("noise-canceling");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_button() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("button")
+        .arg("left")
+        .arg("double-click")
+        .arg("--action")
+        .arg("play-pause")
+        .arg("--disconnected-action")
+        .arg("next-song")
+        .arg("--enabled")
+        .arg("true");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_single_click_button_disconnected_action() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("button")
+        .arg("right")
+        .arg("single-click")
+        .arg("--disconnected-action")
+        .arg("play-pause");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("single click has only one action"));
+}
+
+#[test]
+fn test_set_hear_id() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("hear-id")
+        .arg("--enabled")
+        .arg("true")
+        .arg("--left")
+        .args(["-10", "0", "10", "20", "30", "40", "50", "60"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_hear_id_with_wrong_number_of_bands() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("hear-id")
+        .arg("--right")
+        .args(["0", "0", "0", "0", "0", "0", "0", "0", "0"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("the device has 8 bands, got 9"));
+}
+
+#[test]
+fn test_set_gender_and_age_range() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("gender-and-age-range")
+        .arg("--gender")
+        .arg("male")
+        .arg("--age-range")
+        .arg("30-to-39");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_set_gender_rejects_numbers() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("gender-and-age-range")
+        .arg("--gender")
+        .arg("1");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value '1'"));
+}
+
+#[test]
+fn test_set_hear_id_type_requires_custom_hear_id() {
+    let mut cmd = common::openscq30();
+    cmd.arg("set")
+        .arg("hear-id")
+        .arg("--hear-id-type")
+        .arg("hearing-test");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("hear id type"));
+}
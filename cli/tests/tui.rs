@@ -0,0 +1,15 @@
+mod common;
+
+use predicates::prelude::*;
+
+// Key handling and rendering are tested with the demo device in the tui module itself, since
+// raw mode needs a real terminal
+
+#[test]
+fn test_tui_requires_terminal() {
+    let mut cmd = common::openscq30();
+    cmd.arg("tui");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("interactive terminal"));
+}
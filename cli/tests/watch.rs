@@ -0,0 +1,167 @@
+mod common;
+
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Stdio},
+    sync::mpsc,
+    time::Duration,
+};
+
+/// Runs watch in the background, since it only exits once the device disconnects, which the demo
+/// device never does. Lines are read on another thread so that a missing line fails the test
+/// rather than hanging it.
+struct Watch {
+    child: Child,
+    lines: mpsc::Receiver<String>,
+}
+
+impl Watch {
+    fn spawn(configure: impl FnOnce(&mut std::process::Command)) -> Self {
+        let mut command = common::openscq30_std();
+        command.arg("watch").stdout(Stdio::piped());
+        configure(&mut command);
+        let mut child = command.spawn().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { child, lines }
+    }
+
+    fn next_line(&self) -> String {
+        self.lines
+            .recv_timeout(Duration::from_secs(10))
+            .expect("watch should print another line")
+    }
+
+    /// Reads the text format's initial state, which ends with the last key in alphabetical order.
+    fn initial_state_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.next_line();
+            let is_last = line.starts_with("soundModesTypeTwo.windNoiseSuppression: ");
+            lines.push(line);
+            if is_last {
+                return lines;
+            }
+        }
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_watch_json_lines() {
+    let watch = Watch::spawn(|command| {
+        command.arg("--format").arg("json-lines");
+    });
+    assert_eq!(
+        r#"{"connectionStatus":"connected","type":"connectionStatus"}"#,
+        watch.next_line(),
+    );
+    let state = watch.next_line();
+    assert!(state.starts_with(r#"{"state":{"#), "{state}");
+    assert!(state.contains(r#""serialNumber":"0123456789ABCDEF""#));
+}
+
+#[test]
+fn test_watch_text_prints_initial_state() {
+    let watch = Watch::spawn(|_| ());
+    assert_eq!("connection status: connected", watch.next_line());
+    let state_lines = watch.initial_state_lines();
+    assert!(
+        state_lines.contains(&r#"serialNumber: "0123456789ABCDEF""#.to_owned()),
+        "{state_lines:?}"
+    );
+    assert!(
+        state_lines.contains(&r#"soundModes.ambientSoundMode: "normal""#.to_owned()),
+        "{state_lines:?}"
+    );
+}
+
+#[cfg(unix)]
+mod with_daemon {
+    use std::path::Path;
+
+    use openscq30_daemon::server::Server;
+    use openscq30_lib::{demo::device::DemoDeviceRegistry, futures::TokioFutures};
+    use tokio::{net::UnixListener, task::LocalSet};
+
+    use super::Watch;
+
+    // The demo device only changes when told to, so changes are made through a daemon that both
+    // watch and set are connected to
+    fn start_daemon(socket_path: &Path) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let listener = runtime.block_on(async { UnixListener::bind(socket_path).unwrap() });
+        std::thread::spawn(move || {
+            LocalSet::new().block_on(
+                &runtime,
+                Server::new(DemoDeviceRegistry::<TokioFutures>::new()).serve(listener),
+            )
+        });
+    }
+
+    fn set_ambient_sound_mode(socket_path: &Path) {
+        crate::common::openscq30_with_daemon(socket_path)
+            .arg("set")
+            .arg("ambient-sound-mode")
+            .arg("transparency")
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_watch_text_prints_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("openscq30d.sock");
+        start_daemon(&socket_path);
+
+        let watch = Watch::spawn(|command| crate::common::use_daemon(command, &socket_path));
+        watch.initial_state_lines();
+
+        set_ambient_sound_mode(&socket_path);
+        assert_eq!(
+            r#"soundModes.ambientSoundMode: "normal" -> "transparency""#,
+            watch.next_line(),
+        );
+    }
+
+    #[test]
+    fn test_watch_json_lines_prints_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("openscq30d.sock");
+        start_daemon(&socket_path);
+
+        let watch = Watch::spawn(|command| {
+            crate::common::use_daemon(command, &socket_path);
+            command.arg("--format").arg("json-lines");
+        });
+        watch.next_line();
+        watch.next_line();
+
+        set_ambient_sound_mode(&socket_path);
+        let line = watch.next_line();
+        assert!(
+            line.starts_with(r#"{"changed":["soundModes.ambientSoundMode"],"state":{"#),
+            "{line}"
+        );
+        assert!(
+            line.contains(r#""ambientSoundMode":"transparency""#),
+            "{line}"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Write,
+    fs,
+    path::Path,
+    sync::LazyLock,
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{write_atomically, CustomEqualizerProfile, Error, QuickPreset};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    equalizer_custom_profiles: HashMap<String, CustomEqualizerProfile>,
+    quick_presets: HashMap<Uuid, HashMap<String, QuickPreset>>,
+    dynamic_range_compression: HashMap<Uuid, bool>,
+}
+
+impl Config {
+    /// A missing file is treated as an empty config, since it is only created once something is
+    /// saved.
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|source| Error::Parse {
+                path: path.to_owned(),
+                source,
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(Error::Read {
+                path: path.to_owned(),
+                source,
+            }),
+        }
+    }
+
+    /// Writes to a temporary file first so that other programs never see a partially written
+    /// config.
+    pub fn save(&self, path: &Path) -> crate::Result<()> {
+        write_atomically(path, &toml::to_string(self)?).map_err(|source| Error::Write {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    /// Set a single profile. This iterates all existing profiles for validation, so use insert_custom_profiles when inserting many.
+    pub fn set_custom_profile(&mut self, name: String, profile: CustomEqualizerProfile) {
+        // If multiple profiles with the same volume adjustments existed, it would be ambiguous which should be selected,
+        // since the selection is determined only by volume adjustments.
+        self.equalizer_custom_profiles
+            .retain(|_name, p| p.volume_adjustments() != profile.volume_adjustments());
+        self.equalizer_custom_profiles.insert(name, profile);
+    }
+
+    /// Like set_custom_profile except optimized for inserting multiple profiles
+    pub fn insert_custom_profiles(
+        &mut self,
+        profiles: impl IntoIterator<Item = (String, CustomEqualizerProfile)>,
+        overwrite: bool,
+    ) {
+        let profiles = profiles.into_iter();
+        let unique_values: HashMap<CustomEqualizerProfile, String> = self
+            .equalizer_custom_profiles
+            .iter()
+            .map(|(key, value)| (value.to_owned(), key.to_owned()))
+            .collect();
+        for (mut name, profile) in profiles {
+            let existing_name_from_values = unique_values.get(&profile);
+            if !overwrite {
+                // If a profile exists with the same values, there's nothing we can do to not overwrite
+                if existing_name_from_values.is_some() {
+                    continue;
+                }
+                if self.equalizer_custom_profiles.contains_key(&name) {
+                    match self.find_name_for_duplicate(name) {
+                        Some(new_name) => name = new_name,
+                        None => continue,
+                    }
+                }
+            }
+            self.equalizer_custom_profiles.insert(name, profile);
+            existing_name_from_values.into_iter().for_each(|name| {
+                self.equalizer_custom_profiles.remove(name);
+            });
+        }
+    }
+
+    fn find_name_for_duplicate(&self, mut name: String) -> Option<String> {
+        let original_name_len = name.len();
+        for i in 2..1000 {
+            write!(name, " ({i})").unwrap();
+            if !self.equalizer_custom_profiles.contains_key(&name) {
+                return Some(name);
+            }
+            name.truncate(original_name_len);
+        }
+        None
+    }
+
+    /// Returns the removed profile, if there was one
+    pub fn remove_custom_profile(&mut self, name: &str) -> Option<CustomEqualizerProfile> {
+        self.equalizer_custom_profiles.remove(name)
+    }
+
+    pub fn custom_profiles(&self) -> &HashMap<String, CustomEqualizerProfile> {
+        &self.equalizer_custom_profiles
+    }
+
+    /// Quick presets are stored separately for each device type, which is identified by its
+    /// service uuid.
+    pub fn quick_presets(&self, device_service_uuid: Uuid) -> &HashMap<String, QuickPreset> {
+        static EMPTY_HASHMAP: LazyLock<HashMap<String, QuickPreset>> = LazyLock::new(HashMap::new);
+        self.quick_presets
+            .get(&device_service_uuid)
+            .unwrap_or(&EMPTY_HASHMAP)
+    }
+
+    pub fn set_quick_preset(
+        &mut self,
+        device_service_uuid: Uuid,
+        name: impl Into<String>,
+        quick_preset: QuickPreset,
+    ) {
+        let name = name.into();
+        match self.quick_presets.entry(device_service_uuid) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().insert(name, quick_preset);
+            }
+            Entry::Vacant(entry) => {
+                let device_quick_presets = HashMap::from([(name, quick_preset)]);
+                entry.insert(device_quick_presets);
+            }
+        }
+    }
+
+    /// Returns the removed quick preset, if there was one
+    pub fn remove_quick_preset(
+        &mut self,
+        device_service_uuid: Uuid,
+        name: &str,
+    ) -> Option<QuickPreset> {
+        self.quick_presets
+            .get_mut(&device_service_uuid)?
+            .remove(name)
+    }
+
+    /// Devices don't report whether dynamic range compression is enabled, so the last choice made
+    /// with the CLI is remembered for each device type.
+    pub fn dynamic_range_compression(&self, device_service_uuid: Uuid) -> Option<bool> {
+        self.dynamic_range_compression
+            .get(&device_service_uuid)
+            .copied()
+    }
+
+    pub fn set_dynamic_range_compression(&mut self, device_service_uuid: Uuid, is_enabled: bool) {
+        self.dynamic_range_compression
+            .insert(device_service_uuid, is_enabled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use openscq30_lib::devices::standard::structures::{AmbientSoundMode, PresetEqualizerProfile};
+    use uuid::Uuid;
+
+    use crate::{Config, CustomEqualizerProfile, PresetOrCustomEqualizerProfile, QuickPreset};
+
+    #[test]
+    fn test_reads_existing_config() {
+        let config: Config = toml::from_str(
+            r#"
+[equalizer_custom_profiles.Bassy]
+volume_offsets = [60, 40, 20, 0, 0, 0, 0, 0]
+
+[quick_presets.00000000-0000-0000-0000-000000000000.Commute]
+ambient_sound_mode = "noiseCanceling"
+
+[quick_presets.00000000-0000-0000-0000-000000000000.Commute.equalizer_profile]
+custom = "Bassy"
+
+[quick_presets.00000000-0000-0000-0000-000000000000.Home.equalizer_profile]
+preset = "BassBooster"
+
+[dynamic_range_compression]
+00000000-0000-0000-0000-000000000000 = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            [6.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0].as_slice(),
+            config.custom_profiles()["Bassy"]
+                .volume_adjustments()
+                .as_ref(),
+        );
+        let quick_presets = config.quick_presets(Uuid::default());
+        assert_eq!(
+            QuickPreset {
+                ambient_sound_mode: Some(AmbientSoundMode::NoiseCanceling),
+                equalizer_profile: Some(PresetOrCustomEqualizerProfile::Custom(Arc::from("Bassy"))),
+                ..Default::default()
+            },
+            quick_presets["Commute"],
+        );
+        assert_eq!(
+            Some(PresetOrCustomEqualizerProfile::Preset(
+                PresetEqualizerProfile::BassBooster
+            )),
+            quick_presets["Home"].equalizer_profile,
+        );
+        assert_eq!(
+            Some(true),
+            config.dynamic_range_compression(Uuid::default())
+        );
+    }
+
+    #[test]
+    fn test_import_renames_conflicting_names_and_skips_duplicate_values() {
+        let mut config = Config::default();
+        config.set_custom_profile("a".to_string(), CustomEqualizerProfile::new(&[0.0; 8]));
+        config.insert_custom_profiles(
+            [
+                ("a".to_string(), CustomEqualizerProfile::new(&[1.0; 8])),
+                ("b".to_string(), CustomEqualizerProfile::new(&[0.0; 8])),
+            ],
+            false,
+        );
+        let mut names = config.custom_profiles().keys().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(vec!["a", "a (2)"], names);
+    }
+
+    #[test]
+    fn test_missing_file_loads_as_empty_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        assert_eq!(Config::default(), Config::load(&path).unwrap());
+
+        let mut config = Config::default();
+        config.set_quick_preset(Uuid::default(), "Commute", QuickPreset::default());
+        config.save(&path).unwrap();
+        assert_eq!(config, Config::load(&path).unwrap());
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use openscq30_lib::devices::standard::structures::{
+    EqualizerConfiguration, VolumeAdjustments, VolumeAdjustmentsError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CustomEqualizerProfile {
+    // Not renamed to volume_adjustments to keep backwards compatibility with old settings files
+    volume_offsets: Vec<i16>,
+}
+
+impl CustomEqualizerProfile {
+    /// `volume_adjustments` are in dB
+    pub fn new(volume_adjustments: &[f64]) -> Self {
+        // Convert f64 to i16 for backwards compatibility with serialization format
+        Self {
+            volume_offsets: volume_adjustments
+                .iter()
+                .map(|value| (value * 10.0).round() as i16)
+                .collect(),
+        }
+    }
+
+    /// In dB
+    pub fn volume_adjustments(&self) -> Arc<[f64]> {
+        self.volume_offsets
+            .iter()
+            .map(|adjustment| f64::from(*adjustment) / 10.0)
+            .collect()
+    }
+
+    /// The profile resampled to fit a device with `num_bands` bands
+    pub fn equalizer_configuration(
+        &self,
+        num_bands: usize,
+    ) -> Result<EqualizerConfiguration, VolumeAdjustmentsError> {
+        let volume_adjustments = VolumeAdjustments::new(self.volume_adjustments().iter().copied())?;
+        resample(
+            EqualizerConfiguration::new_custom_profile(volume_adjustments),
+            num_bands,
+        )
+    }
+}
+
+/// Profiles may have been created for a device with a different number of bands, so they are
+/// resampled to fit the device.
+pub(crate) fn resample(
+    equalizer_configuration: EqualizerConfiguration,
+    num_bands: usize,
+) -> Result<EqualizerConfiguration, VolumeAdjustmentsError> {
+    if VolumeAdjustments::VALID_NUMBER_OF_BANDS.contains(&num_bands) {
+        equalizer_configuration.resample(num_bands)
+    } else {
+        Ok(equalizer_configuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openscq30_lib::devices::standard::structures::{
+        EqualizerConfiguration, PresetEqualizerProfile,
+    };
+
+    use super::{resample, CustomEqualizerProfile};
+
+    #[test]
+    fn test_resamples_to_number_of_bands() {
+        let profile = CustomEqualizerProfile::new(&[0.0; 8]);
+        let equalizer_configuration = profile.equalizer_configuration(10).unwrap();
+        assert_eq!(
+            10,
+            equalizer_configuration
+                .volume_adjustments()
+                .adjustments()
+                .len()
+        );
+
+        let equalizer_configuration = resample(
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster),
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(PresetEqualizerProfile::BassBooster),
+            equalizer_configuration.preset_profile()
+        );
+        assert_eq!(
+            10,
+            equalizer_configuration
+                .volume_adjustments()
+                .adjustments()
+                .len()
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use openscq30_lib::devices::standard::structures::VolumeAdjustmentsError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("error reading {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("error writing {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("error parsing {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    VolumeAdjustments(#[from] VolumeAdjustmentsError),
+    #[error(transparent)]
+    Device(#[from] openscq30_lib::Error),
+}
@@ -0,0 +1,24 @@
+use std::{fs, io::Write, path::Path};
+
+use tempfile::NamedTempFile;
+
+/// Replaces `path` through a temporary file in the same directory, keeping its permissions, so that
+/// it is never seen partially written.
+pub fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let dir = path.parent().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} has no parent directory", path.display()),
+        )
+    })?;
+    fs::create_dir_all(dir)?;
+    let permissions = path.metadata().ok().map(|metadata| metadata.permissions());
+
+    let mut file = NamedTempFile::new_in(dir)?;
+    if let Some(permissions) = permissions {
+        fs::set_permissions(file.path(), permissions)?;
+    }
+    file.write_all(contents.as_bytes())?;
+    file.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+//! The config file shared by the GUI and the CLI, which holds custom equalizer profiles and quick
+//! presets. Both frontends use these types and [`activate_quick_preset`] so that they read and
+//! write the same format and a quick preset does the same thing no matter where it is activated.
+
+mod config;
+mod custom_equalizer_profile;
+mod error;
+mod file;
+mod quick_preset;
+
+pub use config::*;
+pub use custom_equalizer_profile::*;
+pub use error::*;
+pub use file::*;
+pub use quick_preset::*;
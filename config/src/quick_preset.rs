@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use openscq30_lib::{
+    api::device::Device,
+    device_profile::{NoiseCancelingModeType, TransparencyModeType},
+    devices::standard::structures::{
+        AmbientSoundMode, CustomNoiseCanceling, EqualizerConfiguration, NoiseCancelingMode,
+        PresetEqualizerProfile, SoundModes, TransparencyMode,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{custom_equalizer_profile::resample, Config};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default, Hash)]
+pub struct QuickPreset {
+    pub ambient_sound_mode: Option<AmbientSoundMode>,
+    pub transparency_mode: Option<TransparencyMode>,
+    pub noise_canceling_mode: Option<NoiseCancelingMode>,
+    pub custom_noise_canceling: Option<CustomNoiseCanceling>,
+    pub equalizer_profile: Option<PresetOrCustomEqualizerProfile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetOrCustomEqualizerProfile {
+    Preset(PresetEqualizerProfile),
+    Custom(Arc<str>),
+}
+
+/// Applies the settings in `quick_preset` to `device`, looking up custom equalizer profiles in
+/// `config`. A custom profile that has since been deleted is ignored.
+pub async fn activate_quick_preset(
+    device: &impl Device,
+    config: &Config,
+    quick_preset: &QuickPreset,
+) -> crate::Result<()> {
+    let device_state = device.state().await;
+    if let (Some(sound_modes), Some(sound_mode_profile)) = (
+        device_state.sound_modes,
+        device_state.device_features.sound_mode,
+    ) {
+        // Quick presets aren't tied to a device, so skip settings this device doesn't have rather
+        // than failing the whole preset
+        let has_custom_transparency =
+            sound_mode_profile.transparency_mode_type == TransparencyModeType::Custom;
+        let has_custom_noise_canceling =
+            sound_mode_profile.noise_canceling_mode_type == NoiseCancelingModeType::Custom;
+        let new_sound_modes = SoundModes {
+            ambient_sound_mode: quick_preset
+                .ambient_sound_mode
+                .unwrap_or(sound_modes.ambient_sound_mode),
+            transparency_mode: quick_preset
+                .transparency_mode
+                .filter(|_| has_custom_transparency)
+                .unwrap_or(sound_modes.transparency_mode),
+            noise_canceling_mode: quick_preset
+                .noise_canceling_mode
+                .filter(|mode| has_custom_noise_canceling || *mode != NoiseCancelingMode::Custom)
+                .unwrap_or(sound_modes.noise_canceling_mode),
+            custom_noise_canceling: quick_preset
+                .custom_noise_canceling
+                .filter(|_| has_custom_noise_canceling)
+                .unwrap_or(sound_modes.custom_noise_canceling),
+        };
+        device.set_sound_modes(new_sound_modes).await?;
+    }
+
+    let num_bands = device_state.device_features.num_equalizer_bands;
+    let equalizer_configuration = match &quick_preset.equalizer_profile {
+        Some(PresetOrCustomEqualizerProfile::Preset(profile)) => Some(resample(
+            EqualizerConfiguration::new_from_preset_profile(*profile),
+            num_bands,
+        )?),
+        Some(PresetOrCustomEqualizerProfile::Custom(name)) => config
+            .custom_profiles()
+            .get(name.as_ref())
+            .map(|profile| profile.equalizer_configuration(num_bands))
+            .transpose()?,
+        None => None,
+    };
+    if let Some(equalizer_configuration) = equalizer_configuration {
+        device
+            .set_equalizer_configuration(equalizer_configuration)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use macaddr::MacAddr6;
+    use openscq30_lib::{
+        api::device::{Device, DeviceRegistry},
+        demo::device::DemoDeviceRegistry,
+        devices::standard::structures::{AmbientSoundMode, PresetEqualizerProfile},
+        futures::TokioFutures,
+    };
+
+    use crate::{
+        activate_quick_preset, Config, CustomEqualizerProfile, PresetOrCustomEqualizerProfile,
+        QuickPreset,
+    };
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_activate_with_custom_profile() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let device = registry.device(MacAddr6::nil()).await.unwrap().unwrap();
+        let mut config = Config::default();
+        config.set_custom_profile(
+            "Bassy".to_string(),
+            CustomEqualizerProfile::new(&[6.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+        );
+        let quick_preset = QuickPreset {
+            ambient_sound_mode: Some(AmbientSoundMode::NoiseCanceling),
+            equalizer_profile: Some(PresetOrCustomEqualizerProfile::Custom(Arc::from("Bassy"))),
+            ..Default::default()
+        };
+
+        activate_quick_preset(device.as_ref(), &config, &quick_preset)
+            .await
+            .unwrap();
+
+        let state = device.state().await;
+        assert_eq!(
+            AmbientSoundMode::NoiseCanceling,
+            state.sound_modes.unwrap().ambient_sound_mode,
+        );
+        assert_eq!(
+            [6.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0].as_slice(),
+            state
+                .equalizer_configuration
+                .volume_adjustments()
+                .adjustments()
+                .as_ref(),
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_activate_skips_deleted_custom_profile() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let device = registry.device(MacAddr6::nil()).await.unwrap().unwrap();
+        let quick_preset = QuickPreset {
+            equalizer_profile: Some(PresetOrCustomEqualizerProfile::Custom(Arc::from("Gone"))),
+            ..Default::default()
+        };
+
+        activate_quick_preset(device.as_ref(), &Config::default(), &quick_preset)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(PresetEqualizerProfile::SoundcoreSignature),
+            device
+                .state()
+                .await
+                .equalizer_configuration
+                .preset_profile(),
+        );
+    }
+}
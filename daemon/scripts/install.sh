@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+script_path="$(readlink -f -- "$0")"
+script_dir="$(dirname -- "$script_path")"
+project_root="$script_dir/../.."
+
+install_prefix="$1"
+
+echo Installing binary
+install -Dm755 "$project_root/target/release/openscq30d" -t "$install_prefix/bin/"
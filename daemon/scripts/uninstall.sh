@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+install_prefix="$1"
+
+echo Removing binary
+rm "$install_prefix/bin/openscq30d" || true
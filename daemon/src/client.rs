@@ -0,0 +1,482 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::Path,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+};
+
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    api::{
+        connection::ConnectionStatus,
+        device::{Device, DeviceRegistry, GenericDeviceDescriptor},
+    },
+    bug_report::BugReport,
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, Gender,
+            HearId, SoundModes, SoundModesTypeTwo,
+        },
+    },
+    hear_id_history::HearIdHistory,
+    soundcore_device::device::DegradedMode,
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader, Lines},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+    sync::{oneshot, watch},
+};
+use uuid::Uuid;
+
+use crate::protocol::{
+    write_message, Event, Handshake, Request, RequestMessage, Response, ResponseResult,
+    ServerMessage, PROTOCOL_VERSION,
+};
+
+/// Connection to the daemon. Cloning it shares the same connection.
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    writer: tokio::sync::Mutex<OwnedWriteHalf>,
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<ResponseResult>>>,
+    subscriptions: Mutex<HashMap<MacAddr6, Subscription>>,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    state: watch::Sender<DeviceState>,
+    connection_status: watch::Sender<ConnectionStatus>,
+}
+
+impl DaemonClient {
+    /// Fails if the daemon isn't running or uses a different protocol version.
+    pub async fn connect(path: &Path) -> io::Result<Self> {
+        let stream = UnixStream::connect(path).await?;
+        let (reader, mut writer) = stream.into_split();
+        write_message(
+            &mut writer,
+            &Handshake {
+                protocol_version: PROTOCOL_VERSION,
+            },
+        )
+        .await?;
+        let mut lines = BufReader::new(reader).lines();
+        let handshake: Handshake =
+            serde_json::from_str(&lines.next_line().await?.ok_or(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "daemon closed the connection during the handshake",
+            ))?)?;
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "daemon uses protocol version {}, expected {PROTOCOL_VERSION}",
+                    handshake.protocol_version,
+                ),
+            ));
+        }
+
+        let inner = Arc::new(Inner {
+            writer: tokio::sync::Mutex::new(writer),
+            next_id: AtomicU64::new(0),
+            pending: Default::default(),
+            subscriptions: Default::default(),
+        });
+        tokio::spawn(read_messages(lines, Arc::downgrade(&inner)));
+        Ok(Self { inner })
+    }
+
+    pub async fn request(&self, request: Request) -> openscq30_lib::Result<Response> {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        lock(&self.inner.pending).insert(id, sender);
+
+        let result = write_message(
+            &mut *self.inner.writer.lock().await,
+            &RequestMessage { id, request },
+        )
+        .await;
+        if let Err(err) = result {
+            lock(&self.inner.pending).remove(&id);
+            return Err(openscq30_lib::Error::NotConnected {
+                source: Box::new(err),
+            });
+        }
+
+        match receiver.await {
+            Ok(ResponseResult::Ok(response)) => Ok(response),
+            Ok(ResponseResult::Err(details)) => Err(openscq30_lib::Error::Remote { details }),
+            Err(_) => Err(openscq30_lib::Error::NotConnected {
+                source: "daemon closed the connection".into(),
+            }),
+        }
+    }
+
+    /// Sends a setter request and applies the state it responds with, so that the change is
+    /// visible as soon as the setter returns rather than when the event arrives.
+    async fn set(&self, mac_address: MacAddr6, request: Request) -> openscq30_lib::Result<()> {
+        match self.request(request).await? {
+            Response::State(state) => {
+                if let Some(subscription) = lock(&self.inner.subscriptions).get(&mac_address) {
+                    update(&subscription.state, *state);
+                }
+                Ok(())
+            }
+            response => Err(unexpected_response(response)),
+        }
+    }
+}
+
+async fn read_messages(mut lines: Lines<BufReader<OwnedReadHalf>>, inner: Weak<Inner>) {
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                tracing::warn!("failed to read from daemon: {err}");
+                break;
+            }
+        };
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        let message = match serde_json::from_str::<ServerMessage>(&line) {
+            Ok(message) => message,
+            Err(err) => {
+                tracing::warn!("failed to parse message from daemon: {err}");
+                continue;
+            }
+        };
+        match message {
+            ServerMessage::Response { id, result } => {
+                // Set up the subscription before handing over the response so that events that
+                // arrive right after it aren't dropped
+                if let ResponseResult::Ok(Response::Connect(Some(device_info))) = &result {
+                    lock(&inner.subscriptions)
+                        .entry(device_info.mac_address)
+                        .and_modify(|subscription| {
+                            update(&subscription.state, device_info.state.to_owned());
+                            update(
+                                &subscription.connection_status,
+                                device_info.connection_status,
+                            );
+                        })
+                        .or_insert_with(|| Subscription {
+                            state: watch::channel(device_info.state.to_owned()).0,
+                            connection_status: watch::channel(device_info.connection_status).0,
+                        });
+                }
+                if let Some(sender) = lock(&inner.pending).remove(&id) {
+                    // The request may have been cancelled
+                    let _ = sender.send(result);
+                }
+            }
+            ServerMessage::Event { mac_address, event } => {
+                if let Some(subscription) = lock(&inner.subscriptions).get(&mac_address) {
+                    match event {
+                        Event::State(state) => update(&subscription.state, *state),
+                        Event::ConnectionStatus(connection_status) => {
+                            update(&subscription.connection_status, connection_status)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Dropping the senders fails any requests that are still waiting for a response
+    if let Some(inner) = inner.upgrade() {
+        lock(&inner.pending).clear();
+        for subscription in lock(&inner.subscriptions).values() {
+            update(
+                &subscription.connection_status,
+                ConnectionStatus::Disconnected,
+            );
+        }
+    }
+}
+
+/// Only notifies receivers if the value actually changed, since the response to a setter and the
+/// following event usually carry the same state.
+fn update<T: PartialEq>(sender: &watch::Sender<T>, value: T) {
+    sender.send_if_modified(|current| {
+        if *current == value {
+            false
+        } else {
+            *current = value;
+            true
+        }
+    });
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .expect("lock is never held across an await or while panicking")
+}
+
+fn unexpected_response(response: Response) -> openscq30_lib::Error {
+    openscq30_lib::Error::Other {
+        source: format!("unexpected response from daemon: {response:?}").into(),
+    }
+}
+
+/// [`DeviceRegistry`] whose devices are owned by the daemon.
+#[derive(Debug)]
+pub struct DaemonDeviceRegistry {
+    client: DaemonClient,
+}
+
+impl DaemonDeviceRegistry {
+    pub fn new(client: DaemonClient) -> Self {
+        Self { client }
+    }
+
+    pub async fn connect(path: &Path) -> io::Result<Self> {
+        Ok(Self::new(DaemonClient::connect(path).await?))
+    }
+}
+
+impl DeviceRegistry for DaemonDeviceRegistry {
+    type DeviceType = DaemonDevice;
+    type DescriptorType = GenericDeviceDescriptor;
+
+    async fn device_descriptors(&self) -> openscq30_lib::Result<Vec<Self::DescriptorType>> {
+        match self.client.request(Request::DeviceDescriptors).await? {
+            Response::DeviceDescriptors(descriptors) => Ok(descriptors
+                .into_iter()
+                .map(|descriptor| {
+                    GenericDeviceDescriptor::new(descriptor.name, descriptor.mac_address)
+                })
+                .collect()),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    async fn device(
+        &self,
+        mac_address: MacAddr6,
+    ) -> openscq30_lib::Result<Option<Rc<Self::DeviceType>>> {
+        let device_info = match self
+            .client
+            .request(Request::Connect { mac_address })
+            .await?
+        {
+            Response::Connect(Some(device_info)) => device_info,
+            Response::Connect(None) => return Ok(None),
+            response => return Err(unexpected_response(response)),
+        };
+        let subscriptions = lock(&self.client.inner.subscriptions);
+        let subscription = subscriptions
+            .get(&mac_address)
+            .expect("subscription is created before the connect response is passed on");
+        Ok(Some(Rc::new(DaemonDevice {
+            client: self.client.to_owned(),
+            name: device_info.name,
+            mac_address,
+            service_uuid: device_info.service_uuid,
+            degraded_mode: device_info.degraded_mode,
+            state_receiver: subscription.state.subscribe(),
+            connection_status_receiver: subscription.connection_status.subscribe(),
+        })))
+    }
+
+    async fn bug_report(&self, mac_address: MacAddr6) -> openscq30_lib::Result<Option<BugReport>> {
+        match self
+            .client
+            .request(Request::BugReport { mac_address })
+            .await?
+        {
+            Response::BugReport(report) => Ok(report.map(|report| *report)),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    async fn hear_id_history(&self) -> openscq30_lib::Result<HearIdHistory> {
+        match self.client.request(Request::HearIdHistory).await? {
+            Response::HearIdHistory(history) => Ok(*history),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> openscq30_lib::Result<()> {
+        match self
+            .client
+            .request(Request::LoadHearIdHistory {
+                history: Box::new(history),
+            })
+            .await?
+        {
+            Response::HearIdHistory(_) => Ok(()),
+            response => Err(unexpected_response(response)),
+        }
+    }
+}
+
+/// Device connected to by the daemon. Its state is kept up to date by the daemon's events.
+#[derive(Debug)]
+pub struct DaemonDevice {
+    client: DaemonClient,
+    name: String,
+    mac_address: MacAddr6,
+    service_uuid: Uuid,
+    degraded_mode: Option<DegradedMode>,
+    state_receiver: watch::Receiver<DeviceState>,
+    connection_status_receiver: watch::Receiver<ConnectionStatus>,
+}
+
+impl Device for DaemonDevice {
+    async fn subscribe_to_state_updates(&self) -> watch::Receiver<DeviceState> {
+        self.state_receiver.clone()
+    }
+
+    async fn mac_address(&self) -> openscq30_lib::Result<MacAddr6> {
+        Ok(self.mac_address)
+    }
+
+    fn service_uuid(&self) -> Uuid {
+        self.service_uuid
+    }
+
+    async fn name(&self) -> openscq30_lib::Result<String> {
+        Ok(self.name.to_owned())
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status_receiver.clone()
+    }
+
+    async fn state(&self) -> DeviceState {
+        self.state_receiver.borrow().to_owned()
+    }
+
+    async fn degraded_mode(&self) -> Option<DegradedMode> {
+        self.degraded_mode.to_owned()
+    }
+
+    async fn set_sound_modes(&self, sound_modes: SoundModes) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetSoundModes {
+                    mac_address: self.mac_address,
+                    sound_modes,
+                },
+            )
+            .await
+    }
+
+    async fn set_sound_modes_type_two(
+        &self,
+        sound_modes: SoundModesTypeTwo,
+    ) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetSoundModesTypeTwo {
+                    mac_address: self.mac_address,
+                    sound_modes,
+                },
+            )
+            .await
+    }
+
+    async fn set_ambient_sound_mode_cycle(
+        &self,
+        cycle: AmbientSoundModeCycle,
+    ) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetAmbientSoundModeCycle {
+                    mac_address: self.mac_address,
+                    cycle,
+                },
+            )
+            .await
+    }
+
+    async fn set_equalizer_configuration(
+        &self,
+        configuration: EqualizerConfiguration,
+    ) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetEqualizerConfiguration {
+                    mac_address: self.mac_address,
+                    configuration,
+                },
+            )
+            .await
+    }
+
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetDynamicRangeCompression {
+                    mac_address: self.mac_address,
+                    is_enabled,
+                },
+            )
+            .await
+    }
+
+    async fn set_hear_id(&self, hear_id: HearId) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetHearId {
+                    mac_address: self.mac_address,
+                    hear_id,
+                },
+            )
+            .await
+    }
+
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetGenderAndAgeRange {
+                    mac_address: self.mac_address,
+                    gender,
+                    age_range,
+                },
+            )
+            .await
+    }
+
+    async fn set_custom_button_model(
+        &self,
+        custom_button_model: CustomButtonModel,
+    ) -> openscq30_lib::Result<()> {
+        self.client
+            .set(
+                self.mac_address,
+                Request::SetCustomButtonModel {
+                    mac_address: self.mac_address,
+                    custom_button_model,
+                },
+            )
+            .await
+    }
+}
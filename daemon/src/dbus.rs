@@ -0,0 +1,823 @@
+//! Publishes devices on D-Bus so that desktop tooling can control them. The manager object at
+//! [`MANAGER_PATH`] lists and connects to devices, and each connected device gets its own object
+//! implementing [`DEVICE_INTERFACE`], with properties for its state, methods for its setters, and
+//! `PropertiesChanged` signals whenever its state changes.
+//!
+//! Enum values such as ambient sound modes and equalizer presets are passed as the same camelCase
+//! strings used in the CLI's json output. For devices with the second type of sound modes, the
+//! noise canceling mode is one of theirs, such as `adaptive` or `manual`. Settings with more structure than fits nicely in a D-Bus
+//! signature, such as the custom button model, are passed as json.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    rc::Rc,
+    sync::Arc,
+};
+
+use dbus::{
+    arg::{PropMap, RefArg, Variant},
+    channel::{Channel, MatchingReceiver, Sender},
+    message::{MatchRule, SignalArgs},
+    nonblock::{
+        stdintf::org_freedesktop_dbus::{PropertiesPropertiesChanged, RequestNameReply},
+        LocalConnection,
+    },
+    Message, MethodErr, Path,
+};
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    api::{
+        connection::ConnectionStatus,
+        device::{Device, DeviceDescriptor, DeviceRegistry},
+    },
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AgeRange, AmbientSoundModeCycle, Battery, CustomNoiseCanceling, EqualizerConfiguration,
+            Gender, SoundModes, SoundModesTypeTwo, VolumeAdjustments,
+        },
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+};
+
+use crate::devices::Devices;
+
+pub const BUS_NAME: &str = "com.oppzippy.OpenSCQ30";
+pub const MANAGER_PATH: &str = "/com/oppzippy/OpenSCQ30";
+pub const MANAGER_INTERFACE: &str = "com.oppzippy.OpenSCQ30.Manager1";
+pub const DEVICE_INTERFACE: &str = "com.oppzippy.OpenSCQ30.Device1";
+/// Errors from the device are named this followed by their
+/// [`ErrorCode`](openscq30_lib::ErrorCode), such as `com.oppzippy.OpenSCQ30.Error.TimedOut`.
+pub const ERROR_PREFIX: &str = "com.oppzippy.OpenSCQ30.Error";
+
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const INTROSPECTABLE_INTERFACE: &str = "org.freedesktop.DBus.Introspectable";
+
+/// Object path of the device with the given mac address, such as
+/// `/com/oppzippy/OpenSCQ30/dev_00_11_22_33_44_55`.
+pub fn device_path(mac_address: MacAddr6) -> String {
+    format!(
+        "{MANAGER_PATH}/dev_{}",
+        mac_address.to_string().replace(':', "_")
+    )
+}
+
+/// Publishes devices on the session bus, or on the bus at `address` if one is given, until the
+/// connection to the bus is lost. Must be run inside of a [`LocalSet`](tokio::task::LocalSet).
+pub async fn serve<T>(devices: Rc<Devices<T>>, address: Option<&str>) -> Result<(), Box<dyn Error>>
+where
+    T: DeviceRegistry + 'static,
+{
+    let channel = match address {
+        Some(address) => {
+            let mut channel = Channel::open_private(address)?;
+            channel.register()?;
+            channel
+        }
+        None => Channel::get_private(dbus::channel::BusType::Session)?,
+    };
+    let (resource, connection) = dbus_tokio::connection::from_channel::<LocalConnection>(channel)?;
+    let service = DbusService {
+        devices,
+        connection,
+        objects: Default::default(),
+    };
+    tokio::select! {
+        err = resource => Err(err.into()),
+        result = service.run() => result,
+    }
+}
+
+struct DbusService<T: DeviceRegistry> {
+    devices: Rc<Devices<T>>,
+    connection: Arc<LocalConnection>,
+    /// Published devices by object path
+    objects: RefCell<BTreeMap<String, Object<T::DeviceType>>>,
+}
+
+struct Object<DeviceType> {
+    device: Rc<DeviceType>,
+    name: String,
+    mac_address: MacAddr6,
+    /// Emits `PropertiesChanged` signals. Aborted when the object is replaced.
+    signal_task: JoinHandle<()>,
+}
+
+impl<DeviceType> Drop for Object<DeviceType> {
+    fn drop(&mut self) {
+        self.signal_task.abort();
+    }
+}
+
+impl<T> DbusService<T>
+where
+    T: DeviceRegistry + 'static,
+{
+    async fn run(self) -> Result<(), Box<dyn Error>> {
+        let reply = self
+            .connection
+            .request_name(BUS_NAME, false, true, true)
+            .await?;
+        if reply != RequestNameReply::PrimaryOwner {
+            return Err(format!("{BUS_NAME} is already owned by another process").into());
+        }
+        tracing::info!("published {BUS_NAME} on D-Bus");
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        self.connection.start_receive(
+            MatchRule::new_method_call(),
+            Box::new(move |message, _| sender.send(message).is_ok()),
+        );
+        let service = Rc::new(self);
+        while let Some(message) = receiver.recv().await {
+            // Calls are handled concurrently so that one slow device doesn't hold up the others
+            let service = service.to_owned();
+            tokio::task::spawn_local(async move {
+                let reply = match service.handle_method_call(&message).await {
+                    Ok(reply) => reply,
+                    Err(err) => err.to_message(&message),
+                };
+                if !message.get_no_reply() {
+                    // Only fails if the connection is gone, which ends the service anyway
+                    let _ = service.connection.send(reply);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn handle_method_call(&self, message: &Message) -> Result<Message, MethodErr> {
+        let path = message.path().ok_or_else(|| MethodErr::no_path(""))?;
+        let interface = message
+            .interface()
+            .ok_or_else(|| MethodErr::no_interface(""))?;
+        let member = message.member().ok_or_else(|| MethodErr::no_method(""))?;
+        tracing::debug!("method call {interface}.{member} on {path}");
+
+        match (&*interface, &*member) {
+            (INTROSPECTABLE_INTERFACE, "Introspect") => {
+                Ok(message.method_return().append1(self.introspect(&path)))
+            }
+            (PROPERTIES_INTERFACE, "Get") => {
+                let (interface, name): (&str, &str) = message.read2()?;
+                let mut properties = self.properties(&path, interface).await?;
+                let value = properties
+                    .remove(name)
+                    .ok_or_else(|| MethodErr::no_property(name))?;
+                Ok(message.method_return().append1(value))
+            }
+            (PROPERTIES_INTERFACE, "GetAll") => {
+                let interface: &str = message.read1()?;
+                Ok(message
+                    .method_return()
+                    .append1(self.properties(&path, interface).await?))
+            }
+            (PROPERTIES_INTERFACE, "Set") => {
+                let (_, name): (&str, &str) = message.read2()?;
+                Err(MethodErr::ro_property(name))
+            }
+            (MANAGER_INTERFACE, member) if &*path == MANAGER_PATH => {
+                self.handle_manager_call(message, member).await
+            }
+            (DEVICE_INTERFACE, member) => {
+                let mac_address = self
+                    .objects
+                    .borrow()
+                    .get(&*path)
+                    .map(|object| object.mac_address)
+                    .ok_or_else(|| MethodErr::no_path(&path))?;
+                self.handle_device_call(message, member, mac_address).await
+            }
+            _ => Err(MethodErr::no_method(&member)),
+        }
+    }
+
+    async fn handle_manager_call(
+        &self,
+        message: &Message,
+        member: &str,
+    ) -> Result<Message, MethodErr> {
+        match member {
+            "ListDevices" => {
+                let descriptors = self
+                    .devices
+                    .registry()
+                    .device_descriptors()
+                    .await
+                    .map_err(method_err)?;
+                let devices = descriptors
+                    .iter()
+                    .map(|descriptor| {
+                        (
+                            descriptor.name().to_owned(),
+                            descriptor.mac_address().to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                Ok(message.method_return().append1(devices))
+            }
+            "ConnectDevice" => {
+                let mac_address = message
+                    .read1::<&str>()?
+                    .parse::<MacAddr6>()
+                    .map_err(|err| MethodErr::invalid_arg(&err.to_string()))?;
+                let device = self
+                    .devices
+                    .connected_device(mac_address)
+                    .await
+                    .map_err(method_err)?;
+                let path = self.publish(mac_address, device).await?;
+                Ok(message.method_return().append1(Path::from(path)))
+            }
+            _ => Err(MethodErr::no_method(member)),
+        }
+    }
+
+    async fn handle_device_call(
+        &self,
+        message: &Message,
+        member: &str,
+        mac_address: MacAddr6,
+    ) -> Result<Message, MethodErr> {
+        // Reconnects if the device was disconnected since it was published
+        let device = self
+            .devices
+            .connected_device(mac_address)
+            .await
+            .map_err(method_err)?;
+        self.publish(mac_address, device.to_owned()).await?;
+
+        let result = match member {
+            "SetAmbientSoundMode" => {
+                let ambient_sound_mode = parse_enum(message.read1()?)?;
+                match current_sound_modes(device.as_ref()).await? {
+                    CurrentSoundModes::TypeOne(sound_modes) => {
+                        device
+                            .set_sound_modes(SoundModes {
+                                ambient_sound_mode,
+                                ..sound_modes
+                            })
+                            .await
+                    }
+                    CurrentSoundModes::TypeTwo(sound_modes) => {
+                        device
+                            .set_sound_modes_type_two(SoundModesTypeTwo {
+                                ambient_sound_mode,
+                                ..sound_modes
+                            })
+                            .await
+                    }
+                }
+            }
+            "SetNoiseCancelingMode" => {
+                let noise_canceling_mode = message.read1()?;
+                match current_sound_modes(device.as_ref()).await? {
+                    CurrentSoundModes::TypeOne(sound_modes) => {
+                        device
+                            .set_sound_modes(SoundModes {
+                                noise_canceling_mode: parse_enum(noise_canceling_mode)?,
+                                ..sound_modes
+                            })
+                            .await
+                    }
+                    CurrentSoundModes::TypeTwo(_) => {
+                        device
+                            .set_noise_canceling_mode_type_two(parse_enum(noise_canceling_mode)?)
+                            .await
+                    }
+                }
+            }
+            "SetTransparencyMode" => {
+                device
+                    .set_transparency_mode(parse_enum(message.read1()?)?)
+                    .await
+            }
+            "SetCustomNoiseCanceling" => {
+                let value: u8 = message.read1()?;
+                let custom_noise_canceling = CustomNoiseCanceling::try_new(value)
+                    .map_err(|err| MethodErr::invalid_arg(&err.to_string()))?;
+                device
+                    .set_custom_noise_canceling(custom_noise_canceling)
+                    .await
+            }
+            "SetEqualizerPreset" => {
+                device
+                    .set_equalizer_configuration(EqualizerConfiguration::new_from_preset_profile(
+                        parse_enum(message.read1()?)?,
+                    ))
+                    .await
+            }
+            "SetCustomEqualizer" => {
+                let volume_adjustments = VolumeAdjustments::new(message.read1::<Vec<f64>>()?)
+                    .map_err(|err| MethodErr::invalid_arg(&err.to_string()))?;
+                device
+                    .set_equalizer_configuration(EqualizerConfiguration::new_custom_profile(
+                        volume_adjustments,
+                    ))
+                    .await
+            }
+            "SetDynamicRangeCompression" => {
+                device.set_dynamic_range_compression(message.read1()?).await
+            }
+            "SetAmbientSoundModeCycle" => {
+                let (noise_canceling_mode, transparency_mode, normal_mode) = message.read3()?;
+                device
+                    .set_ambient_sound_mode_cycle(AmbientSoundModeCycle {
+                        noise_canceling_mode,
+                        transparency_mode,
+                        normal_mode,
+                    })
+                    .await
+            }
+            "SetSoundModesTypeTwo" => {
+                device
+                    .set_sound_modes_type_two(parse_json(message.read1()?)?)
+                    .await
+            }
+            "SetHearId" => device.set_hear_id(parse_json(message.read1()?)?).await,
+            "SetGenderAndAgeRange" => {
+                let (gender, age_range): (u8, u8) = message.read2()?;
+                device
+                    .set_gender_and_age_range(Gender::from(gender), AgeRange::from(age_range))
+                    .await
+            }
+            "SetCustomButtonModel" => {
+                device
+                    .set_custom_button_model(parse_json(message.read1()?)?)
+                    .await
+            }
+            _ => return Err(MethodErr::no_method(member)),
+        };
+        result.map_err(method_err)?;
+        Ok(message.method_return())
+    }
+
+    /// Publishes the device's object, replacing the existing one if the device has reconnected
+    /// since, and returns its path.
+    async fn publish(
+        &self,
+        mac_address: MacAddr6,
+        device: Rc<T::DeviceType>,
+    ) -> Result<String, MethodErr> {
+        let path = device_path(mac_address);
+        if let Some(object) = self.objects.borrow().get(&path) {
+            if Rc::ptr_eq(&object.device, &device) {
+                return Ok(path);
+            }
+        }
+
+        let name = device.name().await.map_err(method_err)?;
+        // Subscribe before taking the current values so that no changes are missed in between
+        let state_receiver = device.subscribe_to_state_updates().await;
+        let connection_status_receiver = device.connection_status();
+        let signal_task = tokio::task::spawn_local(emit_properties_changed(
+            self.connection.to_owned(),
+            path.to_owned(),
+            name.to_owned(),
+            mac_address,
+            state_receiver,
+            connection_status_receiver,
+        ));
+        tracing::info!("published {mac_address} at {path}");
+        self.objects.borrow_mut().insert(
+            path.to_owned(),
+            Object {
+                device,
+                name,
+                mac_address,
+                signal_task,
+            },
+        );
+        Ok(path)
+    }
+
+    async fn properties(&self, path: &str, interface: &str) -> Result<PropMap, MethodErr> {
+        match interface {
+            MANAGER_INTERFACE if path == MANAGER_PATH => {
+                let devices = self
+                    .objects
+                    .borrow()
+                    .keys()
+                    .map(|path| Path::from(path.to_owned()))
+                    .collect::<Vec<_>>();
+                let mut properties = PropMap::new();
+                properties.insert("Devices".to_owned(), Variant(Box::new(devices)));
+                Ok(properties)
+            }
+            DEVICE_INTERFACE => {
+                let (device, name, mac_address) = {
+                    let objects = self.objects.borrow();
+                    let object = objects.get(path).ok_or_else(|| MethodErr::no_path(path))?;
+                    (
+                        object.device.to_owned(),
+                        object.name.to_owned(),
+                        object.mac_address,
+                    )
+                };
+                let connection_status = *device.connection_status().borrow();
+                Ok(to_prop_map(device_properties(
+                    &name,
+                    mac_address,
+                    connection_status,
+                    &device.state().await,
+                )))
+            }
+            _ => Err(MethodErr::no_interface(interface)),
+        }
+    }
+
+    fn introspect(&self, path: &str) -> String {
+        let mut interfaces = String::new();
+        if path == MANAGER_PATH {
+            interfaces.push_str(MANAGER_INTROSPECTION);
+        } else if self.objects.borrow().contains_key(path) {
+            interfaces.push_str(DEVICE_INTROSPECTION);
+        }
+        if !interfaces.is_empty() {
+            interfaces.push_str(STANDARD_INTROSPECTION);
+        }
+
+        // Each object's ancestors need to list their children so that tools can find it
+        let prefix = if path == "/" {
+            "/".to_owned()
+        } else {
+            format!("{path}/")
+        };
+        let children = std::iter::once(MANAGER_PATH)
+            .chain(self.objects.borrow().keys().map(String::as_str))
+            .filter_map(|object_path| object_path.strip_prefix(&prefix))
+            .filter_map(|relative_path| relative_path.split('/').next())
+            .map(ToOwned::to_owned)
+            .collect::<BTreeSet<_>>();
+        let children = children
+            .iter()
+            .map(|child| format!("  <node name=\"{child}\"/>\n"))
+            .collect::<String>();
+
+        format!("{INTROSPECTION_HEADER}<node>\n{interfaces}{children}</node>\n")
+    }
+}
+
+/// Sends `PropertiesChanged` with only the properties that changed, until the device goes away.
+async fn emit_properties_changed(
+    connection: Arc<LocalConnection>,
+    path: String,
+    name: String,
+    mac_address: MacAddr6,
+    mut state_receiver: watch::Receiver<DeviceState>,
+    mut connection_status_receiver: watch::Receiver<ConnectionStatus>,
+) {
+    let properties =
+        |state_receiver: &mut watch::Receiver<DeviceState>,
+         connection_status_receiver: &mut watch::Receiver<ConnectionStatus>| {
+            device_properties(
+                &name,
+                mac_address,
+                *connection_status_receiver.borrow_and_update(),
+                &state_receiver.borrow_and_update(),
+            )
+        };
+    let mut previous = properties(&mut state_receiver, &mut connection_status_receiver);
+    loop {
+        tokio::select! {
+            result = state_receiver.changed() => if result.is_err() { break },
+            result = connection_status_receiver.changed() => if result.is_err() { break },
+        }
+        let current = properties(&mut state_receiver, &mut connection_status_receiver);
+        let changed = current
+            .iter()
+            .filter(|(name, value)| previous.get(*name) != Some(value))
+            .map(|(name, value)| (*name, value.to_owned()))
+            .collect::<BTreeMap<_, _>>();
+        if !changed.is_empty() {
+            let signal = PropertiesPropertiesChanged {
+                interface_name: DEVICE_INTERFACE.to_owned(),
+                changed_properties: to_prop_map(changed),
+                invalidated_properties: Vec::new(),
+            };
+            if connection
+                .send(signal.to_emit_message(&Path::from(path.as_str())))
+                .is_err()
+            {
+                break;
+            }
+        }
+        previous = current;
+    }
+}
+
+/// Property values are kept in this form rather than as [`Variant`]s so that they can be compared
+/// to find out which ones changed.
+#[derive(Debug, Clone, PartialEq)]
+enum PropertyValue {
+    String(String),
+    Bool(bool),
+    Byte(u8),
+    Bytes(Vec<u8>),
+    Bools(Vec<bool>),
+    Doubles(Vec<f64>),
+}
+
+impl PropertyValue {
+    fn into_variant(self) -> Variant<Box<dyn RefArg>> {
+        match self {
+            PropertyValue::String(value) => Variant(Box::new(value)),
+            PropertyValue::Bool(value) => Variant(Box::new(value)),
+            PropertyValue::Byte(value) => Variant(Box::new(value)),
+            PropertyValue::Bytes(value) => Variant(Box::new(value)),
+            PropertyValue::Bools(value) => Variant(Box::new(value)),
+            PropertyValue::Doubles(value) => Variant(Box::new(value)),
+        }
+    }
+}
+
+fn to_prop_map(properties: BTreeMap<&'static str, PropertyValue>) -> PropMap {
+    properties
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.into_variant()))
+        .collect::<HashMap<_, _>>()
+}
+
+/// Values of the device interface's properties. Settings that the device doesn't support are
+/// empty strings.
+fn device_properties(
+    name: &str,
+    mac_address: MacAddr6,
+    connection_status: ConnectionStatus,
+    state: &DeviceState,
+) -> BTreeMap<&'static str, PropertyValue> {
+    let batteries = match state.battery {
+        Battery::SingleBattery(battery) => vec![battery],
+        Battery::DualBattery(battery) => vec![battery.left, battery.right],
+    };
+    let sound_modes = state.sound_modes;
+    let sound_modes_type_two = state.sound_modes_type_two;
+    let equalizer_configuration = &state.equalizer_configuration;
+
+    BTreeMap::from([
+        ("Name", PropertyValue::String(name.to_owned())),
+        ("MacAddress", PropertyValue::String(mac_address.to_string())),
+        (
+            "Connected",
+            PropertyValue::Bool(connection_status == ConnectionStatus::Connected),
+        ),
+        (
+            "BatteryLevels",
+            PropertyValue::Bytes(batteries.iter().map(|battery| battery.level.0).collect()),
+        ),
+        (
+            "BatteryCharging",
+            PropertyValue::Bools(
+                batteries
+                    .iter()
+                    .map(|battery| bool::from(battery.is_charging))
+                    .collect(),
+            ),
+        ),
+        (
+            "AmbientSoundMode",
+            enum_property(
+                sound_modes
+                    .map(|sound_modes| sound_modes.ambient_sound_mode)
+                    .or(sound_modes_type_two.map(|sound_modes| sound_modes.ambient_sound_mode)),
+            ),
+        ),
+        (
+            "NoiseCancelingMode",
+            match sound_modes {
+                Some(sound_modes) => enum_property(Some(sound_modes.noise_canceling_mode)),
+                None => enum_property(
+                    sound_modes_type_two.map(|sound_modes| sound_modes.noise_canceling_mode),
+                ),
+            },
+        ),
+        (
+            "TransparencyMode",
+            enum_property(
+                sound_modes
+                    .map(|sound_modes| sound_modes.transparency_mode)
+                    .or(sound_modes_type_two.map(|sound_modes| sound_modes.transparency_mode)),
+            ),
+        ),
+        (
+            "CustomNoiseCanceling",
+            PropertyValue::Byte(
+                sound_modes
+                    .map(|sound_modes| sound_modes.custom_noise_canceling.value())
+                    .unwrap_or_default(),
+            ),
+        ),
+        (
+            "EqualizerPreset",
+            enum_property(equalizer_configuration.preset_profile()),
+        ),
+        (
+            "EqualizerVolumeAdjustments",
+            PropertyValue::Doubles(
+                equalizer_configuration
+                    .volume_adjustments()
+                    .adjustments()
+                    .to_vec(),
+            ),
+        ),
+        (
+            "DynamicRangeCompression",
+            PropertyValue::Bool(state.is_dynamic_range_compression_enabled),
+        ),
+        (
+            "State",
+            PropertyValue::String(
+                serde_json::to_string(state).expect("state should always be serializable"),
+            ),
+        ),
+    ])
+}
+
+fn enum_property(value: Option<impl Serialize>) -> PropertyValue {
+    PropertyValue::String(
+        value
+            .and_then(|value| serde_json::to_value(value).ok())
+            .and_then(|value| value.as_str().map(ToOwned::to_owned))
+            .unwrap_or_default(),
+    )
+}
+
+fn parse_enum<T: DeserializeOwned>(value: &str) -> Result<T, MethodErr> {
+    serde_json::from_value(serde_json::Value::String(value.to_owned()))
+        .map_err(|_| MethodErr::invalid_arg(value))
+}
+
+fn parse_json<T: DeserializeOwned>(value: &str) -> Result<T, MethodErr> {
+    serde_json::from_str(value).map_err(|err| MethodErr::invalid_arg(&err.to_string()))
+}
+
+/// Devices have one of the two types of sound modes, which share the ambient sound mode but have
+/// different noise canceling modes
+enum CurrentSoundModes {
+    TypeOne(SoundModes),
+    TypeTwo(SoundModesTypeTwo),
+}
+
+async fn current_sound_modes(device: &impl Device) -> Result<CurrentSoundModes, MethodErr> {
+    let state = device.state().await;
+    state
+        .sound_modes
+        .map(CurrentSoundModes::TypeOne)
+        .or(state.sound_modes_type_two.map(CurrentSoundModes::TypeTwo))
+        .ok_or_else(|| {
+            method_err(openscq30_lib::Error::FeatureNotSupported {
+                feature_name: "sound modes",
+            })
+        })
+}
+
+fn method_err(err: openscq30_lib::Error) -> MethodErr {
+    MethodErr::from((format!("{ERROR_PREFIX}.{:?}", err.code()), err.to_string()))
+}
+
+const INTROSPECTION_HEADER: &str = "<!DOCTYPE node PUBLIC \"-//freedesktop//DTD D-BUS Object Introspection 1.0//EN\"\n \"http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd\">\n";
+
+const STANDARD_INTROSPECTION: &str = r#"  <interface name="org.freedesktop.DBus.Introspectable">
+    <method name="Introspect">
+      <arg name="xml_data" type="s" direction="out"/>
+    </method>
+  </interface>
+  <interface name="org.freedesktop.DBus.Properties">
+    <method name="Get">
+      <arg name="interface_name" type="s" direction="in"/>
+      <arg name="property_name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="GetAll">
+      <arg name="interface_name" type="s" direction="in"/>
+      <arg name="properties" type="a{sv}" direction="out"/>
+    </method>
+    <method name="Set">
+      <arg name="interface_name" type="s" direction="in"/>
+      <arg name="property_name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="in"/>
+    </method>
+    <signal name="PropertiesChanged">
+      <arg name="interface_name" type="s"/>
+      <arg name="changed_properties" type="a{sv}"/>
+      <arg name="invalidated_properties" type="as"/>
+    </signal>
+  </interface>
+"#;
+
+const MANAGER_INTROSPECTION: &str = r#"  <interface name="com.oppzippy.OpenSCQ30.Manager1">
+    <method name="ListDevices">
+      <arg name="devices" type="a(ss)" direction="out"/>
+    </method>
+    <method name="ConnectDevice">
+      <arg name="mac_address" type="s" direction="in"/>
+      <arg name="device" type="o" direction="out"/>
+    </method>
+    <property name="Devices" type="ao" access="read"/>
+  </interface>
+"#;
+
+const DEVICE_INTROSPECTION: &str = r#"  <interface name="com.oppzippy.OpenSCQ30.Device1">
+    <method name="SetAmbientSoundMode">
+      <arg name="ambient_sound_mode" type="s" direction="in"/>
+    </method>
+    <method name="SetNoiseCancelingMode">
+      <arg name="noise_canceling_mode" type="s" direction="in"/>
+    </method>
+    <method name="SetTransparencyMode">
+      <arg name="transparency_mode" type="s" direction="in"/>
+    </method>
+    <method name="SetCustomNoiseCanceling">
+      <arg name="custom_noise_canceling" type="y" direction="in"/>
+    </method>
+    <method name="SetEqualizerPreset">
+      <arg name="preset" type="s" direction="in"/>
+    </method>
+    <method name="SetCustomEqualizer">
+      <arg name="volume_adjustments" type="ad" direction="in"/>
+    </method>
+    <method name="SetDynamicRangeCompression">
+      <arg name="is_enabled" type="b" direction="in"/>
+    </method>
+    <method name="SetAmbientSoundModeCycle">
+      <arg name="noise_canceling_mode" type="b" direction="in"/>
+      <arg name="transparency_mode" type="b" direction="in"/>
+      <arg name="normal_mode" type="b" direction="in"/>
+    </method>
+    <method name="SetSoundModesTypeTwo">
+      <arg name="json" type="s" direction="in"/>
+    </method>
+    <method name="SetHearId">
+      <arg name="json" type="s" direction="in"/>
+    </method>
+    <method name="SetGenderAndAgeRange">
+      <arg name="gender" type="y" direction="in"/>
+      <arg name="age_range" type="y" direction="in"/>
+    </method>
+    <method name="SetCustomButtonModel">
+      <arg name="json" type="s" direction="in"/>
+    </method>
+    <property name="Name" type="s" access="read"/>
+    <property name="MacAddress" type="s" access="read"/>
+    <property name="Connected" type="b" access="read"/>
+    <property name="BatteryLevels" type="ay" access="read"/>
+    <property name="BatteryCharging" type="ab" access="read"/>
+    <property name="AmbientSoundMode" type="s" access="read"/>
+    <property name="NoiseCancelingMode" type="s" access="read"/>
+    <property name="TransparencyMode" type="s" access="read"/>
+    <property name="CustomNoiseCanceling" type="y" access="read"/>
+    <property name="EqualizerPreset" type="s" access="read"/>
+    <property name="EqualizerVolumeAdjustments" type="ad" access="read"/>
+    <property name="DynamicRangeCompression" type="b" access="read"/>
+    <property name="State" type="s" access="read"/>
+  </interface>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use macaddr::MacAddr6;
+    use openscq30_lib::{
+        api::connection::ConnectionStatus,
+        devices::standard::{
+            state::DeviceState,
+            structures::{
+                AmbientSoundMode, NoiseCancelingModeTypeTwo, SoundModesTypeTwo, TransparencyMode,
+            },
+        },
+    };
+
+    use super::{device_properties, PropertyValue};
+
+    #[test]
+    fn sound_mode_properties_fall_back_to_type_two() {
+        let state = DeviceState {
+            sound_modes_type_two: Some(SoundModesTypeTwo {
+                ambient_sound_mode: AmbientSoundMode::NoiseCanceling,
+                transparency_mode: TransparencyMode::VocalMode,
+                noise_canceling_mode: NoiseCancelingModeTypeTwo::Manual,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let properties =
+            device_properties("Test", MacAddr6::nil(), ConnectionStatus::Connected, &state);
+        assert_eq!(
+            PropertyValue::String("noiseCanceling".to_owned()),
+            properties["AmbientSoundMode"],
+        );
+        assert_eq!(
+            PropertyValue::String("manual".to_owned()),
+            properties["NoiseCancelingMode"],
+        );
+        assert_eq!(
+            PropertyValue::String("vocalMode".to_owned()),
+            properties["TransparencyMode"],
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use macaddr::MacAddr6;
+use openscq30_lib::api::{
+    connection::ConnectionStatus,
+    device::{Device, DeviceRegistry},
+};
+
+/// Shares devices between everything the daemon serves so that each device is only connected to
+/// once, and keeps them connected after clients go away.
+pub struct Devices<T: DeviceRegistry> {
+    registry: T,
+    devices: RefCell<HashMap<MacAddr6, Rc<T::DeviceType>>>,
+}
+
+impl<T: DeviceRegistry> Devices<T> {
+    pub fn new(registry: T) -> Self {
+        Self {
+            registry,
+            devices: Default::default(),
+        }
+    }
+
+    pub fn registry(&self) -> &T {
+        &self.registry
+    }
+
+    /// Reuses the existing connection to the device if it is still connected.
+    pub async fn connect(
+        &self,
+        mac_address: MacAddr6,
+    ) -> openscq30_lib::Result<Option<Rc<T::DeviceType>>> {
+        let existing = self.devices.borrow().get(&mac_address).cloned();
+        if let Some(device) = existing {
+            if *device.connection_status().borrow() == ConnectionStatus::Connected {
+                return Ok(Some(device));
+            }
+        }
+
+        tracing::info!("connecting to {mac_address}");
+        let device = self.registry.device(mac_address).await?;
+        match &device {
+            Some(device) => self
+                .devices
+                .borrow_mut()
+                .insert(mac_address, device.to_owned()),
+            None => self.devices.borrow_mut().remove(&mac_address),
+        };
+        Ok(device)
+    }
+
+    /// Like [`connect`](Self::connect), but treats a missing device as an error.
+    pub async fn connected_device(
+        &self,
+        mac_address: MacAddr6,
+    ) -> openscq30_lib::Result<Rc<T::DeviceType>> {
+        self.connect(mac_address)
+            .await?
+            .ok_or_else(|| openscq30_lib::Error::DeviceNotFound {
+                source: format!("no device with mac address {mac_address}").into(),
+            })
+    }
+}
@@ -0,0 +1,28 @@
+//! `openscq30d` keeps connections to devices open so that short-lived clients such as the CLI
+//! don't have to reconnect every time they run. Clients talk to it over a Unix socket using the
+//! messages in [`protocol`].
+#![cfg(unix)]
+
+use std::path::PathBuf;
+
+pub mod client;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+pub mod dbus;
+pub mod devices;
+pub mod protocol;
+pub mod server;
+
+/// Overrides the default socket path for both the daemon and its clients.
+pub const SOCKET_PATH_ENV_VAR: &str = "OPENSCQ30_DAEMON_SOCKET";
+
+/// The socket in the user's runtime directory, falling back to the temp directory. The daemon
+/// restricts the socket to its own user, since the temp directory is usually shared.
+pub fn default_socket_path() -> PathBuf {
+    std::env::var_os(SOCKET_PATH_ENV_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::runtime_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("openscq30d.sock")
+        })
+}
@@ -0,0 +1,96 @@
+#[cfg(unix)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use clap::Parser;
+    use std::{os::unix::fs::PermissionsExt, rc::Rc};
+
+    use openscq30_daemon::{default_socket_path, devices::Devices, server::Server};
+    use openscq30_lib::{
+        api::backend::{Backend, BackendDeviceRegistry},
+        futures::TokioFutures,
+    };
+    use tokio::{net::UnixListener, task::LocalSet};
+
+    let args = Args::parse();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    LocalSet::new().block_on(&runtime, async {
+        tracing_subscriber::fmt()
+            .with_max_level(args.logging_level)
+            .with_writer(std::io::stderr)
+            .init();
+
+        let socket_path = args.socket.unwrap_or_else(default_socket_path);
+        if socket_path.exists() {
+            // A socket left behind by a daemon that didn't shut down cleanly can be replaced, but
+            // one that is still being listened on can't.
+            if tokio::net::UnixStream::connect(&socket_path).await.is_ok() {
+                return Err(format!(
+                    "another daemon is already listening on {}",
+                    socket_path.display(),
+                )
+                .into());
+            }
+            std::fs::remove_file(&socket_path)?;
+        }
+
+        let backend = match args.backend {
+            Some(backend) => backend,
+            None => Backend::from_env()?,
+        };
+        let registry = BackendDeviceRegistry::<TokioFutures>::new(
+            backend,
+            Some(runtime.handle().to_owned()),
+        )
+        .await?;
+        let listener = UnixListener::bind(&socket_path)?;
+        // The socket may end up in a shared directory such as /tmp when there is no runtime
+        // directory, so other users must not be able to control the user's devices through it
+        std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+        tracing::info!("listening on {}", socket_path.display());
+
+        let devices = Rc::new(Devices::new(registry));
+        let dbus = async {
+            #[cfg(all(feature = "dbus", target_os = "linux"))]
+            if args.dbus {
+                return openscq30_daemon::dbus::serve(devices.to_owned(), None).await;
+            }
+            std::future::pending().await
+        };
+
+        let result = tokio::select! {
+            result = Server::with_devices(devices.to_owned()).serve(listener) => result.map_err(Into::into),
+            result = dbus => result,
+            result = tokio::signal::ctrl_c() => result.map_err(Into::into),
+        };
+        std::fs::remove_file(&socket_path)?;
+        result
+    })
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("openscq30d is only supported on unix");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+#[derive(clap::Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Defaults to openscq30d.sock in the runtime directory, or the value of
+    /// OPENSCQ30_DAEMON_SOCKET if it is set
+    #[arg(short, long)]
+    socket: Option<std::path::PathBuf>,
+    #[arg(short, long, default_value_t = tracing::Level::INFO)]
+    logging_level: tracing::Level,
+    /// Where devices come from, either bluetooth or demo. Defaults to the value of
+    /// OPENSCQ30_BACKEND if it is set, otherwise bluetooth.
+    #[arg(long)]
+    backend: Option<openscq30_lib::api::backend::Backend>,
+    /// Also publish devices on the D-Bus session bus
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    #[arg(long)]
+    dbus: bool,
+}
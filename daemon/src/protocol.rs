@@ -0,0 +1,247 @@
+//! Each message is a single line of JSON. After connecting, the client sends a [`Handshake`] and
+//! the daemon replies with its own. If the protocol versions differ, the daemon closes the
+//! connection. Otherwise, the client sends [`RequestMessage`]s, which the daemon handles one at a
+//! time and answers with a [`ServerMessage::Response`] carrying the same id. A request that can't
+//! be parsed is answered with an invalid value error if its id can be read, and skipped otherwise.
+//! Once a client has
+//! connected to a device, the daemon also sends it a [`ServerMessage::Event`] whenever that
+//! device's state or connection status changes.
+
+use std::io;
+
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    api::connection::ConnectionStatus,
+    bug_report::BugReport,
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, Gender,
+            HearId, SoundModes, SoundModesTypeTwo,
+        },
+    },
+    hear_id_history::HearIdHistory,
+    soundcore_device::device::DegradedMode,
+    ErrorDetails,
+};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Must be incremented whenever a change is made that older clients or daemons can't understand.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Handshake {
+    pub protocol_version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMessage {
+    pub id: u64,
+    pub request: Request,
+}
+
+/// Mirrors [`Device`](openscq30_lib::api::device::Device) and
+/// [`DeviceRegistry`](openscq30_lib::api::device::DeviceRegistry).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "method",
+    content = "params",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Request {
+    /// Responds with [`Response::DeviceDescriptors`]
+    DeviceDescriptors,
+    /// Connects to the device unless the daemon already is, and subscribes the client to the
+    /// device's events. Responds with [`Response::Connect`].
+    Connect {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+    },
+    /// Responds with [`Response::State`]
+    State {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+    },
+    /// Responds with [`Response::BugReport`]
+    BugReport {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+    },
+    /// Responds with [`Response::HearIdHistory`]
+    HearIdHistory,
+    /// Merges `history` into the daemon's. Responds with [`Response::HearIdHistory`] containing
+    /// the merged history.
+    LoadHearIdHistory { history: Box<HearIdHistory> },
+    // The setters respond with [`Response::State`] containing the state after the change, so that
+    // the client doesn't have to wait for the event to see its own change.
+    SetSoundModes {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        sound_modes: SoundModes,
+    },
+    SetSoundModesTypeTwo {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        sound_modes: SoundModesTypeTwo,
+    },
+    SetAmbientSoundModeCycle {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        cycle: AmbientSoundModeCycle,
+    },
+    SetEqualizerConfiguration {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        configuration: EqualizerConfiguration,
+    },
+    SetDynamicRangeCompression {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        is_enabled: bool,
+    },
+    SetHearId {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        hear_id: HearId,
+    },
+    SetGenderAndAgeRange {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        gender: Gender,
+        age_range: AgeRange,
+    },
+    SetCustomButtonModel {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        custom_button_model: CustomButtonModel,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ServerMessage {
+    Response {
+        id: u64,
+        result: ResponseResult,
+    },
+    Event {
+        #[serde(with = "mac_address")]
+        mac_address: MacAddr6,
+        event: Event,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResponseResult {
+    Ok(Response),
+    Err(Box<ErrorDetails>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Response {
+    DeviceDescriptors(Vec<DeviceDescriptor>),
+    /// None if the device wasn't found
+    Connect(Option<Box<DeviceInfo>>),
+    State(Box<DeviceState>),
+    /// None if the device wasn't found
+    BugReport(Option<Box<BugReport>>),
+    HearIdHistory(Box<HearIdHistory>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceDescriptor {
+    pub name: String,
+    #[serde(with = "mac_address")]
+    pub mac_address: MacAddr6,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    pub name: String,
+    #[serde(with = "mac_address")]
+    pub mac_address: MacAddr6,
+    pub service_uuid: Uuid,
+    pub connection_status: ConnectionStatus,
+    pub state: DeviceState,
+    /// Doesn't change while connected, so it is only sent here
+    #[serde(default)]
+    pub degraded_mode: Option<DegradedMode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum Event {
+    State(Box<DeviceState>),
+    ConnectionStatus(ConnectionStatus),
+}
+
+/// Mac addresses are sent as strings such as `00:11:22:33:44:55` rather than as arrays of bytes.
+mod mac_address {
+    use macaddr::MacAddr6;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        mac_address: &MacAddr6,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(mac_address)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MacAddr6, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Writes the message followed by a newline.
+pub(crate) async fn write_message(
+    writer: &mut (impl AsyncWriteExt + Unpin),
+    message: &impl Serialize,
+) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+#[cfg(test)]
+mod tests {
+    use macaddr::MacAddr6;
+    use serde_json::json;
+
+    use super::{Request, RequestMessage};
+
+    #[test]
+    fn request_uses_camel_case() {
+        let message = RequestMessage {
+            id: 1,
+            request: Request::SetDynamicRangeCompression {
+                mac_address: MacAddr6::nil(),
+                is_enabled: true,
+            },
+        };
+        assert_eq!(
+            json!({
+                "id": 1,
+                "request": {
+                    "method": "setDynamicRangeCompression",
+                    "params": { "macAddress": "00:00:00:00:00:00", "isEnabled": true },
+                },
+            }),
+            serde_json::to_value(&message).unwrap(),
+        );
+    }
+}
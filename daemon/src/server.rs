@@ -0,0 +1,328 @@
+use std::{collections::HashMap, io, rc::Rc};
+
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    api::{
+        connection::ConnectionStatus,
+        device::{Device, DeviceDescriptor as _, DeviceRegistry},
+    },
+    devices::standard::state::DeviceState,
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::{mpsc, watch},
+    task::{AbortHandle, JoinSet},
+};
+
+use crate::{
+    devices::Devices,
+    protocol::{
+        write_message, DeviceDescriptor, DeviceInfo, Event, Handshake, Request, RequestMessage,
+        Response, ResponseResult, ServerMessage, PROTOCOL_VERSION,
+    },
+};
+
+/// Serves the protocol in [`crate::protocol`] to clients connecting to a Unix socket.
+pub struct Server<T: DeviceRegistry> {
+    devices: Rc<Devices<T>>,
+}
+
+impl<T> Server<T>
+where
+    T: DeviceRegistry + 'static,
+{
+    pub fn new(registry: T) -> Self {
+        Self::with_devices(Rc::new(Devices::new(registry)))
+    }
+
+    /// Shares devices with other frontends, such as the D-Bus service.
+    pub fn with_devices(devices: Rc<Devices<T>>) -> Self {
+        Self { devices }
+    }
+
+    /// Accepts clients until the listener fails. Devices aren't `Send`, so this must be run
+    /// inside of a [`LocalSet`](tokio::task::LocalSet).
+    pub async fn serve(self, listener: UnixListener) -> io::Result<()> {
+        let server = Rc::new(self);
+        let mut clients = JoinSet::new();
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tracing::debug!("client connected");
+            let server = server.to_owned();
+            clients.spawn_local(async move {
+                match server.handle_client(stream).await {
+                    Ok(()) => tracing::debug!("client disconnected"),
+                    Err(err) => tracing::warn!("client disconnected with error: {err}"),
+                }
+            });
+            // Clean up finished clients so that the set doesn't grow forever
+            while clients.try_join_next().is_some() {}
+        }
+    }
+
+    async fn handle_client(self: Rc<Self>, stream: UnixStream) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        let Some(line) = lines.next_line().await? else {
+            return Ok(());
+        };
+        let handshake: Handshake = serde_json::from_str(&line)?;
+        write_message(
+            &mut writer,
+            &Handshake {
+                protocol_version: PROTOCOL_VERSION,
+            },
+        )
+        .await?;
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "client uses protocol version {}, expected {PROTOCOL_VERSION}",
+                    handshake.protocol_version,
+                ),
+            ));
+        }
+
+        // Responses and events are both written by this task, since events are sent from their
+        // own tasks.
+        let (sender, mut receiver) = mpsc::unbounded_channel::<ServerMessage>();
+        let mut tasks = JoinSet::new();
+        tasks.spawn_local(async move {
+            while let Some(message) = receiver.recv().await {
+                if let Err(err) = write_message(&mut writer, &message).await {
+                    tracing::debug!("failed to write to client: {err}");
+                    break;
+                }
+            }
+        });
+
+        let mut client = Client {
+            sender,
+            tasks,
+            subscriptions: HashMap::new(),
+        };
+        while let Some(line) = lines.next_line().await? {
+            let message: RequestMessage = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(err) => {
+                    tracing::warn!("received malformed request: {err}");
+                    // Answer if the request can be identified, so the client isn't left waiting
+                    // for a response that will never come
+                    let id = serde_json::from_str::<serde_json::Value>(&line)
+                        .ok()
+                        .and_then(|value| value.get("id")?.as_u64());
+                    if let Some(id) = id {
+                        let error = openscq30_lib::Error::InvalidValue {
+                            name: "request",
+                            value: err.to_string(),
+                        };
+                        let message = ServerMessage::Response {
+                            id,
+                            result: ResponseResult::Err(Box::new(error.details())),
+                        };
+                        if client.sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+            };
+            tracing::debug!("received request: {message:?}");
+            let result = self.handle_request(message.request, &mut client).await;
+            let message = ServerMessage::Response {
+                id: message.id,
+                result: match result {
+                    Ok(response) => ResponseResult::Ok(response),
+                    Err(err) => ResponseResult::Err(Box::new(err.details())),
+                },
+            };
+            if client.sender.send(message).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_request(
+        &self,
+        request: Request,
+        client: &mut Client,
+    ) -> openscq30_lib::Result<Response> {
+        let response = match request {
+            Request::DeviceDescriptors => Response::DeviceDescriptors(
+                self.devices
+                    .registry()
+                    .device_descriptors()
+                    .await?
+                    .iter()
+                    .map(|descriptor| DeviceDescriptor {
+                        name: descriptor.name().to_owned(),
+                        mac_address: descriptor.mac_address(),
+                    })
+                    .collect(),
+            ),
+            Request::Connect { mac_address } => {
+                Response::Connect(self.subscribe(mac_address, client).await?)
+            }
+            Request::State { mac_address } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::BugReport { mac_address } => Response::BugReport(
+                self.devices
+                    .registry()
+                    .bug_report(mac_address)
+                    .await?
+                    .map(Box::new),
+            ),
+            Request::HearIdHistory => {
+                Response::HearIdHistory(Box::new(self.devices.registry().hear_id_history().await?))
+            }
+            Request::LoadHearIdHistory { history } => {
+                let registry = self.devices.registry();
+                registry.load_hear_id_history(*history).await?;
+                Response::HearIdHistory(Box::new(registry.hear_id_history().await?))
+            }
+            Request::SetSoundModes {
+                mac_address,
+                sound_modes,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_sound_modes(sound_modes).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetSoundModesTypeTwo {
+                mac_address,
+                sound_modes,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_sound_modes_type_two(sound_modes).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetAmbientSoundModeCycle { mac_address, cycle } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_ambient_sound_mode_cycle(cycle).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetEqualizerConfiguration {
+                mac_address,
+                configuration,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_equalizer_configuration(configuration).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetDynamicRangeCompression {
+                mac_address,
+                is_enabled,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_dynamic_range_compression(is_enabled).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetHearId {
+                mac_address,
+                hear_id,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_hear_id(hear_id).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetGenderAndAgeRange {
+                mac_address,
+                gender,
+                age_range,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_gender_and_age_range(gender, age_range).await?;
+                Response::State(Box::new(device.state().await))
+            }
+            Request::SetCustomButtonModel {
+                mac_address,
+                custom_button_model,
+            } => {
+                let device = self.devices.connected_device(mac_address).await?;
+                device.set_custom_button_model(custom_button_model).await?;
+                Response::State(Box::new(device.state().await))
+            }
+        };
+        Ok(response)
+    }
+
+    /// Connects to the device and starts sending its events to the client. If the client was
+    /// already subscribed to an older connection to the device, that subscription is replaced.
+    async fn subscribe(
+        &self,
+        mac_address: MacAddr6,
+        client: &mut Client,
+    ) -> openscq30_lib::Result<Option<Box<DeviceInfo>>> {
+        let Some(device) = self.devices.connect(mac_address).await? else {
+            return Ok(None);
+        };
+        // Subscribe before taking the current values so that no changes are missed in between
+        let mut state_receiver = device.subscribe_to_state_updates().await;
+        let mut connection_status_receiver = device.connection_status();
+        let device_info = DeviceInfo {
+            name: device.name().await?,
+            mac_address,
+            service_uuid: device.service_uuid(),
+            connection_status: *connection_status_receiver.borrow_and_update(),
+            state: state_receiver.borrow_and_update().to_owned(),
+            degraded_mode: device.degraded_mode().await,
+        };
+
+        let task = client.tasks.spawn_local(forward_events(
+            mac_address,
+            state_receiver,
+            connection_status_receiver,
+            client.sender.to_owned(),
+        ));
+        if let Some(previous_task) = client.subscriptions.insert(mac_address, task) {
+            previous_task.abort();
+        }
+        Ok(Some(Box::new(device_info)))
+    }
+}
+
+struct Client {
+    sender: mpsc::UnboundedSender<ServerMessage>,
+    /// Aborted when the client disconnects
+    tasks: JoinSet<()>,
+    subscriptions: HashMap<MacAddr6, AbortHandle>,
+}
+
+/// Sends state and connection status changes to the client until either the device or the
+/// client goes away.
+async fn forward_events(
+    mac_address: MacAddr6,
+    mut state_receiver: watch::Receiver<DeviceState>,
+    mut connection_status_receiver: watch::Receiver<ConnectionStatus>,
+    sender: mpsc::UnboundedSender<ServerMessage>,
+) {
+    loop {
+        let event = tokio::select! {
+            result = state_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                Event::State(Box::new(state_receiver.borrow_and_update().to_owned()))
+            }
+            result = connection_status_receiver.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                Event::ConnectionStatus(*connection_status_receiver.borrow_and_update())
+            }
+            _ = sender.closed() => break,
+        };
+        if sender
+            .send(ServerMessage::Event { mac_address, event })
+            .is_err()
+        {
+            break;
+        }
+    }
+}
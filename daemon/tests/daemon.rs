@@ -0,0 +1,191 @@
+#![cfg(unix)]
+
+use std::{future::Future, path::PathBuf};
+
+use macaddr::MacAddr6;
+use openscq30_daemon::{
+    client::DaemonDeviceRegistry,
+    protocol::{Handshake, ResponseResult, ServerMessage, PROTOCOL_VERSION},
+    server::Server,
+};
+use openscq30_lib::{
+    api::{
+        connection::ConnectionStatus,
+        device::{Device, DeviceDescriptor, DeviceRegistry},
+    },
+    demo::device::DemoDeviceRegistry,
+    devices::standard::structures::{
+        AmbientSoundMode, EqualizerConfiguration, SoundModes, VolumeAdjustments,
+    },
+    futures::TokioFutures,
+    ErrorCode,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    task::LocalSet,
+};
+
+/// Runs `test` with a daemon serving the demo device registry
+async fn with_daemon<F>(test: impl FnOnce(PathBuf) -> F)
+where
+    F: Future<Output = ()>,
+{
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("openscq30d.sock");
+    let listener = UnixListener::bind(&socket_path).unwrap();
+    LocalSet::new()
+        .run_until(async {
+            tokio::task::spawn_local(
+                Server::new(DemoDeviceRegistry::<TokioFutures>::new()).serve(listener),
+            );
+            test(socket_path).await;
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_list_and_connect() {
+    with_daemon(|socket_path| async move {
+        let registry = DaemonDeviceRegistry::connect(&socket_path).await.unwrap();
+        let descriptors = registry.device_descriptors().await.unwrap();
+        assert_eq!(1, descriptors.len());
+        assert_eq!("Demo Q30", descriptors[0].name());
+
+        let device = registry.device(MacAddr6::nil()).await.unwrap().unwrap();
+        assert_eq!("Demo Q30", device.name().await.unwrap());
+        assert_eq!(
+            ConnectionStatus::Connected,
+            *device.connection_status().borrow()
+        );
+        assert!(device.state().await.sound_modes.is_some());
+
+        let missing = MacAddr6::new(1, 2, 3, 4, 5, 6);
+        assert!(registry.device(missing).await.unwrap().is_none());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_changes_are_shared_between_clients() {
+    with_daemon(|socket_path| async move {
+        let first = DaemonDeviceRegistry::connect(&socket_path)
+            .await
+            .unwrap()
+            .device(MacAddr6::nil())
+            .await
+            .unwrap()
+            .unwrap();
+        let second = DaemonDeviceRegistry::connect(&socket_path)
+            .await
+            .unwrap()
+            .device(MacAddr6::nil())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut second_state_receiver = second.subscribe_to_state_updates().await;
+
+        let sound_modes = SoundModes {
+            ambient_sound_mode: AmbientSoundMode::Transparency,
+            ..first.state().await.sound_modes.unwrap()
+        };
+        first.set_sound_modes(sound_modes).await.unwrap();
+        // The setter's own client sees the change as soon as it returns
+        assert_eq!(Some(sound_modes), first.state().await.sound_modes);
+
+        second_state_receiver.changed().await.unwrap();
+        assert_eq!(
+            Some(sound_modes),
+            second_state_receiver.borrow().sound_modes
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_errors_keep_their_code() {
+    with_daemon(|socket_path| async move {
+        let registry = DaemonDeviceRegistry::connect(&socket_path).await.unwrap();
+        let device = registry.device(MacAddr6::nil()).await.unwrap().unwrap();
+        let err = device
+            .set_equalizer_configuration(EqualizerConfiguration::new_custom_profile(
+                VolumeAdjustments::new([0.0; 10]).unwrap(),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorCode::InvalidValue, err.code());
+        assert_eq!(
+            Some("number of equalizer bands"),
+            err.details().name.as_deref()
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_rejects_other_protocol_versions() {
+    with_daemon(|socket_path| async move {
+        let stream = UnixStream::connect(&socket_path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        writer
+            .write_all(b"{\"protocolVersion\":0}\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(reader).lines();
+
+        let handshake: Handshake =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(PROTOCOL_VERSION, handshake.protocol_version);
+        assert_eq!(None, lines.next_line().await.unwrap());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_answers_malformed_requests() {
+    with_daemon(|socket_path| async move {
+        let stream = UnixStream::connect(&socket_path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer
+            .write_all(format!("{{\"protocolVersion\":{PROTOCOL_VERSION}}}\n").as_bytes())
+            .await
+            .unwrap();
+        lines.next_line().await.unwrap().unwrap();
+
+        writer.write_all(b"not json\n").await.unwrap();
+        writer
+            .write_all(b"{\"id\":1,\"request\":{\"method\":\"noSuchRequest\"}}\n")
+            .await
+            .unwrap();
+        let response: ServerMessage =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let ServerMessage::Response {
+            id: 1,
+            result: ResponseResult::Err(details),
+        } = response
+        else {
+            panic!("expected an error response to request 1, got {response:?}");
+        };
+        assert_eq!(ErrorCode::InvalidValue, details.code);
+
+        // The client is still served afterwards
+        writer
+            .write_all(b"{\"id\":2,\"request\":{\"method\":\"deviceDescriptors\"}}\n")
+            .await
+            .unwrap();
+        let response: ServerMessage =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert!(
+            matches!(
+                response,
+                ServerMessage::Response {
+                    id: 2,
+                    result: ResponseResult::Ok(_)
+                }
+            ),
+            "{response:?}"
+        );
+    })
+    .await;
+}
@@ -0,0 +1,212 @@
+#![cfg(all(feature = "dbus", target_os = "linux"))]
+
+use std::{
+    future::Future,
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use dbus::{
+    arg::{PropMap, RefArg},
+    channel::Channel,
+    message::SignalArgs,
+    nonblock::{
+        stdintf::org_freedesktop_dbus::{Properties, PropertiesPropertiesChanged},
+        LocalConnection, Proxy,
+    },
+    Path,
+};
+use futures::StreamExt;
+use openscq30_daemon::{
+    dbus::{
+        device_path, BUS_NAME, DEVICE_INTERFACE, ERROR_PREFIX, MANAGER_INTERFACE, MANAGER_PATH,
+    },
+    devices::Devices,
+};
+use openscq30_lib::{demo::device::DemoDeviceRegistry, futures::TokioFutures};
+use tokio::task::LocalSet;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Private bus so that tests don't interfere with the user's session
+struct Bus {
+    process: Child,
+    address: String,
+}
+
+impl Bus {
+    fn start() -> Self {
+        let mut process = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("dbus-daemon must be installed to run the D-Bus tests");
+        let mut address = String::new();
+        BufReader::new(process.stdout.as_mut().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        Self {
+            process,
+            address: address.trim().to_owned(),
+        }
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn connect(address: &str) -> Arc<LocalConnection> {
+    let mut channel = Channel::open_private(address).unwrap();
+    channel.register().unwrap();
+    let (resource, connection) =
+        dbus_tokio::connection::from_channel::<LocalConnection>(channel).unwrap();
+    tokio::task::spawn_local(resource);
+    connection
+}
+
+/// Runs `test` with the demo registry published on a private bus
+async fn with_service<F>(test: impl FnOnce(Arc<LocalConnection>) -> F)
+where
+    F: Future<Output = ()>,
+{
+    let bus = Bus::start();
+    LocalSet::new()
+        .run_until(async {
+            let devices = Rc::new(Devices::new(DemoDeviceRegistry::<TokioFutures>::new()));
+            let address = bus.address.to_owned();
+            tokio::task::spawn_local(async move {
+                openscq30_daemon::dbus::serve(devices, Some(&address))
+                    .await
+                    .unwrap();
+            });
+
+            let connection = connect(&bus.address);
+            let bus_proxy = Proxy::new(
+                "org.freedesktop.DBus",
+                "/org/freedesktop/DBus",
+                TIMEOUT,
+                connection.to_owned(),
+            );
+            // Wait for the service to take its name
+            loop {
+                let (has_owner,): (bool,) = bus_proxy
+                    .method_call("org.freedesktop.DBus", "NameHasOwner", (BUS_NAME,))
+                    .await
+                    .unwrap();
+                if has_owner {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+
+            test(connection).await;
+        })
+        .await;
+}
+
+async fn connect_demo_device(connection: &Arc<LocalConnection>) -> Path<'static> {
+    let manager = Proxy::new(BUS_NAME, MANAGER_PATH, TIMEOUT, connection.to_owned());
+    let (path,): (Path<'static>,) = manager
+        .method_call(MANAGER_INTERFACE, "ConnectDevice", ("00:00:00:00:00:00",))
+        .await
+        .unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_lists_and_publishes_devices() {
+    with_service(|connection| async move {
+        let manager = Proxy::new(BUS_NAME, MANAGER_PATH, TIMEOUT, connection.to_owned());
+        let (devices,): (Vec<(String, String)>,) = manager
+            .method_call(MANAGER_INTERFACE, "ListDevices", ())
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![("Demo Q30".to_owned(), "00:00:00:00:00:00".to_owned())],
+            devices
+        );
+
+        let path = connect_demo_device(&connection).await;
+        assert_eq!(device_path(macaddr::MacAddr6::nil()), path.to_string());
+        let device = Proxy::new(BUS_NAME, path.to_owned(), TIMEOUT, connection.to_owned());
+        let name: String = device.get(DEVICE_INTERFACE, "Name").await.unwrap();
+        assert_eq!("Demo Q30", name);
+        let ambient_sound_mode: String = device
+            .get(DEVICE_INTERFACE, "AmbientSoundMode")
+            .await
+            .unwrap();
+        assert_eq!("normal", ambient_sound_mode);
+
+        let published: Vec<Path<'static>> =
+            manager.get(MANAGER_INTERFACE, "Devices").await.unwrap();
+        assert_eq!(vec![path], published);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_setters_emit_properties_changed() {
+    with_service(|connection| async move {
+        let path = connect_demo_device(&connection).await;
+        let rule = PropertiesPropertiesChanged::match_rule(None, Some(&path)).static_clone();
+        let (signal_match, mut signals) = connection
+            .add_match(rule)
+            .await
+            .unwrap()
+            .stream::<PropertiesPropertiesChanged>();
+
+        let device = Proxy::new(BUS_NAME, path, TIMEOUT, connection.to_owned());
+        device
+            .method_call::<(), _, _, _>(DEVICE_INTERFACE, "SetAmbientSoundMode", ("transparency",))
+            .await
+            .unwrap();
+
+        let (_, signal) = tokio::time::timeout(TIMEOUT, signals.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(DEVICE_INTERFACE, signal.interface_name);
+        assert_eq!(
+            Some("transparency"),
+            property(&signal.changed_properties, "AmbientSoundMode")
+        );
+        // Only properties that changed are included
+        assert!(!signal.changed_properties.contains_key("Name"));
+        connection.remove_match(signal_match.token()).await.unwrap();
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_errors_are_named_after_their_code() {
+    with_service(|connection| async move {
+        let path = connect_demo_device(&connection).await;
+        let device = Proxy::new(BUS_NAME, path, TIMEOUT, connection.to_owned());
+        let err = device
+            .method_call::<(), _, _, _>(DEVICE_INTERFACE, "SetCustomEqualizer", (vec![0f64; 10],))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            Some(format!("{ERROR_PREFIX}.InvalidValue").as_str()),
+            err.name()
+        );
+
+        let err = device
+            .method_call::<(), _, _, _>(DEVICE_INTERFACE, "SetAmbientSoundMode", ("loud",))
+            .await
+            .unwrap_err();
+        assert_eq!(Some("org.freedesktop.DBus.Error.InvalidArgs"), err.name());
+    })
+    .await;
+}
+
+fn property<'a>(properties: &'a PropMap, name: &str) -> Option<&'a str> {
+    properties.get(name).and_then(|value| value.0.as_str())
+}
@@ -1,4 +1,6 @@
 mod activate_quick_preset;
+mod compare_equalizer;
+mod create_bug_report;
 mod create_custom_equalizer_profile;
 mod create_quick_preset;
 mod delete_custom_equalizer_profile;
@@ -15,6 +17,7 @@ mod set_custom_button_model;
 mod set_custom_noise_canceling;
 mod set_device;
 mod set_equalizer_configuration;
+mod set_gender_and_age_range;
 mod set_hear_id;
 mod set_manual_noise_canceling;
 mod set_noise_canceling_mode;
@@ -26,19 +29,24 @@ mod state;
 use std::sync::Arc;
 
 pub use activate_quick_preset::*;
+pub use compare_equalizer::*;
+pub use create_bug_report::*;
 pub use create_custom_equalizer_profile::*;
 pub use create_quick_preset::*;
 pub use delete_custom_equalizer_profile::*;
 pub use delete_quick_preset::*;
 pub use import_custom_equalizer_profiles::*;
 use macaddr::MacAddr6;
-use openscq30_lib::devices::standard::{
-    state::DeviceState,
-    structures::{
-        AmbientSoundMode, AmbientSoundModeCycle, CustomButtonModel, CustomNoiseCanceling,
-        EqualizerConfiguration, HearId, ManualNoiseCanceling, NoiseCancelingMode,
-        NoiseCancelingModeTypeTwo, TransparencyMode,
+use openscq30_lib::{
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AgeRange, AmbientSoundMode, AmbientSoundModeCycle, CustomButtonModel,
+            CustomNoiseCanceling, EqualizerConfiguration, Gender, HearId, ManualNoiseCanceling,
+            NoiseCancelingMode, NoiseCancelingModeTypeTwo, TransparencyMode,
+        },
     },
+    equalizer_comparison::ComparisonSide,
 };
 pub use refresh_custom_equalizer_profiles::*;
 pub use refresh_devices::*;
@@ -51,6 +59,7 @@ pub use set_custom_button_model::*;
 pub use set_custom_noise_canceling::*;
 pub use set_device::*;
 pub use set_equalizer_configuration::*;
+pub use set_gender_and_age_range::*;
 pub use set_hear_id::*;
 pub use set_manual_noise_canceling::*;
 pub use set_noise_canceling_mode::*;
@@ -71,6 +80,8 @@ pub enum StateUpdate {
     SetCustomEqualizerProfiles(Vec<GlibCustomEqualizerProfile>),
     AddToast(String),
     SetQuickPresets(Vec<GlibNamedQuickPresetValue>),
+    SetEqualizerComparisonStatus(EqualizerComparisonStatus),
+    CopyToClipboard(String),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -89,6 +100,7 @@ pub enum Action {
     ActivateQuickPreset(GlibNamedQuickPresetValue),
     DeleteQuickPreset(Arc<str>),
     SetHearId(HearId),
+    SetGenderAndAgeRange(Gender, AgeRange),
     SetCustomButtonModel(CustomButtonModel),
     SetAmbientSoundModeCycle(AmbientSoundModeCycle),
     ImportCustomEqualizerProfiles {
@@ -99,4 +111,9 @@ pub enum Action {
     SetManualNoiseCanceling(ManualNoiseCanceling),
     SetAmbientSoundModeTypeTwo(AmbientSoundMode),
     SetTransparencyModeTypeTwo(TransparencyMode),
+    SetEqualizerComparisonSide(ComparisonSide, EqualizerConfiguration),
+    ToggleEqualizerComparison,
+    SetEqualizerComparisonBlind(bool),
+    PreferEqualizerComparisonSide,
+    CreateBugReport(MacAddr6),
 }
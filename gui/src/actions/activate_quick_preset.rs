@@ -1,13 +1,7 @@
 use anyhow::Context;
-use openscq30_lib::{
-    api::device::{Device, DeviceRegistry},
-    devices::standard::{
-        state::DeviceState,
-        structures::{EqualizerConfiguration, SoundModes, VolumeAdjustments},
-    },
-};
+use openscq30_lib::api::device::DeviceRegistry;
 
-use crate::settings::{Config, PresetOrCustomEqualizerProfile, QuickPreset, SettingsFile};
+use crate::settings::{Config, QuickPreset, SettingsFile};
 
 use super::State;
 
@@ -20,79 +14,12 @@ where
     T: DeviceRegistry + 'static,
 {
     if let Some(device) = state.selected_device() {
-        let device_state = device.state().await;
-        set_sound_modes_from_quick_preset(device.as_ref(), &device_state, quick_preset).await?;
-        set_equalizer_configuration_from_quick_preset(device.as_ref(), settings_file, quick_preset)
-            .await?;
-    }
-    Ok(())
-}
-
-async fn set_sound_modes_from_quick_preset(
-    device: &impl Device,
-    device_state: &DeviceState,
-    quick_preset: &QuickPreset,
-) -> anyhow::Result<()> {
-    if let Some(sound_modes) = device_state.sound_modes {
-        let new_sound_modes = SoundModes {
-            ambient_sound_mode: quick_preset
-                .ambient_sound_mode
-                .unwrap_or(sound_modes.ambient_sound_mode),
-            transparency_mode: quick_preset
-                .transparency_mode
-                .unwrap_or(sound_modes.transparency_mode),
-            noise_canceling_mode: quick_preset
-                .noise_canceling_mode
-                .unwrap_or(sound_modes.noise_canceling_mode),
-            custom_noise_canceling: quick_preset
-                .custom_noise_canceling
-                .unwrap_or(sound_modes.custom_noise_canceling),
-        };
-        device
-            .set_sound_modes(new_sound_modes)
-            .await
-            .context("set sound modes")?;
-    }
-    Ok(())
-}
-
-async fn set_equalizer_configuration_from_quick_preset(
-    device: &impl Device,
-    settings_file: &SettingsFile<Config>,
-    quick_preset: &QuickPreset,
-) -> anyhow::Result<()> {
-    let new_equalizer_configuration = match &quick_preset.equalizer_profile {
-        Some(PresetOrCustomEqualizerProfile::Preset(profile)) => {
-            Some(EqualizerConfiguration::new_from_preset_profile(*profile))
-        }
-        Some(PresetOrCustomEqualizerProfile::Custom(custom_profile_name)) => {
-            let profile = settings_file
-                .get(|config| {
-                    config
-                        .custom_profiles()
-                        .get(custom_profile_name.as_ref())
-                        .cloned()
-                })
-                .context("get custom eq profile from config")?;
-            if let Some(profile) = profile {
-                let volume_adjustments =
-                    VolumeAdjustments::new(profile.volume_adjustments().iter().cloned())
-                        .context("parse volume adjustments of custom eq profile")?;
-                Some(EqualizerConfiguration::new_custom_profile(
-                    volume_adjustments,
-                ))
-            } else {
-                None
-            }
-        }
-        None => None,
-    };
-
-    if let Some(new_equalizer_configuration) = new_equalizer_configuration {
-        device
-            .set_equalizer_configuration(new_equalizer_configuration)
+        let config = settings_file
+            .get(Clone::clone)
+            .context("get config for custom eq profiles")?;
+        openscq30_config::activate_quick_preset(device.as_ref(), &config, quick_preset)
             .await
-            .context("set equalizer configuration")?;
+            .context("activate quick preset")?;
     }
     Ok(())
 }
@@ -101,11 +28,16 @@ async fn set_equalizer_configuration_from_quick_preset(
 mod tests {
     use std::rc::Rc;
 
-    use openscq30_lib::devices::standard::{
-        state::DeviceState,
-        structures::{
-            AmbientSoundMode, CustomNoiseCanceling, EqualizerConfiguration, NoiseCancelingMode,
-            PresetEqualizerProfile, SoundModes, TransparencyMode, VolumeAdjustments,
+    use openscq30_lib::{
+        device_profile::{
+            DeviceFeatures, NoiseCancelingModeType, SoundModeProfile, TransparencyModeType,
+        },
+        devices::standard::{
+            state::DeviceState,
+            structures::{
+                AmbientSoundMode, CustomNoiseCanceling, EqualizerConfiguration, NoiseCancelingMode,
+                PresetEqualizerProfile, SoundModes, TransparencyMode, VolumeAdjustments,
+            },
         },
     };
     use uuid::Uuid;
@@ -130,6 +62,13 @@ mod tests {
         let mut device = MockDevice::new();
         device.expect_service_uuid().return_const(Uuid::default());
         device.expect_state().return_const(DeviceState {
+            device_features: DeviceFeatures {
+                sound_mode: Some(SoundModeProfile {
+                    noise_canceling_mode_type: NoiseCancelingModeType::Custom,
+                    transparency_mode_type: TransparencyModeType::Custom,
+                }),
+                ..Default::default()
+            },
             sound_modes: Some(SoundModes {
                 ambient_sound_mode: AmbientSoundMode::Normal,
                 transparency_mode: TransparencyMode::FullyTransparent,
@@ -166,6 +105,60 @@ mod tests {
             .unwrap();
     }
 
+    #[gtk::test]
+    async fn test_skips_unsupported_sound_modes() {
+        crate::load_resources();
+        let registry = MockDeviceRegistry::new();
+        let (state, _sender) = State::new(registry);
+
+        let sound_modes = SoundModes {
+            ambient_sound_mode: AmbientSoundMode::Normal,
+            transparency_mode: TransparencyMode::VocalMode,
+            noise_canceling_mode: NoiseCancelingMode::Transport,
+            custom_noise_canceling: CustomNoiseCanceling::new(0),
+        };
+        let mut device = MockDevice::new();
+        device.expect_service_uuid().return_const(Uuid::default());
+        device.expect_state().return_const(DeviceState {
+            device_features: DeviceFeatures {
+                sound_mode: Some(SoundModeProfile {
+                    noise_canceling_mode_type: NoiseCancelingModeType::Basic,
+                    transparency_mode_type: TransparencyModeType::Basic,
+                }),
+                ..Default::default()
+            },
+            sound_modes: Some(sound_modes),
+            ..Default::default()
+        });
+        device
+            .expect_set_sound_modes()
+            .withf(move |new_sound_modes| {
+                new_sound_modes
+                    == &SoundModes {
+                        ambient_sound_mode: AmbientSoundMode::NoiseCanceling,
+                        ..sound_modes
+                    }
+            })
+            .once()
+            .returning(|_| Ok(()));
+        *state.selected_device.borrow_mut() = Some(Rc::new(device));
+
+        let dir = tempfile::tempdir().unwrap();
+        let settings_file = SettingsFile::new(dir.path().join("config.toml"));
+
+        let quick_preset = QuickPreset {
+            ambient_sound_mode: Some(AmbientSoundMode::NoiseCanceling),
+            transparency_mode: Some(TransparencyMode::FullyTransparent),
+            noise_canceling_mode: Some(NoiseCancelingMode::Custom),
+            custom_noise_canceling: Some(CustomNoiseCanceling::ADAPTIVE),
+            ..Default::default()
+        };
+
+        activate_quick_preset(&state, &settings_file, &quick_preset)
+            .await
+            .unwrap();
+    }
+
     #[gtk::test]
     async fn test_set_preset_equalizer_profile() {
         crate::load_resources();
@@ -0,0 +1,256 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use openscq30_lib::{
+    api::device::{Device, DeviceRegistry},
+    devices::standard::structures::EqualizerConfiguration,
+    equalizer_comparison::{ComparisonSide, ComparisonTally, EqualizerComparison},
+};
+
+use crate::gtk_futures::GtkFutures;
+
+use super::{State, StateUpdate};
+
+pub struct EqualizerComparisonState<DeviceType>
+where
+    DeviceType: Device,
+{
+    a: Option<EqualizerConfiguration>,
+    b: Option<EqualizerConfiguration>,
+    is_blind: bool,
+    comparison: Option<Rc<EqualizerComparison<DeviceType, GtkFutures>>>,
+}
+
+impl<DeviceType> Default for EqualizerComparisonState<DeviceType>
+where
+    DeviceType: Device,
+{
+    fn default() -> Self {
+        Self {
+            a: None,
+            b: None,
+            is_blind: false,
+            comparison: None,
+        }
+    }
+}
+
+impl<DeviceType> EqualizerComparisonState<DeviceType>
+where
+    DeviceType: Device,
+{
+    pub fn status(&self) -> EqualizerComparisonStatus {
+        EqualizerComparisonStatus {
+            has_a: self.a.is_some(),
+            has_b: self.b.is_some(),
+            is_blind: self.is_blind,
+            active_side: self
+                .comparison
+                .as_ref()
+                .and_then(|comparison| comparison.active_side()),
+            tally: self
+                .comparison
+                .as_ref()
+                .map(|comparison| comparison.tally())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EqualizerComparisonStatus {
+    pub has_a: bool,
+    pub has_b: bool,
+    pub is_blind: bool,
+    /// None if there is nothing to compare yet or the comparison is blind
+    pub active_side: Option<ComparisonSide>,
+    pub tally: ComparisonTally,
+}
+
+/// Saves `equalizer_configuration` as one side of the comparison. Once both sides are saved, the
+/// comparison starts on the side that was just saved, since that is what the device is already
+/// playing. Saving a side again starts a new comparison, since preferences recorded for the old
+/// curve no longer apply.
+pub async fn set_equalizer_comparison_side<T>(
+    state: &State<T>,
+    side: ComparisonSide,
+    equalizer_configuration: EqualizerConfiguration,
+) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let device = state
+        .selected_device()
+        .ok_or_else(|| anyhow!("no device is selected"))?;
+    let comparison = {
+        let mut comparison_state = state.equalizer_comparison.borrow_mut();
+        match side {
+            ComparisonSide::A => comparison_state.a = Some(equalizer_configuration),
+            ComparisonSide::B => comparison_state.b = Some(equalizer_configuration),
+        }
+        comparison_state.comparison = match (&comparison_state.a, &comparison_state.b) {
+            (Some(a), Some(b)) => {
+                let comparison = EqualizerComparison::new(device, a.to_owned(), b.to_owned());
+                comparison.set_blind(comparison_state.is_blind);
+                Some(Rc::new(comparison))
+            }
+            _ => None,
+        };
+        comparison_state.comparison.to_owned()
+    };
+    if let Some(comparison) = comparison {
+        if comparison.is_blind() {
+            comparison.shuffle().await?;
+        } else {
+            comparison.select(side).await?;
+        }
+    }
+    send_status(state)
+}
+
+pub async fn toggle_equalizer_comparison<T>(state: &State<T>) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let comparison = comparison(state)?;
+    comparison.toggle().await?;
+    send_status(state)
+}
+
+pub async fn set_equalizer_comparison_blind<T>(
+    state: &State<T>,
+    is_blind: bool,
+) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let comparison = {
+        let mut comparison_state = state.equalizer_comparison.borrow_mut();
+        comparison_state.is_blind = is_blind;
+        comparison_state.comparison.to_owned()
+    };
+    if let Some(comparison) = comparison {
+        comparison.set_blind(is_blind);
+        // Otherwise the side that was playing before going blind is known
+        if is_blind {
+            comparison.shuffle().await?;
+        }
+    }
+    send_status(state)
+}
+
+/// Records a preference for the side that is currently playing. In blind mode, the next trial is
+/// started right away.
+pub async fn prefer_equalizer_comparison_side<T>(state: &State<T>) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let comparison = comparison(state)?;
+    comparison.prefer_active();
+    if comparison.is_blind() {
+        comparison.shuffle().await?;
+    }
+    send_status(state)
+}
+
+fn comparison<T>(
+    state: &State<T>,
+) -> anyhow::Result<Rc<EqualizerComparison<T::DeviceType, GtkFutures>>>
+where
+    T: DeviceRegistry + 'static,
+{
+    state
+        .equalizer_comparison
+        .borrow()
+        .comparison
+        .to_owned()
+        .ok_or_else(|| anyhow!("both sides must be saved before comparing"))
+}
+
+fn send_status<T>(state: &State<T>) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let status = state.equalizer_comparison.borrow().status();
+    state
+        .state_update_sender
+        .send(StateUpdate::SetEqualizerComparisonStatus(status))
+        .map_err(|err| anyhow!("{err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use mockall::predicate;
+    use openscq30_lib::{
+        devices::standard::structures::{EqualizerConfiguration, PresetEqualizerProfile},
+        equalizer_comparison::{ComparisonSide, ComparisonTally},
+    };
+
+    use crate::{
+        actions::{State, StateUpdate},
+        mock::{MockDevice, MockDeviceRegistry},
+    };
+
+    use super::{
+        prefer_equalizer_comparison_side, set_equalizer_comparison_side,
+        toggle_equalizer_comparison, EqualizerComparisonStatus,
+    };
+
+    #[gtk::test]
+    async fn it_switches_between_saved_sides() {
+        crate::load_resources();
+        let registry = MockDeviceRegistry::new();
+        let (state, mut receiver) = State::new(registry);
+        let a = EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::Acoustic);
+        let b =
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster);
+        let mut selected_device = MockDevice::new();
+        selected_device
+            .expect_set_equalizer_configuration()
+            .once()
+            .with(predicate::eq(b.to_owned()))
+            .return_once(|_equalizer_configuration| Ok(()));
+        selected_device
+            .expect_set_equalizer_configuration()
+            .once()
+            .with(predicate::eq(a.to_owned()))
+            .return_once(|_equalizer_configuration| Ok(()));
+        *state.selected_device.borrow_mut() = Some(Rc::new(selected_device));
+
+        set_equalizer_comparison_side(&state, ComparisonSide::A, a)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(StateUpdate::SetEqualizerComparisonStatus(
+                EqualizerComparisonStatus {
+                    has_a: true,
+                    ..Default::default()
+                }
+            )),
+            receiver.recv().await,
+        );
+        assert!(toggle_equalizer_comparison(&state).await.is_err());
+
+        set_equalizer_comparison_side(&state, ComparisonSide::B, b)
+            .await
+            .unwrap();
+        toggle_equalizer_comparison(&state).await.unwrap();
+        prefer_equalizer_comparison_side(&state).await.unwrap();
+        receiver.recv().await.unwrap();
+        receiver.recv().await.unwrap();
+        assert_eq!(
+            Some(StateUpdate::SetEqualizerComparisonStatus(
+                EqualizerComparisonStatus {
+                    has_a: true,
+                    has_b: true,
+                    is_blind: false,
+                    active_side: Some(ComparisonSide::A),
+                    tally: ComparisonTally { a: 1, b: 0 },
+                }
+            )),
+            receiver.recv().await,
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::anyhow;
+use macaddr::MacAddr6;
+use openscq30_lib::api::device::DeviceRegistry;
+
+use super::{State, StateUpdate};
+
+pub async fn create_bug_report<T>(state: &State<T>, mac_address: MacAddr6) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let report = state
+        .registry
+        .bug_report(mac_address)
+        .await?
+        .ok_or_else(|| anyhow!("device not found: {mac_address}"))?;
+    state
+        .state_update_sender
+        .send(StateUpdate::CopyToClipboard(report.to_string()))
+        .map_err(|err| anyhow!("{err}"))?;
+    state
+        .state_update_sender
+        .send(StateUpdate::AddToast(
+            "Bug report copied to clipboard".to_string(),
+        ))
+        .map_err(|err| anyhow!("{err}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use macaddr::MacAddr6;
+    use mockall::predicate;
+    use openscq30_lib::{bug_report::BugReport, devices::standard::state::DeviceState};
+
+    use crate::{
+        actions::{State, StateUpdate},
+        mock::MockDeviceRegistry,
+    };
+
+    use super::create_bug_report;
+
+    #[gtk::test]
+    async fn it_copies_report_to_clipboard() {
+        crate::load_resources();
+        let mut registry = MockDeviceRegistry::new();
+        let report = BugReport::from_state(&DeviceState::default());
+        let expected_text = report.to_string();
+        registry
+            .expect_bug_report()
+            .once()
+            .with(predicate::eq(MacAddr6::nil()))
+            .return_once(|_| Ok(Some(report)));
+        let (state, mut receiver) = State::new(registry);
+
+        create_bug_report(&state, MacAddr6::nil()).await.unwrap();
+
+        assert_eq!(
+            StateUpdate::CopyToClipboard(expected_text),
+            receiver.recv().await.unwrap()
+        );
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            StateUpdate::AddToast(_)
+        ));
+    }
+
+    #[gtk::test]
+    async fn it_fails_when_device_is_not_found() {
+        crate::load_resources();
+        let mut registry = MockDeviceRegistry::new();
+        registry
+            .expect_bug_report()
+            .once()
+            .return_once(|_| Ok(None));
+        let (state, _receiver) = State::new(registry);
+
+        assert!(create_bug_report(&state, MacAddr6::nil()).await.is_err());
+    }
+}
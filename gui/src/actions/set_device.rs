@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use gtk::glib::{clone, MainContext};
 use macaddr::MacAddr6;
 use openscq30_lib::api::{
@@ -27,6 +27,18 @@ where
         handle.abort();
     }
     *state.selected_device.borrow_mut() = None;
+    // Curves saved for one device shouldn't be compared on another
+    if state
+        .equalizer_comparison
+        .replace(Default::default())
+        .status()
+        != Default::default()
+    {
+        state
+            .state_update_sender
+            .send(StateUpdate::SetEqualizerComparisonStatus(Default::default()))
+            .map_err(|err| anyhow!("{err}"))?;
+    }
 
     let Some(mac_address) = mac_address else {
         // Disconnect
@@ -99,7 +111,18 @@ where
 
     let name = device.name().await?;
     let mac_address = device.mac_address().await?;
-    let device_state = device.state().await;
+    let mut device_state = device.state().await;
+    let is_dynamic_range_compression_enabled = settings_file
+        .get(|config| config.dynamic_range_compression(device.service_uuid()))
+        .context("get dynamic range compression from config")?;
+    if let Some(is_enabled) = is_dynamic_range_compression_enabled {
+        if device_state.supports_dynamic_range_compression()
+            && device_state.is_dynamic_range_compression_enabled != is_enabled
+        {
+            device.set_dynamic_range_compression(is_enabled).await?;
+            device_state.is_dynamic_range_compression_enabled = is_enabled;
+        }
+    }
     state
         .state_update_sender
         .send(StateUpdate::SetSelectedDevice(Some(GlibDevice::new(
@@ -112,6 +135,16 @@ where
         .state_update_sender
         .send(StateUpdate::SetDeviceState(device_state))
         .map_err(|err| anyhow!("{err:?}"))?;
+    if let Some(degraded_mode) = device.degraded_mode().await {
+        state
+            .state_update_sender
+            .send(StateUpdate::AddToast(format!(
+                "The {} sent {} bytes that aren't understood yet, so some settings may be missing",
+                degraded_mode.model,
+                degraded_mode.trailing_bytes.len(),
+            )))
+            .map_err(|err| anyhow!("{err:?}"))?;
+    }
 
     actions::refresh_quick_presets(state, settings_file, device.service_uuid())?;
 
@@ -219,6 +252,7 @@ mod tests {
                     .return_const(receiver);
                 device.expect_service_uuid().return_const(Uuid::default());
                 device.expect_state().once().return_const(device_state_2);
+                device.expect_degraded_mode().once().return_const(None);
 
                 Ok(Some(Rc::new(device)))
             });
@@ -315,6 +349,7 @@ mod tests {
                     ),
                     ..Default::default()
                 });
+                device.expect_degraded_mode().once().return_const(None);
 
                 Ok(Some(Rc::new(device)))
             });
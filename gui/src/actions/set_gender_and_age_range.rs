@@ -0,0 +1,59 @@
+use openscq30_lib::{
+    api::device::{Device, DeviceRegistry},
+    devices::standard::structures::{AgeRange, Gender},
+};
+
+use super::State;
+
+#[tracing::instrument(level = "trace", skip(state))]
+pub async fn set_gender_and_age_range<T>(
+    state: &State<T>,
+    gender: Gender,
+    age_range: AgeRange,
+) -> anyhow::Result<()>
+where
+    T: DeviceRegistry + 'static,
+{
+    let device = state
+        .selected_device()
+        .ok_or_else(|| anyhow::anyhow!("no device is selected"))?;
+
+    device.set_gender_and_age_range(gender, age_range).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use mockall::predicate;
+    use openscq30_lib::devices::standard::structures::{AgeRange, Gender};
+
+    use crate::{
+        actions::State,
+        mock::{MockDevice, MockDeviceRegistry},
+    };
+
+    use super::set_gender_and_age_range;
+
+    #[gtk::test]
+    async fn it_works() {
+        crate::load_resources();
+        let registry = MockDeviceRegistry::new();
+        let (state, _receiver) = State::new(registry);
+        let mut selected_device = MockDevice::new();
+        selected_device
+            .expect_set_gender_and_age_range()
+            .once()
+            .with(
+                predicate::eq(Gender::Male),
+                predicate::eq(AgeRange::From30To39),
+            )
+            .return_once(|_gender, _age_range| Ok(()));
+        *state.selected_device.borrow_mut() = Some(Rc::new(selected_device));
+
+        set_gender_and_age_range(&state, Gender::Male, AgeRange::From30To39)
+            .await
+            .unwrap();
+    }
+}
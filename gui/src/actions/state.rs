@@ -9,7 +9,7 @@ use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
 use crate::swappable_broadcast::SwappableBroadcast;
 
-use super::StateUpdate;
+use super::{EqualizerComparisonState, StateUpdate};
 
 pub struct State<T>
 where
@@ -23,6 +23,7 @@ where
     pub set_custom_noise_canceling_handle: RefCell<Option<JoinHandle<()>>>,
     pub is_refresh_in_progress: Cell<bool>,
     pub state_update_receiver: SwappableBroadcast<DeviceState>,
+    pub equalizer_comparison: RefCell<EqualizerComparisonState<T::DeviceType>>,
 }
 
 impl<T> State<T>
@@ -39,6 +40,7 @@ where
                 selected_device: RefCell::new(None),
                 is_refresh_in_progress: Cell::new(false),
                 state_update_receiver: SwappableBroadcast::new(),
+                equalizer_comparison: Default::default(),
                 registry,
                 state_update_sender: sender,
             }),
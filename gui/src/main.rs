@@ -16,10 +16,11 @@ use gtk::{
     CssProvider,
 };
 use logging_level::LoggingLevel;
-use openscq30_lib::api::{
-    device::DeviceRegistry, new_soundcore_device_registry_with_custom_runtime,
+use openscq30_lib::{
+    api::{device::DeviceRegistry, new_soundcore_device_registry_with_custom_runtime},
+    hear_id_history::HearIdHistory,
 };
-use settings::Settings;
+use settings::{Settings, SettingsFile};
 use tokio::sync::mpsc;
 use tracing::Level;
 use ui::widgets::MainWindow;
@@ -44,6 +45,7 @@ mod settings;
 mod swappable_broadcast;
 #[cfg(test)]
 mod test_utils;
+mod tone_player;
 #[allow(clippy::new_without_default)]
 mod ui;
 
@@ -219,6 +221,14 @@ fn build_ui(application: &adw::Application) {
             let registry = new_soundcore_device_registry_with_custom_runtime::<GtkFutures>()
                 .await
                 .expect("failed to initialize device registry");
+            match settings.hear_id_history.get(Clone::clone) {
+                Ok(history) => {
+                    if let Err(err) = registry.load_hear_id_history(history).await {
+                        tracing::warn!("failed to load hear id history: {err:?}");
+                    }
+                }
+                Err(err) => tracing::warn!("failed to read hear id history: {err:?}"),
+            }
             // Async initialization done, now set up event handlers and such
             delayed_initialize_application(&application, &main_window, registry, settings);
         });
@@ -236,6 +246,8 @@ fn delayed_initialize_application(
     let (state, mut ui_state_receiver) = State::new(registry);
 
     let main_context = MainContext::default();
+    let hear_id_history = settings.hear_id_history.to_owned();
+    let ui_state = state.to_owned();
     main_context.spawn_local(clone!(
         #[weak]
         main_window,
@@ -245,7 +257,15 @@ fn delayed_initialize_application(
                     match update {
                         StateUpdate::SetDevices(devices) => main_window.set_devices(&devices),
                         StateUpdate::SetLoading(is_loading) => main_window.set_loading(is_loading),
-                        StateUpdate::SetDeviceState(state) => main_window.set_device_state(&state),
+                        StateUpdate::SetDeviceState(state) => {
+                            main_window.set_device_state(&state);
+                            // HearID profiles are recorded as the device's state changes
+                            if let Err(err) =
+                                save_hear_id_history(&ui_state.registry, &hear_id_history).await
+                            {
+                                tracing::warn!("failed to save hear id history: {err:?}");
+                            }
+                        }
                         StateUpdate::SetEqualizerConfiguration(equalizer_configuration) => {
                             main_window.set_equalizer_configuration(&equalizer_configuration)
                         }
@@ -261,12 +281,29 @@ fn delayed_initialize_application(
                         StateUpdate::SetQuickPresets(quick_presets) => {
                             main_window.set_quick_presets(quick_presets)
                         }
+                        StateUpdate::SetEqualizerComparisonStatus(status) => {
+                            main_window.set_equalizer_comparison_status(status)
+                        }
+                        StateUpdate::CopyToClipboard(text) => {
+                            main_window.clipboard().set_text(&text)
+                        }
                     }
                 }
             }
         }
     ));
 
+    async fn save_hear_id_history(
+        registry: &impl DeviceRegistry,
+        file: &SettingsFile<HearIdHistory>,
+    ) -> anyhow::Result<()> {
+        let history = registry.hear_id_history().await?;
+        if file.get(|saved| saved != &history)? {
+            file.edit(|saved| *saved = history)?;
+        }
+        Ok(())
+    }
+
     fn handle_error<T>(err: anyhow::Error, state: &State<T>)
     where
         T: DeviceRegistry,
@@ -291,7 +328,7 @@ fn delayed_initialize_application(
                 deselect_device();
                 send_toast(format!("Device Not Supported: {serial_number}"));
             }
-            Some(openscq30_lib::Error::TimedOut { action }) => {
+            Some(openscq30_lib::Error::TimedOut { action, .. }) => {
                 deselect_device();
                 send_toast(format!("Action Timed Out: {action}"));
             }
@@ -335,6 +372,12 @@ fn delayed_initialize_application(
             Some(openscq30_lib::Error::IncompleteStateError { message }) => {
                 send_toast(format!("Action failed due to incomplete state: {message}"))
             }
+            Some(openscq30_lib::Error::InvalidValue { name, value }) => {
+                send_toast(format!("Invalid {name}: {value}"))
+            }
+            Some(openscq30_lib::Error::Remote { details }) => {
+                send_toast(details.message.to_owned())
+            }
             Some(openscq30_lib::Error::Other { .. }) | None => {
                 state
                     .state_update_sender
@@ -462,6 +505,11 @@ fn delayed_initialize_application(
                         Action::SetHearId(hear_id) => actions::set_hear_id(&state, hear_id)
                             .await
                             .context("set hear id"),
+                        Action::SetGenderAndAgeRange(gender, age_range) => {
+                            actions::set_gender_and_age_range(&state, gender, age_range)
+                                .await
+                                .context("set gender and age range")
+                        }
                         Action::SetCustomButtonModel(custom_button_model) => {
                             actions::set_custom_button_model(&state, custom_button_model)
                                 .await
@@ -481,6 +529,31 @@ fn delayed_initialize_application(
                             &profiles,
                             overwrite,
                         ),
+                        Action::SetEqualizerComparisonSide(side, configuration) => {
+                            actions::set_equalizer_comparison_side(&state, side, configuration)
+                                .await
+                                .context("save equalizer comparison side")
+                        }
+                        Action::ToggleEqualizerComparison => {
+                            actions::toggle_equalizer_comparison(&state)
+                                .await
+                                .context("switch equalizer comparison side")
+                        }
+                        Action::SetEqualizerComparisonBlind(is_blind) => {
+                            actions::set_equalizer_comparison_blind(&state, is_blind)
+                                .await
+                                .context("set equalizer comparison blind mode")
+                        }
+                        Action::PreferEqualizerComparisonSide => {
+                            actions::prefer_equalizer_comparison_side(&state)
+                                .await
+                                .context("prefer equalizer comparison side")
+                        }
+                        Action::CreateBugReport(mac_address) => {
+                            actions::create_bug_report(&state, mac_address)
+                                .await
+                                .context("create bug report")
+                        }
                     };
 
                     if let Err(err) = result {
@@ -8,10 +8,11 @@ use openscq30_lib::{
     devices::standard::{
         state::DeviceState,
         structures::{
-            AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, HearId, SoundModes,
-            SoundModesTypeTwo,
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, Gender,
+            HearId, SoundModes, SoundModesTypeTwo,
         },
     },
+    soundcore_device::device::DegradedMode,
 };
 use tokio::sync::watch;
 use uuid::Uuid;
@@ -25,6 +26,7 @@ mock! {
         pub fn name(&self) -> openscq30_lib::Result<String>;
         pub fn service_uuid(&self) -> Uuid;
         pub fn state(&self) -> DeviceState;
+        pub fn degraded_mode(&self) -> Option<DegradedMode>;
         pub fn set_sound_modes(
             &self,
             sound_modes: SoundModes,
@@ -41,10 +43,19 @@ mock! {
             &self,
             configuration: EqualizerConfiguration,
         ) -> openscq30_lib::Result<()>;
+        pub fn set_dynamic_range_compression(
+            &self,
+            is_enabled: bool,
+        ) -> openscq30_lib::Result<()>;
         pub fn set_hear_id(
             &self,
             hear_id: HearId,
         ) -> openscq30_lib::Result<()>;
+        pub fn set_gender_and_age_range(
+            &self,
+            gender: Gender,
+            age_range: AgeRange,
+        ) -> openscq30_lib::Result<()>;
         pub fn set_custom_button_model(
             &self,
             custom_button_model: CustomButtonModel,
@@ -74,6 +85,10 @@ impl Device for MockDevice {
         timeout_future(Duration::from_millis(10)).await;
         self.state()
     }
+    async fn degraded_mode(&self) -> Option<DegradedMode> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.degraded_mode()
+    }
     async fn set_sound_modes(&self, sound_modes: SoundModes) -> openscq30_lib::Result<()> {
         timeout_future(Duration::from_millis(10)).await;
         self.set_sound_modes(sound_modes)
@@ -99,10 +114,22 @@ impl Device for MockDevice {
         timeout_future(Duration::from_millis(10)).await;
         self.set_equalizer_configuration(equalizer_configuration)
     }
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> openscq30_lib::Result<()> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.set_dynamic_range_compression(is_enabled)
+    }
     async fn set_hear_id(&self, hear_id: HearId) -> openscq30_lib::Result<()> {
         timeout_future(Duration::from_millis(10)).await;
         self.set_hear_id(hear_id)
     }
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> openscq30_lib::Result<()> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.set_gender_and_age_range(gender, age_range)
+    }
     async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
@@ -3,7 +3,11 @@ use std::{rc::Rc, time::Duration};
 use gtk::glib::timeout_future;
 use macaddr::MacAddr6;
 use mockall::mock;
-use openscq30_lib::api::device::{DeviceRegistry, GenericDeviceDescriptor};
+use openscq30_lib::{
+    api::device::{DeviceRegistry, GenericDeviceDescriptor},
+    bug_report::BugReport,
+    hear_id_history::HearIdHistory,
+};
 
 use super::MockDevice;
 
@@ -11,6 +15,9 @@ mock! {
     pub DeviceRegistry {
         pub fn device_descriptors(&self) -> openscq30_lib::Result<Vec<GenericDeviceDescriptor>>;
         pub fn device(&self, mac_address: MacAddr6) -> openscq30_lib::Result<Option<Rc<MockDevice>>>;
+        pub fn bug_report(&self, mac_address: MacAddr6) -> openscq30_lib::Result<Option<BugReport>>;
+        pub fn hear_id_history(&self) -> openscq30_lib::Result<HearIdHistory>;
+        pub fn load_hear_id_history(&self, history: HearIdHistory) -> openscq30_lib::Result<()>;
     }
 }
 
@@ -30,4 +37,19 @@ impl DeviceRegistry for MockDeviceRegistry {
         timeout_future(Duration::from_millis(10)).await;
         self.device(mac_address)
     }
+
+    async fn bug_report(&self, mac_address: MacAddr6) -> openscq30_lib::Result<Option<BugReport>> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.bug_report(mac_address)
+    }
+
+    async fn hear_id_history(&self) -> openscq30_lib::Result<HearIdHistory> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.hear_id_history()
+    }
+
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> openscq30_lib::Result<()> {
+        timeout_future(Duration::from_millis(10)).await;
+        self.load_hear_id_history(history)
+    }
 }
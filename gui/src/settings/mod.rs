@@ -1,11 +1,10 @@
-mod config;
-mod custom_equalizer_profile;
 mod settings;
 mod settings_file;
 mod state;
 
-pub use config::*;
-pub use custom_equalizer_profile::*;
+pub use openscq30_config::{
+    Config, CustomEqualizerProfile, PresetOrCustomEqualizerProfile, QuickPreset,
+};
 pub use settings::*;
 pub use settings_file::*;
 pub use state::*;
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use anyhow::Context;
+use openscq30_lib::hear_id_history::HearIdHistory;
 
 use super::{Config, SettingsFile, State};
 
@@ -8,6 +9,8 @@ use super::{Config, SettingsFile, State};
 pub struct Settings {
     pub state: Rc<SettingsFile<State>>,
     pub config: Rc<SettingsFile<Config>>,
+    /// Kept separately from the config since it's written by the app rather than the user
+    pub hear_id_history: Rc<SettingsFile<HearIdHistory>>,
 }
 
 impl Settings {
@@ -15,8 +18,13 @@ impl Settings {
         // We don't want to stop if one fails, so handle errors after everything is done
         let config_result = self.config.load().with_context(|| "Failed to load config");
         let state_result = self.state.load().with_context(|| "Failed to load state");
+        let hear_id_history_result = self
+            .hear_id_history
+            .load()
+            .with_context(|| "Failed to load HearID history");
         config_result?;
         state_result?;
+        hear_id_history_result?;
         Ok(())
     }
 }
@@ -39,6 +47,7 @@ impl Default for Settings {
         Self {
             state: Rc::new(SettingsFile::new(state_dir.join("state.toml"))),
             config: Rc::new(SettingsFile::new(config_dir.join("config.toml"))),
+            hear_id_history: Rc::new(SettingsFile::new(config_dir.join("hear_id_history.toml"))),
         }
     }
 }
@@ -41,14 +41,20 @@ where
         Ok(())
     }
 
+    /// Reloads the file before editing, so that changes made by other programs since it was last
+    /// loaded, such as the CLI editing the config, aren't overwritten.
     pub fn edit<F>(&self, f: F) -> anyhow::Result<()>
     where
         F: FnOnce(&mut SettingsStateType),
     {
+        let on_disk = self.read_latest();
         let mut state = self
             .state
             .write()
             .map_err(|err| anyhow::anyhow!("failed to write rwlock: {err}"))?;
+        if let Some(on_disk) = on_disk {
+            *state = on_disk;
+        }
         f(&mut state);
         self.save(&state)?;
         Ok(())
@@ -71,6 +77,26 @@ where
         Ok(f(&state))
     }
 
+    /// None if the file doesn't exist yet or can't be used, in which case what was last loaded is
+    /// kept
+    fn read_latest(&self) -> Option<SettingsStateType> {
+        if !self.settings_file_path.exists() {
+            return None;
+        }
+        match self.read_file().and_then(|buffer| {
+            toml::from_str::<SettingsStateType>(&buffer).context("parse toml config file")
+        }) {
+            Ok(settings) => Some(settings),
+            Err(err) => {
+                tracing::warn!(
+                    "failed to reload {} before editing, continuing with loaded settings: {err:?}",
+                    self.settings_file_path.to_string_lossy(),
+                );
+                None
+            }
+        }
+    }
+
     fn read_file(&self) -> anyhow::Result<String> {
         let mut options = OpenOptions::new();
         options.read(true);
@@ -202,4 +228,17 @@ mod tests {
             "file should have been written to"
         );
     }
+
+    #[test]
+    fn it_keeps_changes_made_by_other_programs() {
+        let file_dir = tempdir().unwrap();
+        let file_path = file_dir.path().join("config.toml");
+        let settings_file = SettingsFile::<TestConfig>::new(&file_path);
+        settings_file.edit(|config| config.number = 1).unwrap();
+
+        std::fs::write(&file_path, "number = 5").unwrap();
+        settings_file.edit(|config| config.number += 1).unwrap();
+
+        assert_eq!(6, settings_file.get(|config| config.number).unwrap());
+    }
 }
@@ -0,0 +1,121 @@
+use std::{f64::consts::TAU, time::Duration};
+
+use gtk::{
+    gio,
+    glib::{self, Bytes},
+    prelude::MediaStreamExt,
+};
+use openscq30_lib::hear_id_test::{Ear, HearIdTest, Tone, TonePlayer};
+
+/// Plays tones through the default audio output with [`gtk::MediaFile`]. Levels aren't calibrated,
+/// so [`HearIdTest::MAX_LEVEL`] is played at half of full scale and everything else relative to
+/// that. Results depend on the system volume.
+#[derive(Debug, Default)]
+pub struct GtkTonePlayer {}
+
+impl GtkTonePlayer {
+    const SAMPLE_RATE: u32 = 48000;
+    const FADE_DURATION: Duration = Duration::from_millis(20);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn amplitude(level: f64) -> f64 {
+        0.5 * 10f64.powf((level - HearIdTest::MAX_LEVEL as f64) / 20.0)
+    }
+
+    /// 16 bit stereo PCM wav with the tone in one channel and silence in the other
+    fn wav(tone: Tone, duration: Duration) -> Vec<u8> {
+        let num_frames = (duration.as_secs_f64() * Self::SAMPLE_RATE as f64) as u32;
+        let fade_frames = (Self::FADE_DURATION.as_secs_f64() * Self::SAMPLE_RATE as f64) as u32;
+        let amplitude = Self::amplitude(tone.level);
+        let data_size = num_frames * 4;
+
+        let mut wav = Vec::with_capacity(44 + data_size as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&2u16.to_le_bytes()); // channels
+        wav.extend_from_slice(&Self::SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(Self::SAMPLE_RATE * 4).to_le_bytes()); // byte rate
+        wav.extend_from_slice(&4u16.to_le_bytes()); // block align
+        wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+
+        for frame in 0..num_frames {
+            // Fade in and out to avoid clicks, which would be audible at any frequency
+            let fade = (frame.min(num_frames - frame) as f64 / fade_frames as f64).min(1.0);
+            let phase = TAU * tone.frequency * frame as f64 / Self::SAMPLE_RATE as f64;
+            let sample = (phase.sin() * amplitude * fade * i16::MAX as f64) as i16;
+            let (left, right) = match tone.ear {
+                Ear::Left => (sample, 0),
+                Ear::Right => (0, sample),
+            };
+            wav.extend_from_slice(&left.to_le_bytes());
+            wav.extend_from_slice(&right.to_le_bytes());
+        }
+        wav
+    }
+}
+
+impl TonePlayer for GtkTonePlayer {
+    async fn play(&self, tone: Tone, duration: Duration) -> openscq30_lib::Result<()> {
+        let stream =
+            gio::MemoryInputStream::from_bytes(&Bytes::from_owned(Self::wav(tone, duration)));
+        let media_file = gtk::MediaFile::for_input_stream(&stream);
+        media_file.play();
+        glib::timeout_future(duration).await;
+        media_file.pause();
+        match media_file.error() {
+            Some(err) => Err(openscq30_lib::Error::Other {
+                source: Box::new(err),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use openscq30_lib::hear_id_test::{Ear, HearIdTest, Tone};
+
+    use super::GtkTonePlayer;
+
+    #[test]
+    fn it_plays_tone_in_one_ear() {
+        let wav = GtkTonePlayer::wav(
+            Tone {
+                ear: Ear::Right,
+                frequency: 1000.0,
+                level: HearIdTest::MAX_LEVEL as f64,
+            },
+            Duration::from_millis(100),
+        );
+        assert_eq!(44 + 4800 * 4, wav.len());
+        let frames = wav[44..]
+            .chunks_exact(4)
+            .map(|frame| {
+                (
+                    i16::from_le_bytes([frame[0], frame[1]]),
+                    i16::from_le_bytes([frame[2], frame[3]]),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(frames.iter().all(|(left, _)| *left == 0));
+        let peak = frames.iter().map(|(_, right)| right.abs()).max().unwrap();
+        assert!((i16::MAX / 2 - 10..=i16::MAX / 2).contains(&peak));
+    }
+
+    #[test]
+    fn it_lowers_amplitude_by_level() {
+        let max = GtkTonePlayer::amplitude(HearIdTest::MAX_LEVEL as f64);
+        let lower = GtkTonePlayer::amplitude(HearIdTest::MAX_LEVEL as f64 - 20.0);
+        assert!((max / 10.0 - lower).abs() < 1e-9);
+    }
+}
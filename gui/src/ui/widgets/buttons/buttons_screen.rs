@@ -168,6 +168,11 @@ mod imp {
             });
             if let Some(index) = index {
                 row.set_selected(index);
+            } else if let Some(action @ ButtonAction::Unknown(_)) = selection {
+                // Actions we don't know about aren't listed ahead of time, so add them as they
+                // are seen to allow them to be displayed
+                button_actions.append(&GlibButtonAction::new(GlibButtonActionValue(Some(action))));
+                row.set_selected(button_actions.n_items() - 1);
             } else {
                 panic!(
                     "every possible button action should be listed but {selection:?} was not found"
@@ -280,7 +285,11 @@ mod imp {
             let expression = ClosureExpression::with_callback(gtk::Expression::NONE, |args| {
                 let button_action: GlibButtonAction = args[0].get().unwrap();
                 if let Some(button_action) = button_action.button_action().0 {
-                    glib::dpgettext2(Some(APPLICATION_ID_STR), "buttons", button_action.as_ref())
+                    glib::dpgettext2(
+                        Some(APPLICATION_ID_STR),
+                        "buttons",
+                        &button_action.to_string(),
+                    )
                 } else {
                     glib::dpgettext2(Some(APPLICATION_ID_STR), "buttons", "Disabled")
                 }
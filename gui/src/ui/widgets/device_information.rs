@@ -70,7 +70,7 @@ mod imp {
             self.age_range.set_text(
                 &state
                     .age_range
-                    .map(|age_range| age_range.0.to_string())
+                    .map(|age_range| age_range.to_string())
                     .unwrap_or_default(),
             );
             // TODO display as JSON or something
@@ -85,6 +85,21 @@ mod imp {
             }
         }
 
+        #[template_callback]
+        pub fn handle_create_bug_report_clicked(&self, _button: &gtk::Button) {
+            if let Some(selected_device) =
+                self.dropdown.selected_item().and_downcast::<GlibDevice>()
+            {
+                self.sender
+                    .get()
+                    .unwrap()
+                    .send(Action::CreateBugReport(
+                        MacAddr6::from_str(&selected_device.mac_address()).unwrap(),
+                    ))
+                    .unwrap();
+            }
+        }
+
         pub fn set_devices(&self, devices: &[GlibDevice]) {
             if let Some(model) = self.devices.get() {
                 model.remove_all();
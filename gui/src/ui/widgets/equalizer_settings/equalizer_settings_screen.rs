@@ -5,7 +5,10 @@ use gtk::{
 use openscq30_lib::devices::standard::structures::EqualizerConfiguration;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{actions::Action, objects::GlibCustomEqualizerProfile};
+use crate::{
+    actions::{Action, EqualizerComparisonStatus},
+    objects::GlibCustomEqualizerProfile,
+};
 
 glib::wrapper! {
     pub struct EqualizerSettingsScreen(ObjectSubclass<imp::EqualizerSettingsScreen>)
@@ -34,6 +37,10 @@ impl EqualizerSettingsScreen {
     pub fn set_custom_profiles(&self, custom_profiles: Vec<GlibCustomEqualizerProfile>) {
         self.imp().set_custom_profiles(custom_profiles)
     }
+
+    pub fn set_equalizer_comparison_status(&self, status: EqualizerComparisonStatus) {
+        self.imp().set_equalizer_comparison_status(status)
+    }
 }
 
 mod imp {
@@ -50,14 +57,17 @@ mod imp {
         ClosureExpression, CompositeTemplate, Expression, PropertyExpression,
         SignalListItemFactory, TemplateChild,
     };
-    use openscq30_lib::devices::standard::structures::{
-        EqualizerConfiguration, PresetEqualizerProfile, VolumeAdjustments,
+    use openscq30_lib::{
+        devices::standard::structures::{
+            EqualizerConfiguration, PresetEqualizerProfile, VolumeAdjustments,
+        },
+        equalizer_comparison::ComparisonSide,
     };
     use strum::IntoEnumIterator;
     use tokio::sync::mpsc::UnboundedSender;
 
     use crate::{
-        actions::Action,
+        actions::{Action, EqualizerComparisonStatus},
         objects::{GlibCustomEqualizerProfile, GlibEqualizerProfile, GlibVolumeAdjustments},
         ui::widgets::equalizer_settings::{
             equalizer::Equalizer, profile_dropdown_row::ProfileDropdownRow,
@@ -84,6 +94,20 @@ mod imp {
         pub delete_custom_profile_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub custom_profile_buttons: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub equalizer_row: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub clipping_warning: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub clipping_warning_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub normalize_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub comparison_toggle_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub comparison_prefer_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub comparison_status_label: TemplateChild<gtk::Label>,
 
         profiles: OnceCell<gio::ListStore>,
         custom_profiles: OnceCell<gio::ListStore>,
@@ -130,9 +154,122 @@ mod imp {
             }
         }
 
+        #[template_callback]
+        fn handle_normalize(&self, _button: &gtk::Button) {
+            let equalizer_configuration = EqualizerConfiguration::new_custom_profile(
+                self.equalizer.volume_adjustments().normalize(),
+            );
+            self.set_equalizer_configuration(&equalizer_configuration);
+            self.update_custom_profile_selection();
+            self.sender
+                .get()
+                .unwrap()
+                .send(Action::SetEqualizerConfiguration(equalizer_configuration))
+                .unwrap();
+        }
+
+        fn update_clipping_warning(&self) {
+            let analysis = self.equalizer.volume_adjustments().analyze();
+            self.clipping_warning
+                .set_visible(analysis.is_clipping_likely());
+            if analysis.is_clipping_likely() {
+                self.clipping_warning_label.set_label(
+                    &glib::dpgettext2(
+                        Some(APPLICATION_ID_STR),
+                        "equalizer",
+                        "Peak gain of {peak_gain} dB may cause clipping",
+                    )
+                    .replace("{peak_gain}", &format!("{:+.1}", analysis.peak_gain)),
+                );
+            }
+        }
+
+        #[template_callback]
+        fn handle_save_comparison_a(&self, _button: &gtk::Button) {
+            self.send_comparison_side(ComparisonSide::A);
+        }
+
+        #[template_callback]
+        fn handle_save_comparison_b(&self, _button: &gtk::Button) {
+            self.send_comparison_side(ComparisonSide::B);
+        }
+
+        fn send_comparison_side(&self, side: ComparisonSide) {
+            self.sender
+                .get()
+                .unwrap()
+                .send(Action::SetEqualizerComparisonSide(
+                    side,
+                    self.equalizer_configuration(),
+                ))
+                .unwrap();
+        }
+
+        #[template_callback]
+        fn handle_toggle_comparison(&self, _button: &gtk::Button) {
+            self.sender
+                .get()
+                .unwrap()
+                .send(Action::ToggleEqualizerComparison)
+                .unwrap();
+        }
+
+        #[template_callback]
+        fn handle_prefer_comparison_side(&self, _button: &gtk::Button) {
+            self.sender
+                .get()
+                .unwrap()
+                .send(Action::PreferEqualizerComparisonSide)
+                .unwrap();
+        }
+
+        #[template_callback]
+        fn handle_comparison_blind_toggled(&self, check_button: &gtk::CheckButton) {
+            if let Some(sender) = self.sender.get() {
+                sender
+                    .send(Action::SetEqualizerComparisonBlind(
+                        check_button.is_active(),
+                    ))
+                    .unwrap();
+            }
+        }
+
+        pub fn set_equalizer_comparison_status(&self, status: EqualizerComparisonStatus) {
+            let is_ready = status.has_a && status.has_b;
+            self.comparison_toggle_button.set_sensitive(is_ready);
+            self.comparison_prefer_button.set_sensitive(is_ready);
+            // The sliders would give away which curve is playing
+            self.equalizer_row
+                .set_visible(!(is_ready && status.is_blind));
+
+            let text = if !is_ready {
+                glib::dpgettext2(
+                    Some(APPLICATION_ID_STR),
+                    "equalizer comparison",
+                    "Save the current curve as A and as B to compare them",
+                )
+                .to_string()
+            } else {
+                let active_side = status
+                    .active_side
+                    .map(|side| side.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                glib::dpgettext2(
+                    Some(APPLICATION_ID_STR),
+                    "equalizer comparison",
+                    "Playing: {active_side}, A preferred {a} times, B preferred {b} times",
+                )
+                .replace("{active_side}", &active_side)
+                .replace("{a}", &status.tally.a.to_string())
+                .replace("{b}", &status.tally.b.to_string())
+            };
+            self.comparison_status_label.set_label(&text);
+        }
+
         #[template_callback]
         fn handle_volumes_changed(&self, equalizer: &Equalizer) {
             self.update_custom_profile_selection();
+            self.update_clipping_warning();
             // apply-equalizer-settings fires instantly when changing the preset profile, so we only need to be concerned
             // with custom profiles here.
             let selected_profile = self.profile_dropdown.selected_item().map(|item| {
@@ -189,6 +326,7 @@ mod imp {
         ) {
             self.equalizer
                 .set_volumes(&equalizer_configuration.volume_adjustments().adjustments());
+            self.update_clipping_warning();
             let profile_index = self
                 .profiles
                 .get()
@@ -560,6 +698,7 @@ mod imp {
             self.parent_constructed();
             self.set_up_preset_profile();
             self.set_up_custom_profile();
+            self.set_equalizer_comparison_status(EqualizerComparisonStatus::default());
         }
     }
     impl WidgetImpl for EqualizerSettingsScreen {}
@@ -576,10 +715,34 @@ mod tests {
     };
     use tokio::sync::mpsc;
 
-    use crate::objects::GlibCustomEqualizerProfile;
+    use crate::{
+        actions::{Action, EqualizerComparisonStatus},
+        objects::GlibCustomEqualizerProfile,
+    };
 
     use super::EqualizerSettingsScreen;
 
+    #[gtk::test]
+    fn test_hides_equalizer_during_blind_comparison() {
+        crate::load_resources();
+        let settings = EqualizerSettingsScreen::new();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        settings.set_sender(sender);
+        assert_eq!(
+            false,
+            settings.imp().comparison_toggle_button.is_sensitive()
+        );
+
+        settings.set_equalizer_comparison_status(EqualizerComparisonStatus {
+            has_a: true,
+            has_b: true,
+            is_blind: true,
+            ..Default::default()
+        });
+        assert_eq!(true, settings.imp().comparison_toggle_button.is_sensitive());
+        assert_eq!(false, settings.imp().equalizer_row.is_visible());
+    }
+
     #[gtk::test]
     fn test_does_not_show_any_button_with_preset_profile_selected() {
         crate::load_resources();
@@ -640,4 +803,31 @@ mod tests {
             settings.imp().delete_custom_profile_button.is_visible(),
         );
     }
+
+    #[gtk::test]
+    fn test_warns_about_clipping_and_normalizes() {
+        crate::load_resources();
+        let settings = EqualizerSettingsScreen::new();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        settings.set_sender(sender);
+        settings.set_equalizer_configuration(&EqualizerConfiguration::new_custom_profile(
+            VolumeAdjustments::new([9.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap(),
+        ));
+        assert_eq!(true, settings.imp().clipping_warning.is_visible());
+
+        settings.imp().normalize_button.emit_clicked();
+        assert_eq!(false, settings.imp().clipping_warning.is_visible());
+        let mut last_configuration = None;
+        while let Ok(action) = receiver.try_recv() {
+            if let Action::SetEqualizerConfiguration(equalizer_configuration) = action {
+                last_configuration = Some(equalizer_configuration);
+            }
+        }
+        assert_eq!(
+            Some(EqualizerConfiguration::new_custom_profile(
+                VolumeAdjustments::new([0.0, -9.0, -9.0, -9.0, -9.0, -9.0, -9.0, -9.0]).unwrap(),
+            )),
+            last_configuration,
+        );
+    }
 }
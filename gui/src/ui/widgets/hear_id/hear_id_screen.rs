@@ -28,24 +28,34 @@ impl HearIdScreen {
 }
 
 mod imp {
-    use std::cell::{OnceCell, RefCell};
+    use std::{
+        cell::{Cell, OnceCell, RefCell},
+        fmt::Display,
+        time::SystemTime,
+    };
 
-    use adw::prelude::WidgetExt;
+    use adw::prelude::*;
     use gtk::{
-        glib,
+        glib::{self, clone, MainContext},
         subclass::{
             prelude::*,
             widget::{CompositeTemplateClass, CompositeTemplateInitializingExt, WidgetImpl},
         },
         template_callbacks, CompositeTemplate,
     };
-    use openscq30_lib::devices::standard::{
-        state::DeviceState,
-        structures::{BasicHearId, CustomHearId, HearId, VolumeAdjustments},
+    use openscq30_lib::{
+        devices::standard::{
+            state::DeviceState,
+            structures::{
+                AgeRange, BasicHearId, CustomHearId, Gender, HearId, HearIdType, VolumeAdjustments,
+            },
+        },
+        hear_id_test::HearIdTest,
     };
+    use strum::IntoEnumIterator;
     use tokio::sync::mpsc::UnboundedSender;
 
-    use crate::actions::Action;
+    use crate::{actions::Action, tone_player::GtkTonePlayer, APPLICATION_ID_STR};
 
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/com/oppzippy/OpenSCQ30/ui/widgets/hear_id/hear_id_screen.ui")]
@@ -57,11 +67,13 @@ mod imp {
         #[template_child]
         volume_adjustments_right: TemplateChild<gtk::Label>,
         #[template_child]
-        time: TemplateChild<gtk::Label>,
+        gender: TemplateChild<adw::ComboRow>,
         #[template_child]
-        hear_id_type_row: TemplateChild<adw::ActionRow>,
+        age_range: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        time: TemplateChild<gtk::Label>,
         #[template_child]
-        hear_id_type: TemplateChild<gtk::Label>,
+        hear_id_type: TemplateChild<adw::ComboRow>,
         #[template_child]
         hear_id_music_type_row: TemplateChild<adw::ActionRow>,
         #[template_child]
@@ -74,17 +86,49 @@ mod imp {
         custom_volume_adjustments_right_row: TemplateChild<adw::ActionRow>,
         #[template_child]
         custom_volume_adjustments_right: TemplateChild<gtk::Label>,
+        #[template_child]
+        hearing_test: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        hearing_test_tone: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        tone_heard_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        tone_not_heard_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        hearing_test_progress: TemplateChild<gtk::ProgressBar>,
+        #[template_child]
+        start_hearing_test_button: TemplateChild<gtk::Button>,
 
         hear_id: RefCell<Option<HearId>>,
+        // The value of each item in the combo rows, in the same order
+        genders: RefCell<Vec<Gender>>,
+        age_ranges: RefCell<Vec<AgeRange>>,
+        hear_id_types: RefCell<Vec<HearIdType>>,
+        // Selecting items in the combo rows would otherwise send them back to the device
+        ignore_changes: Cell<bool>,
+        // The hearing test produces a custom hear id with the same number of bands as the current one
+        num_hear_id_bands: Cell<usize>,
+        hear_id_test: RefCell<Option<HearIdTest>>,
         sender: OnceCell<UnboundedSender<Action>>,
     }
 
     impl HearIdScreen {
         pub fn set_device_state(&self, state: &DeviceState) {
+            self.ignore_changes.set(true);
             *self.hear_id.borrow_mut() = state.hear_id.to_owned();
+            self.gender.set_visible(state.gender.is_some());
+            if let Some(gender) = state.gender {
+                Self::select(&self.gender, &self.genders, gender);
+            }
+            self.age_range.set_visible(state.age_range.is_some());
+            if let Some(age_range) = state.age_range {
+                Self::select(&self.age_range, &self.age_ranges, age_range);
+            }
+            self.hearing_test
+                .set_visible(matches!(state.hear_id, Some(HearId::Custom(_))));
             match &state.hear_id {
                 Some(HearId::Basic(hear_id)) => {
-                    self.hear_id_type_row.set_visible(false);
+                    self.hear_id_type.set_visible(false);
                     self.hear_id_music_type_row.set_visible(false);
                     self.custom_volume_adjustments_left_row.set_visible(false);
                     self.custom_volume_adjustments_right_row.set_visible(false);
@@ -101,7 +145,9 @@ mod imp {
                     self.time.set_label(&hear_id.time.to_string());
                 }
                 Some(HearId::Custom(hear_id)) => {
-                    self.hear_id_type_row.set_visible(true);
+                    self.num_hear_id_bands
+                        .set(hear_id.volume_adjustments.left.adjustments().len());
+                    self.hear_id_type.set_visible(true);
                     self.hear_id_music_type_row.set_visible(true);
 
                     self.is_enabled.set_active(hear_id.is_enabled);
@@ -114,10 +160,13 @@ mod imp {
                             &hear_id.volume_adjustments.right,
                         ));
                     self.time.set_label(&hear_id.time.to_string());
-                    self.hear_id_type
-                        .set_label(&hear_id.hear_id_type.0.to_string());
+                    Self::select(
+                        &self.hear_id_type,
+                        &self.hear_id_types,
+                        hear_id.hear_id_type,
+                    );
                     self.hear_id_music_type
-                        .set_label(&hear_id.hear_id_music_type.0.to_string());
+                        .set_label(&hear_id.hear_id_music_type.to_string());
 
                     if let Some(custom_volume_adjustments) = &hear_id.custom_volume_adjustments {
                         self.custom_volume_adjustments_left_row.set_visible(true);
@@ -139,12 +188,130 @@ mod imp {
                     );
                 }
             }
+            self.ignore_changes.set(false);
+        }
+
+        fn populate<T: Display>(row: &adw::ComboRow, options: &RefCell<Vec<T>>, values: Vec<T>) {
+            let labels = values
+                .iter()
+                .map(|value| {
+                    glib::dpgettext2(Some(APPLICATION_ID_STR), "hear id", &value.to_string())
+                })
+                .collect::<Vec<_>>();
+            row.set_model(Some(&gtk::StringList::new(
+                &labels
+                    .iter()
+                    .map(|label| label.as_str())
+                    .collect::<Vec<_>>(),
+            )));
+            *options.borrow_mut() = values;
+        }
+
+        fn select<T: PartialEq + Display>(
+            row: &adw::ComboRow,
+            options: &RefCell<Vec<T>>,
+            value: T,
+        ) {
+            let mut options = options.borrow_mut();
+            let index = match options.iter().position(|option| *option == value) {
+                Some(index) => index,
+                None => {
+                    // Values we don't know about aren't listed ahead of time, so add them as they
+                    // are seen to allow them to be displayed
+                    row.model()
+                        .and_downcast::<gtk::StringList>()
+                        .expect("model should be a StringList")
+                        .append(&value.to_string());
+                    options.push(value);
+                    options.len() - 1
+                }
+            };
+            drop(options);
+            row.set_selected(index as u32);
+        }
+
+        fn selected<T: Copy>(row: &adw::ComboRow, options: &RefCell<Vec<T>>) -> Option<T> {
+            options.borrow().get(row.selected() as usize).copied()
         }
 
         fn format_volume_adjustments(volume_adjustments: &VolumeAdjustments) -> String {
             format!("{:?}", volume_adjustments.adjustments())
         }
 
+        fn update_hear_id_test(&self) {
+            let hear_id_test = self.hear_id_test.borrow();
+            let tone = hear_id_test.as_ref().and_then(HearIdTest::current_tone);
+            self.start_hearing_test_button.set_visible(tone.is_none());
+            self.hearing_test_tone.set_visible(tone.is_some());
+            self.hearing_test_progress.set_visible(tone.is_some());
+            if let (Some(hear_id_test), Some(tone)) = (hear_id_test.as_ref(), tone) {
+                self.hearing_test_progress
+                    .set_fraction(hear_id_test.progress());
+                self.hearing_test_tone.set_subtitle(
+                    &glib::dpgettext2(
+                        Some(APPLICATION_ID_STR),
+                        "hear id",
+                        "{ear} ear, {frequency} Hz",
+                    )
+                    .replace(
+                        "{ear}",
+                        &glib::dpgettext2(
+                            Some(APPLICATION_ID_STR),
+                            "hear id",
+                            &tone.ear.to_string(),
+                        ),
+                    )
+                    .replace("{frequency}", &tone.frequency.to_string()),
+                );
+            }
+        }
+
+        fn play_current_tone(&self) {
+            let Some(hear_id_test) = self.hear_id_test.borrow().to_owned() else {
+                return;
+            };
+            // Answering before the tone has finished playing would skip ahead
+            self.tone_heard_button.set_sensitive(false);
+            self.tone_not_heard_button.set_sensitive(false);
+            MainContext::default().spawn_local(clone!(
+                #[weak(rename_to=this)]
+                self,
+                async move {
+                    if let Err(err) = hear_id_test.play_current_tone(&GtkTonePlayer::new()).await {
+                        tracing::error!("failed to play hearing test tone: {err:?}");
+                    }
+                    this.tone_heard_button.set_sensitive(true);
+                    this.tone_not_heard_button.set_sensitive(true);
+                }
+            ));
+        }
+
+        fn respond_to_tone(&self, is_heard: bool) {
+            let mut hear_id_test = self.hear_id_test.borrow_mut();
+            let Some(test) = hear_id_test.as_mut() else {
+                return;
+            };
+            test.respond(is_heard);
+            if !test.is_complete() {
+                drop(hear_id_test);
+                self.update_hear_id_test();
+                self.play_current_tone();
+                return;
+            }
+
+            let custom_hear_id = test.custom_hear_id(SystemTime::now());
+            *hear_id_test = None;
+            drop(hear_id_test);
+            self.update_hear_id_test();
+            if let Some(custom_hear_id) = custom_hear_id {
+                self.sender
+                    .get()
+                    .unwrap()
+                    .send(Action::SetHearId(HearId::Custom(custom_hear_id)))
+                    .unwrap();
+            }
+        }
+
         pub fn set_sender(&self, sender: UnboundedSender<Action>) {
             self.sender.set(sender.to_owned()).unwrap();
         }
@@ -154,6 +321,9 @@ mod imp {
     impl HearIdScreen {
         #[template_callback]
         pub fn handle_is_enabled_toggled(&self) {
+            if self.ignore_changes.get() {
+                return;
+            }
             if let Some(hear_id) = &*self.hear_id.borrow() {
                 let is_enabled = self.is_enabled.is_active();
                 let new_hear_id = match hear_id {
@@ -175,6 +345,74 @@ mod imp {
                 tracing::error!("tried to toggle hear id, but hear_id is None");
             }
         }
+
+        #[template_callback]
+        pub fn handle_gender_or_age_range_changed(&self) {
+            if self.ignore_changes.get() {
+                return;
+            }
+            let (Some(gender), Some(age_range)) = (
+                Self::selected(&self.gender, &self.genders),
+                Self::selected(&self.age_range, &self.age_ranges),
+            ) else {
+                return;
+            };
+            self.sender
+                .get()
+                .unwrap()
+                .send(Action::SetGenderAndAgeRange(gender, age_range))
+                .unwrap();
+        }
+
+        #[template_callback]
+        pub fn handle_hear_id_type_changed(&self) {
+            if self.ignore_changes.get() {
+                return;
+            }
+            let Some(hear_id_type) = Self::selected(&self.hear_id_type, &self.hear_id_types) else {
+                return;
+            };
+            if let Some(HearId::Custom(hear_id)) = &*self.hear_id.borrow() {
+                self.sender
+                    .get()
+                    .unwrap()
+                    .send(Action::SetHearId(HearId::Custom(CustomHearId {
+                        hear_id_type,
+                        ..hear_id.to_owned()
+                    })))
+                    .unwrap();
+            } else {
+                tracing::error!("tried to set hear id type, but hear_id is not custom");
+            }
+        }
+
+        #[template_callback]
+        fn handle_start_hearing_test(&self) {
+            match HearIdTest::new(self.num_hear_id_bands.get()) {
+                Ok(hear_id_test) => {
+                    *self.hear_id_test.borrow_mut() = Some(hear_id_test);
+                    self.update_hear_id_test();
+                    self.play_current_tone();
+                }
+                Err(err) => tracing::error!("failed to start hearing test: {err:?}"),
+            }
+        }
+
+        #[template_callback]
+        fn handle_tone_heard(&self) {
+            self.respond_to_tone(true);
+        }
+
+        #[template_callback]
+        fn handle_tone_not_heard(&self) {
+            self.respond_to_tone(false);
+        }
+
+        #[template_callback]
+        fn handle_stop_hearing_test(&self) {
+            *self.hear_id_test.borrow_mut() = None;
+            self.update_hear_id_test();
+        }
     }
 
     #[glib::object_subclass]
@@ -192,7 +430,24 @@ mod imp {
             obj.init_template();
         }
     }
-    impl ObjectImpl for HearIdScreen {}
+    impl ObjectImpl for HearIdScreen {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.ignore_changes.set(true);
+            Self::populate(&self.gender, &self.genders, Gender::iter().collect());
+            Self::populate(
+                &self.age_range,
+                &self.age_ranges,
+                AgeRange::iter().collect(),
+            );
+            Self::populate(
+                &self.hear_id_type,
+                &self.hear_id_types,
+                HearIdType::iter().collect(),
+            );
+            self.ignore_changes.set(false);
+        }
+    }
     impl WidgetImpl for HearIdScreen {}
     impl BoxImpl for HearIdScreen {}
 }
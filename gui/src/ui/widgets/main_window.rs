@@ -12,7 +12,7 @@ use openscq30_lib::devices::standard::{state::DeviceState, structures::Equalizer
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
-    actions::Action,
+    actions::{Action, EqualizerComparisonStatus},
     objects::{GlibCustomEqualizerProfile, GlibDevice, GlibNamedQuickPresetValue},
     settings::SettingsFile,
 };
@@ -104,6 +104,12 @@ impl MainWindow {
             .set_custom_profiles(custom_profiles)
     }
 
+    pub fn set_equalizer_comparison_status(&self, status: EqualizerComparisonStatus) {
+        self.imp()
+            .selected_device_settings
+            .set_equalizer_comparison_status(status)
+    }
+
     pub fn set_quick_presets(&self, quick_presets: Vec<GlibNamedQuickPresetValue>) {
         self.imp()
             .selected_device_settings
@@ -6,7 +6,7 @@ use openscq30_lib::devices::standard::{state::DeviceState, structures::Equalizer
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
-    actions::Action,
+    actions::{Action, EqualizerComparisonStatus},
     objects::{GlibCustomEqualizerProfile, GlibNamedQuickPresetValue},
 };
 
@@ -51,6 +51,12 @@ impl SelectedDeviceSettings {
             .set_custom_equalizer_profiles(&custom_profiles);
     }
 
+    pub fn set_equalizer_comparison_status(&self, status: EqualizerComparisonStatus) {
+        self.imp()
+            .equalizer_settings
+            .set_equalizer_comparison_status(status);
+    }
+
     pub fn set_quick_presets(&self, quick_presets: Vec<GlibNamedQuickPresetValue>) {
         self.imp().quick_presets.set_quick_presets(quick_presets)
     }
@@ -1,48 +1,23 @@
+pub mod backend;
 pub mod connection;
 pub mod device;
 
-#[cfg(any(feature = "bluetooth", feature = "demo"))]
-use crate::futures::Futures;
+use crate::futures::{Futures, TokioFutures};
 
-#[cfg(any(feature = "bluetooth", feature = "demo"))]
-use self::device::DeviceRegistry;
+use self::backend::{Backend, BackendDeviceRegistry};
 
-#[cfg(all(feature = "bluetooth", not(feature = "demo")))]
+/// Uses the backend from [`Backend::from_env`].
 pub async fn new_soundcore_device_registry(
     handle: tokio::runtime::Handle,
-) -> crate::Result<impl DeviceRegistry> {
-    use crate::{futures::TokioFutures, soundcore_device::device::SoundcoreDeviceRegistry};
-    let connection_registry =
-        crate::soundcore_device::connection::new_connection_registry(Some(handle)).await?;
-    SoundcoreDeviceRegistry::<_, TokioFutures>::new(connection_registry).await
+) -> crate::Result<BackendDeviceRegistry<TokioFutures>> {
+    BackendDeviceRegistry::new(Backend::from_env()?, Some(handle)).await
 }
 
-#[cfg(all(feature = "bluetooth", not(feature = "demo")))]
+/// Uses the backend from [`Backend::from_env`].
 pub async fn new_soundcore_device_registry_with_custom_runtime<FuturesType>(
-) -> crate::Result<impl DeviceRegistry>
+) -> crate::Result<BackendDeviceRegistry<FuturesType>>
 where
     FuturesType: Futures,
 {
-    use crate::soundcore_device::device::SoundcoreDeviceRegistry;
-    let connection_registry =
-        crate::soundcore_device::connection::new_connection_registry(None).await?;
-    SoundcoreDeviceRegistry::<_, FuturesType>::new(connection_registry).await
-}
-
-#[cfg(feature = "demo")]
-pub async fn new_soundcore_device_registry(
-    _handle: tokio::runtime::Handle,
-) -> crate::Result<impl DeviceRegistry> {
-    use crate::{demo::device::DemoDeviceRegistry, futures::TokioFutures};
-    Ok(DemoDeviceRegistry::<TokioFutures>::new())
-}
-
-#[cfg(feature = "demo")]
-pub async fn new_soundcore_device_registry_with_custom_runtime<FuturesType>(
-) -> crate::Result<impl DeviceRegistry>
-where
-    FuturesType: Futures,
-{
-    use crate::demo::device::DemoDeviceRegistry;
-    Ok(DemoDeviceRegistry::<FuturesType>::new())
+    BackendDeviceRegistry::new(Backend::from_env()?, None).await
 }
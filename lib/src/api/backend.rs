@@ -0,0 +1,315 @@
+use std::{fmt::Debug, rc::Rc, str::FromStr};
+
+use macaddr::MacAddr6;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+#[cfg(feature = "bluetooth")]
+use crate::soundcore_device::{
+    connection::PlatformConnectionRegistry,
+    device::{SoundcoreDevice, SoundcoreDeviceRegistry},
+};
+use crate::{
+    api::{
+        connection::ConnectionStatus,
+        device::{Device, DeviceRegistry, GenericDeviceDescriptor},
+    },
+    bug_report::BugReport,
+    demo::device::{DemoDevice, DemoDeviceRegistry},
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, Gender,
+            HearId, SoundModes, SoundModesTypeTwo,
+        },
+    },
+    futures::Futures,
+    hear_id_history::HearIdHistory,
+    soundcore_device::device::DegradedMode,
+};
+
+/// Where devices come from, chosen at runtime so that the same binary can be used with real
+/// devices or without any bluetooth hardware. There is no backend that replays recorded traffic,
+/// since a recording can't acknowledge changes that weren't in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter, AsRefStr)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Backend {
+    Bluetooth,
+    /// A single simulated device that accepts every change
+    Demo,
+}
+
+impl Backend {
+    /// Overrides the default backend when set to the name of a backend
+    pub const ENV_VAR: &'static str = "OPENSCQ30_BACKEND";
+
+    /// The backend named by [`Self::ENV_VAR`], or the default if it's unset.
+    pub fn from_env() -> crate::Result<Self> {
+        match std::env::var(Self::ENV_VAR) {
+            Ok(value) => Self::from_str(&value).map_err(|_| crate::Error::InvalidValue {
+                name: "backend",
+                value,
+            }),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+impl Default for Backend {
+    /// Demo when built with the `demo` feature or without the `bluetooth` feature
+    fn default() -> Self {
+        if cfg!(all(feature = "bluetooth", not(feature = "demo"))) {
+            Self::Bluetooth
+        } else {
+            Self::Demo
+        }
+    }
+}
+
+/// A [`DeviceRegistry`] for any [`Backend`]
+pub struct BackendDeviceRegistry<FuturesType: Futures> {
+    inner: InnerRegistry<FuturesType>,
+}
+
+enum InnerRegistry<FuturesType: Futures> {
+    #[cfg(feature = "bluetooth")]
+    Bluetooth(Box<SoundcoreDeviceRegistry<PlatformConnectionRegistry, FuturesType>>),
+    Demo(DemoDeviceRegistry<FuturesType>),
+}
+
+impl<FuturesType: Futures> BackendDeviceRegistry<FuturesType> {
+    /// `handle` is used for bluetooth. If it is None, a runtime is created for it.
+    pub async fn new(
+        backend: Backend,
+        handle: Option<tokio::runtime::Handle>,
+    ) -> crate::Result<Self> {
+        let inner = match backend {
+            #[cfg(feature = "bluetooth")]
+            Backend::Bluetooth => {
+                let connection_registry =
+                    crate::soundcore_device::connection::new_platform_connection_registry(handle)
+                        .await?;
+                InnerRegistry::Bluetooth(Box::new(
+                    SoundcoreDeviceRegistry::new(connection_registry).await?,
+                ))
+            }
+            #[cfg(not(feature = "bluetooth"))]
+            Backend::Bluetooth => {
+                std::mem::drop(handle);
+                return Err(crate::Error::FeatureNotSupported {
+                    feature_name: "bluetooth",
+                });
+            }
+            Backend::Demo => InnerRegistry::Demo(DemoDeviceRegistry::new()),
+        };
+        Ok(Self { inner })
+    }
+}
+
+impl<FuturesType: Futures> DeviceRegistry for BackendDeviceRegistry<FuturesType> {
+    type DeviceType = BackendDevice<FuturesType>;
+    type DescriptorType = GenericDeviceDescriptor;
+
+    async fn device_descriptors(&self) -> crate::Result<Vec<Self::DescriptorType>> {
+        match &self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerRegistry::Bluetooth(registry) => registry.device_descriptors().await,
+            InnerRegistry::Demo(registry) => registry.device_descriptors().await,
+        }
+    }
+
+    async fn device(&self, mac_address: MacAddr6) -> crate::Result<Option<Rc<Self::DeviceType>>> {
+        let inner = match &self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerRegistry::Bluetooth(registry) => registry
+                .device(mac_address)
+                .await?
+                .map(InnerDevice::Bluetooth),
+            InnerRegistry::Demo(registry) => {
+                registry.device(mac_address).await?.map(InnerDevice::Demo)
+            }
+        };
+        Ok(inner.map(|inner| Rc::new(BackendDevice { inner })))
+    }
+
+    async fn bug_report(&self, mac_address: MacAddr6) -> crate::Result<Option<BugReport>> {
+        match &self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerRegistry::Bluetooth(registry) => registry.bug_report(mac_address).await,
+            InnerRegistry::Demo(registry) => registry.bug_report(mac_address).await,
+        }
+    }
+
+    async fn hear_id_history(&self) -> crate::Result<HearIdHistory> {
+        match &self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerRegistry::Bluetooth(registry) => registry.hear_id_history().await,
+            InnerRegistry::Demo(registry) => registry.hear_id_history().await,
+        }
+    }
+
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> crate::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerRegistry::Bluetooth(registry) => registry.load_hear_id_history(history).await,
+            InnerRegistry::Demo(registry) => registry.load_hear_id_history(history).await,
+        }
+    }
+}
+
+/// A device from a [`BackendDeviceRegistry`]
+pub struct BackendDevice<FuturesType: Futures> {
+    inner: InnerDevice<FuturesType>,
+}
+
+impl<FuturesType: Futures> Debug for BackendDevice<FuturesType> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+enum InnerDevice<FuturesType: Futures> {
+    #[cfg(feature = "bluetooth")]
+    Bluetooth(
+        Rc<
+            SoundcoreDevice<
+                <PlatformConnectionRegistry as crate::api::connection::ConnectionRegistry>::ConnectionType,
+                FuturesType,
+            >,
+        >,
+    ),
+    Demo(Rc<DemoDevice<FuturesType>>),
+}
+
+impl<FuturesType: Futures> Debug for InnerDevice<FuturesType> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "bluetooth")]
+            InnerDevice::Bluetooth(device) => device.fmt(f),
+            InnerDevice::Demo(device) => device.fmt(f),
+        }
+    }
+}
+
+/// Calls the same method on whichever device is inside
+macro_rules! delegate {
+    ($self:ident, $device:ident => $call:expr) => {
+        match &$self.inner {
+            #[cfg(feature = "bluetooth")]
+            InnerDevice::Bluetooth($device) => $call,
+            InnerDevice::Demo($device) => $call,
+        }
+    };
+}
+
+impl<FuturesType: Futures> Device for BackendDevice<FuturesType> {
+    async fn subscribe_to_state_updates(&self) -> watch::Receiver<DeviceState> {
+        delegate!(self, device => device.subscribe_to_state_updates().await)
+    }
+
+    async fn mac_address(&self) -> crate::Result<MacAddr6> {
+        delegate!(self, device => device.mac_address().await)
+    }
+
+    fn service_uuid(&self) -> Uuid {
+        delegate!(self, device => device.service_uuid())
+    }
+
+    async fn name(&self) -> crate::Result<String> {
+        delegate!(self, device => device.name().await)
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        delegate!(self, device => device.connection_status())
+    }
+
+    async fn state(&self) -> DeviceState {
+        delegate!(self, device => device.state().await)
+    }
+
+    async fn degraded_mode(&self) -> Option<DegradedMode> {
+        delegate!(self, device => device.degraded_mode().await)
+    }
+
+    async fn set_sound_modes(&self, sound_modes: SoundModes) -> crate::Result<()> {
+        delegate!(self, device => device.set_sound_modes(sound_modes).await)
+    }
+
+    async fn set_sound_modes_type_two(&self, sound_modes: SoundModesTypeTwo) -> crate::Result<()> {
+        delegate!(self, device => device.set_sound_modes_type_two(sound_modes).await)
+    }
+
+    async fn set_ambient_sound_mode_cycle(
+        &self,
+        cycle: AmbientSoundModeCycle,
+    ) -> crate::Result<()> {
+        delegate!(self, device => device.set_ambient_sound_mode_cycle(cycle).await)
+    }
+
+    async fn set_equalizer_configuration(
+        &self,
+        configuration: EqualizerConfiguration,
+    ) -> crate::Result<()> {
+        delegate!(self, device => device.set_equalizer_configuration(configuration).await)
+    }
+
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> crate::Result<()> {
+        delegate!(self, device => device.set_dynamic_range_compression(is_enabled).await)
+    }
+
+    async fn set_hear_id(&self, hear_id: HearId) -> crate::Result<()> {
+        delegate!(self, device => device.set_hear_id(hear_id).await)
+    }
+
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> crate::Result<()> {
+        delegate!(self, device => device.set_gender_and_age_range(gender, age_range).await)
+    }
+
+    async fn set_custom_button_model(
+        &self,
+        custom_button_model: CustomButtonModel,
+    ) -> crate::Result<()> {
+        delegate!(self, device => device.set_custom_button_model(custom_button_model).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use macaddr::MacAddr6;
+
+    use super::{Backend, BackendDeviceRegistry};
+    use crate::{
+        api::device::{Device, DeviceDescriptor, DeviceRegistry},
+        futures::TokioFutures,
+    };
+
+    #[test]
+    fn test_backend_names_are_case_insensitive() {
+        assert_eq!(Backend::Demo, Backend::from_str("Demo").unwrap());
+        assert_eq!(Backend::Bluetooth, Backend::from_str("bluetooth").unwrap());
+        assert!(Backend::from_str("replay").is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_demo_backend() {
+        let registry = BackendDeviceRegistry::<TokioFutures>::new(Backend::Demo, None)
+            .await
+            .unwrap();
+        let descriptors = registry.device_descriptors().await.unwrap();
+        assert_eq!(1, descriptors.len());
+        let device = registry
+            .device(descriptors[0].mac_address())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(MacAddr6::nil(), device.mac_address().await.unwrap());
+        assert_eq!("Demo Q30", device.name().await.unwrap());
+    }
+}
@@ -7,10 +7,13 @@ use crate::{
     devices::standard::{
         state::DeviceState,
         structures::{
-            AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, HearId, SoundModes,
-            SoundModesTypeTwo,
+            AgeRange, AmbientSoundModeCycle, CustomButtonModel, CustomNoiseCanceling,
+            EqualizerConfiguration, Gender, HearId, ManualNoiseCanceling,
+            NoiseCancelingAdaptiveSensitivityLevel, NoiseCancelingModeTypeTwo, SoundModes,
+            SoundModesTypeTwo, TransparencyMode,
         },
     },
+    soundcore_device::device::DegradedMode,
 };
 
 pub trait Device {
@@ -26,18 +29,131 @@ pub trait Device {
 
     async fn state(&self) -> DeviceState;
 
+    /// Set if the device's state could only be partially parsed, in which case frontends should
+    /// warn that some settings may be missing.
+    async fn degraded_mode(&self) -> Option<DegradedMode>;
+
     async fn set_sound_modes(&self, sound_modes: SoundModes) -> crate::Result<()>;
     async fn set_sound_modes_type_two(&self, sound_modes: SoundModesTypeTwo) -> crate::Result<()>;
 
+    /// Changes the transparency mode, leaving the rest of the [`SoundModes`] as they are. Fails
+    /// unless the device supports custom transparency.
+    async fn set_transparency_mode(
+        &self,
+        transparency_mode: TransparencyMode,
+    ) -> crate::Result<()> {
+        let sound_modes =
+            self.state()
+                .await
+                .sound_modes
+                .ok_or(crate::Error::FeatureNotSupported {
+                    feature_name: "sound modes",
+                })?;
+        self.set_sound_modes(SoundModes {
+            transparency_mode,
+            ..sound_modes
+        })
+        .await
+    }
+
+    /// Changes the custom noise canceling level, leaving the rest of the [`SoundModes`] as they
+    /// are. Fails unless the device supports custom noise canceling.
+    async fn set_custom_noise_canceling(
+        &self,
+        custom_noise_canceling: CustomNoiseCanceling,
+    ) -> crate::Result<()> {
+        let sound_modes =
+            self.state()
+                .await
+                .sound_modes
+                .ok_or(crate::Error::FeatureNotSupported {
+                    feature_name: "sound modes",
+                })?;
+        self.set_sound_modes(SoundModes {
+            custom_noise_canceling,
+            ..sound_modes
+        })
+        .await
+    }
+
+    /// Changes a single field of the current [`SoundModesTypeTwo`], leaving the rest as they are.
+    async fn modify_sound_modes_type_two(
+        &self,
+        modify: impl FnOnce(&mut SoundModesTypeTwo),
+    ) -> crate::Result<()> {
+        let mut sound_modes =
+            self.state()
+                .await
+                .sound_modes_type_two
+                .ok_or(crate::Error::FeatureNotSupported {
+                    feature_name: "sound modes type two",
+                })?;
+        modify(&mut sound_modes);
+        self.set_sound_modes_type_two(sound_modes).await
+    }
+
+    async fn set_noise_canceling_adaptive_sensitivity_level(
+        &self,
+        level: NoiseCancelingAdaptiveSensitivityLevel,
+    ) -> crate::Result<()> {
+        self.modify_sound_modes_type_two(|sound_modes| {
+            sound_modes.noise_canceling_adaptive_sensitivity_level = level
+        })
+        .await
+    }
+
+    async fn set_wind_noise_suppression(&self, is_enabled: bool) -> crate::Result<()> {
+        self.modify_sound_modes_type_two(|sound_modes| {
+            sound_modes.wind_noise_suppression = is_enabled
+        })
+        .await
+    }
+
+    async fn set_manual_noise_canceling(
+        &self,
+        manual_noise_canceling: ManualNoiseCanceling,
+    ) -> crate::Result<()> {
+        self.modify_sound_modes_type_two(|sound_modes| {
+            sound_modes.manual_noise_canceling = manual_noise_canceling
+        })
+        .await
+    }
+
+    async fn set_noise_canceling_mode_type_two(
+        &self,
+        noise_canceling_mode: NoiseCancelingModeTypeTwo,
+    ) -> crate::Result<()> {
+        self.modify_sound_modes_type_two(|sound_modes| {
+            sound_modes.noise_canceling_mode = noise_canceling_mode
+        })
+        .await
+    }
+
     async fn set_ambient_sound_mode_cycle(&self, cycle: AmbientSoundModeCycle)
         -> crate::Result<()>;
 
+    /// Changes made in quick succession may be coalesced, so that only the latest one is sent. In
+    /// that case, every caller gets the result of the write that sent its configuration or one
+    /// that replaced it, so a caller can get an error even though its own configuration was never
+    /// sent.
     async fn set_equalizer_configuration(
         &self,
         configuration: EqualizerConfiguration,
     ) -> crate::Result<()>;
 
+    /// Resends the current equalizer configuration with or without dynamic range compression.
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> crate::Result<()>;
+
     async fn set_hear_id(&self, hear_id: HearId) -> crate::Result<()>;
+
+    /// These are only sent to the device along with the hear id, so the current hear id is
+    /// resent. Fails if the device doesn't have a hear id.
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> crate::Result<()>;
+
     async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
@@ -2,6 +2,8 @@ use std::{fmt::Debug, rc::Rc};
 
 use macaddr::MacAddr6;
 
+use crate::{bug_report::BugReport, hear_id_history::HearIdHistory};
+
 use super::{Device, DeviceDescriptor};
 
 pub trait DeviceRegistry {
@@ -10,4 +12,11 @@ pub trait DeviceRegistry {
 
     async fn device_descriptors(&self) -> crate::Result<Vec<Self::DescriptorType>>;
     async fn device(&self, mac_address: MacAddr6) -> crate::Result<Option<Rc<Self::DeviceType>>>;
+    /// Collects diagnostic information from the device, even if its packets can't be parsed. Returns
+    /// None if the device doesn't exist.
+    async fn bug_report(&self, mac_address: MacAddr6) -> crate::Result<Option<BugReport>>;
+    /// HearID profiles read from or written to devices from this registry, for frontends to save.
+    async fn hear_id_history(&self) -> crate::Result<HearIdHistory>;
+    /// Adds profiles recorded earlier, such as a history saved by the frontend on its last run.
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> crate::Result<()>;
 }
@@ -0,0 +1,488 @@
+mod traffic_log;
+
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use nom::error::{VerboseError, VerboseErrorKind};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::connection::Connection,
+    device_profile::{DeviceFeatures, DeviceProfile},
+    devices::standard::{
+        packets::{
+            inbound::{
+                state_update_packet::StateUpdatePacket, FirmwareVersionUpdatePacket, InboundPacket,
+                TryIntoInboundPacket,
+            },
+            outbound::{RequestFirmwareVersionPacket, RequestStatePacket},
+        },
+        state::DeviceState,
+        structures::{FirmwareVersion, SerialNumber},
+    },
+    futures::Futures,
+    soundcore_device::{
+        device::{packet_io_controller::PacketIOController, Packet},
+        device_model::DeviceModel,
+    },
+};
+
+pub(crate) use traffic_log::TrafficLog;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum PacketDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct RecordedPacket {
+    pub direction: PacketDirection,
+    pub bytes: Vec<u8>,
+}
+
+/// Everything needed to add support for a device or firmware version that fails to parse, with
+/// the serial number redacted so that it can be attached to a public issue. The serial number
+/// contains the device's mac address, so all but the model number is replaced with `X`, including
+/// where it appears in the raw packets.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BugReport {
+    pub model: Option<DeviceModel>,
+    pub serial_number: Option<String>,
+    pub firmware_version: Option<FirmwareVersion>,
+    pub device_features: Option<DeviceFeatures>,
+    /// Body of the state update packet, without the header or checksum.
+    pub state_update_body: Option<Vec<u8>>,
+    /// Why the state update packet failed to parse, from the innermost parser outwards. Empty if
+    /// it parsed successfully.
+    pub parse_errors: Vec<String>,
+    /// Traffic from while the report was being collected.
+    pub packets: Vec<RecordedPacket>,
+    /// Traffic from before the report was collected, such as while the device was last connected,
+    /// for problems that only show up after changing settings.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recent_packets: Vec<RecordedPacket>,
+}
+
+impl BugReport {
+    /// Time to wait for unprompted packets after the requests have been answered, since some
+    /// devices send extra state when a new client subscribes.
+    const LISTEN_DURATION: Duration = Duration::from_secs(1);
+
+    /// Requests the state and firmware version from the device without assuming that either
+    /// response can be parsed. `recent_packets` is included alongside the packets from collecting
+    /// the report.
+    pub async fn collect<ConnectionType, FuturesType>(
+        connection: Arc<ConnectionType>,
+        recent_packets: Vec<RecordedPacket>,
+    ) -> crate::Result<Self>
+    where
+        ConnectionType: Connection,
+        FuturesType: Futures,
+    {
+        let traffic_log = TrafficLog::default();
+        let (controller, _) =
+            PacketIOController::<_, FuturesType>::new(connection, traffic_log.clone()).await?;
+
+        let state_update = controller
+            .send(&RequestStatePacket::new().into())
+            .await
+            .inspect_err(|err| tracing::debug!("failed to request state: {err:?}"))
+            .ok();
+        let firmware_version_update: Option<FirmwareVersionUpdatePacket> = controller
+            .send(&RequestFirmwareVersionPacket::new().into())
+            .await
+            .inspect_err(|err| tracing::debug!("failed to request firmware version: {err:?}"))
+            .ok()
+            .and_then(|packet| packet.try_into_inbound_packet().ok());
+        FuturesType::sleep(Self::LISTEN_DURATION).await;
+        drop(controller);
+
+        let (parsed_state, parse_errors) = match &state_update {
+            Some(packet) => Self::parse_state_update(packet),
+            None => (None, Vec::new()),
+        };
+        let serial_number = firmware_version_update
+            .as_ref()
+            .map(|packet| packet.serial_number.to_owned())
+            .or_else(|| parsed_state.as_ref()?.serial_number.to_owned());
+        let model = serial_number
+            .as_ref()
+            .and_then(DeviceModel::from_serial_number);
+        let device_features = serial_number
+            .as_ref()
+            .and_then(DeviceProfile::from_serial_number)
+            .or(parsed_state.as_ref().map(|state| state.device_profile))
+            .map(|profile| profile.features);
+        let firmware_version = firmware_version_update
+            .as_ref()
+            .map(|packet| {
+                packet
+                    .left_firmware_version
+                    .max(packet.right_firmware_version)
+            })
+            .or_else(|| parsed_state.as_ref()?.firmware_version);
+
+        let mut report = Self {
+            model,
+            serial_number: None,
+            firmware_version,
+            device_features,
+            state_update_body: state_update.map(|packet| packet.body),
+            parse_errors,
+            packets: traffic_log.packets(),
+            recent_packets,
+        };
+        if let Some(serial_number) = serial_number {
+            report.redact(&serial_number);
+        }
+        Ok(report)
+    }
+
+    /// Builds a report from state that is already known, such as for devices that aren't backed by
+    /// a real connection.
+    pub fn from_state(state: &DeviceState) -> Self {
+        let mut report = Self {
+            model: state
+                .serial_number
+                .as_ref()
+                .and_then(DeviceModel::from_serial_number),
+            serial_number: None,
+            firmware_version: state.firmware_version,
+            device_features: Some(state.device_features),
+            state_update_body: None,
+            parse_errors: Vec::new(),
+            packets: Vec::new(),
+            recent_packets: Vec::new(),
+        };
+        if let Some(serial_number) = &state.serial_number {
+            report.redact(serial_number);
+        }
+        report
+    }
+
+    fn parse_state_update(packet: &Packet) -> (Option<StateUpdatePacket>, Vec<String>) {
+        match StateUpdatePacket::take::<VerboseError<_>>(&packet.body) {
+            Ok((_, parsed)) => (Some(parsed), Vec::new()),
+            Err(nom::Err::Incomplete(needed)) => (None, vec![format!("incomplete: {needed:?}")]),
+            Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+                let errors = err
+                    .errors
+                    .iter()
+                    .map(|(input, kind)| {
+                        let offset = packet.body.len() - input.len();
+                        let description = match kind {
+                            VerboseErrorKind::Context(context) => format!("in {context}"),
+                            VerboseErrorKind::Char(c) => format!("expected '{c}'"),
+                            VerboseErrorKind::Nom(kind) => format!("{kind:?}"),
+                        };
+                        format!("at byte {offset}: {description}")
+                    })
+                    .collect();
+                // Fall back to the prefix parser so that features can still be included
+                (
+                    StateUpdatePacket::take_known_prefix(&packet.body).map(|(parsed, _)| parsed),
+                    errors,
+                )
+            }
+        }
+    }
+
+    fn redact(&mut self, serial_number: &SerialNumber) {
+        let original = serial_number.as_str();
+        if original.is_empty() {
+            return;
+        }
+        let redacted = redact_serial_number(serial_number);
+        let buffers = self.state_update_body.iter_mut().chain(
+            self.packets
+                .iter_mut()
+                .chain(self.recent_packets.iter_mut())
+                .map(|packet| &mut packet.bytes),
+        );
+        for buffer in buffers {
+            replace_all(buffer, original.as_bytes(), redacted.as_bytes());
+        }
+        self.serial_number = Some(redacted);
+    }
+}
+
+/// Keeps the model number, since it's needed to identify the device, but hides the mac address.
+fn redact_serial_number(serial_number: &SerialNumber) -> String {
+    let num_redacted = if DeviceModel::from_serial_number(serial_number).is_some() {
+        12
+    } else {
+        serial_number.as_str().len()
+    };
+    serial_number
+        .as_str()
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i < num_redacted { 'X' } else { c })
+        .collect()
+}
+
+fn replace_all(haystack: &mut [u8], needle: &[u8], replacement: &[u8]) {
+    debug_assert_eq!(needle.len(), replacement.len());
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        if &haystack[i..i + needle.len()] == needle {
+            haystack[i..i + needle.len()].copy_from_slice(replacement);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+}
+
+fn write_hex(f: &mut std::fmt::Formatter<'_>, bytes: &[u8]) -> std::fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if i != 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+impl Display for BugReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unknown = || "unknown".to_owned();
+        writeln!(f, "OpenSCQ30 bug report")?;
+        writeln!(f, "Library version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(
+            f,
+            "Model: {}",
+            self.model
+                .map(|model| model.to_string())
+                .unwrap_or_else(unknown)
+        )?;
+        writeln!(
+            f,
+            "Serial number: {}",
+            self.serial_number.to_owned().unwrap_or_else(unknown)
+        )?;
+        writeln!(
+            f,
+            "Firmware version: {}",
+            self.firmware_version
+                .map(|version| version.to_string())
+                .unwrap_or_else(unknown)
+        )?;
+
+        writeln!(f, "\nState update body:")?;
+        match &self.state_update_body {
+            Some(body) => {
+                write_hex(f, body)?;
+                writeln!(f)?;
+            }
+            None => writeln!(f, "not received")?,
+        }
+
+        writeln!(f, "\nParse errors:")?;
+        if self.parse_errors.is_empty() {
+            writeln!(f, "none")?;
+        }
+        for error in &self.parse_errors {
+            writeln!(f, "{error}")?;
+        }
+
+        writeln!(f, "\nDevice features:")?;
+        match &self.device_features {
+            Some(features) => writeln!(f, "{features:#?}")?,
+            None => writeln!(f, "unknown")?,
+        }
+
+        writeln!(f, "\nPackets:")?;
+        write_packets(f, &self.packets)?;
+
+        writeln!(f, "\nRecent packets:")?;
+        if self.recent_packets.is_empty() {
+            writeln!(f, "none")?;
+        }
+        write_packets(f, &self.recent_packets)
+    }
+}
+
+fn write_packets(f: &mut std::fmt::Formatter<'_>, packets: &[RecordedPacket]) -> std::fmt::Result {
+    for packet in packets {
+        let direction = match packet.direction {
+            PacketDirection::Inbound => "<-",
+            PacketDirection::Outbound => "->",
+        };
+        write!(f, "{direction} ")?;
+        write_hex(f, &packet.bytes)?;
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::sync::mpsc;
+
+    use super::{BugReport, PacketDirection, RecordedPacket};
+    use crate::{
+        devices::standard::packets::inbound::{
+            state_update_packet::StateUpdatePacket, FirmwareVersionUpdatePacket, InboundPacket,
+        },
+        futures::TokioFutures,
+        soundcore_device::{device::Packet, device_model::DeviceModel},
+        stub::connection::StubConnection,
+    };
+
+    const SERIAL_NUMBER: &str = "0123456789AB3028";
+
+    fn state_update_body(serial_number: &str) -> Vec<u8> {
+        let mut body = vec![
+            0x05, 0x00, 0xfe, 0xfe, 0x3c, 0xb4, 0x8f, 0xa0, 0x8e, 0xb4, 0x74, 0x88, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x30, 0x32, 0x2e,
+            0x33, 0x30,
+        ];
+        body.extend(serial_number.as_bytes());
+        body
+    }
+
+    async fn collect(
+        state_update_body: Vec<u8>,
+        firmware_version_body: Option<Vec<u8>>,
+    ) -> BugReport {
+        collect_with_recent_packets(state_update_body, firmware_version_body, Vec::new()).await
+    }
+
+    async fn collect_with_recent_packets(
+        state_update_body: Vec<u8>,
+        firmware_version_body: Option<Vec<u8>>,
+        recent_packets: Vec<RecordedPacket>,
+    ) -> BugReport {
+        let connection = Arc::new(StubConnection::new());
+        let (sender, receiver) = mpsc::channel(10);
+        connection.set_inbound_packets_channel(Ok(receiver)).await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(
+                    Packet {
+                        command: StateUpdatePacket::command(),
+                        body: state_update_body,
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+            if let Some(body) = firmware_version_body {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                sender
+                    .send(
+                        Packet {
+                            command: FirmwareVersionUpdatePacket::command(),
+                            body,
+                        }
+                        .bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+        BugReport::collect::<_, TokioFutures>(connection, recent_packets)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_redacts_serial_number() {
+        let firmware_version_body = format!("02.3002.30{SERIAL_NUMBER}").into_bytes();
+        let report = collect(
+            state_update_body(SERIAL_NUMBER),
+            Some(firmware_version_body),
+        )
+        .await;
+
+        assert_eq!(Some("XXXXXXXXXXXX3028".to_owned()), report.serial_number);
+        assert_eq!(Some(DeviceModel::A3028), report.model);
+        assert_eq!(
+            Some(state_update_body("XXXXXXXXXXXX3028")),
+            report.state_update_body
+        );
+        assert!(report.parse_errors.is_empty());
+        assert!(report.device_features.is_some());
+        let text = report.to_string();
+        assert!(!text.contains(SERIAL_NUMBER));
+        assert!(!text.contains(&hex_string(SERIAL_NUMBER.as_bytes())));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_records_packets() {
+        let report = collect(state_update_body(SERIAL_NUMBER), None).await;
+        // state request, state response, then firmware version requests that time out
+        assert_eq!(PacketDirection::Outbound, report.packets[0].direction);
+        assert_eq!(PacketDirection::Inbound, report.packets[1].direction);
+        assert!(report.packets[2..]
+            .iter()
+            .all(|packet| packet.direction == PacketDirection::Outbound));
+        assert!(report
+            .packets
+            .iter()
+            .all(|packet| !contains(&packet.bytes, SERIAL_NUMBER.as_bytes())));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_includes_and_redacts_recent_packets() {
+        let recent_packet = RecordedPacket {
+            direction: PacketDirection::Inbound,
+            bytes: Packet {
+                command: StateUpdatePacket::command(),
+                body: state_update_body(SERIAL_NUMBER),
+            }
+            .bytes(),
+        };
+        let report = collect_with_recent_packets(
+            state_update_body(SERIAL_NUMBER),
+            None,
+            vec![recent_packet.to_owned()],
+        )
+        .await;
+
+        assert_eq!(1, report.recent_packets.len());
+        assert_eq!(recent_packet.direction, report.recent_packets[0].direction);
+        assert!(!contains(
+            &report.recent_packets[0].bytes,
+            SERIAL_NUMBER.as_bytes()
+        ));
+        assert!(report.to_string().contains("Recent packets:"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_includes_parse_errors() {
+        let mut body = state_update_body(SERIAL_NUMBER);
+        body.truncate(20);
+        let report = collect(body.to_owned(), None).await;
+        assert!(!report.parse_errors.is_empty());
+        assert_eq!(None, report.device_features);
+        assert_eq!(Some(body), report.state_update_body);
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    fn hex_string(bytes: &[u8]) -> String {
+        bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use super::{PacketDirection, RecordedPacket};
+
+/// The most recent packets sent to and received from a device. Clones share the same buffer, so
+/// traffic recorded while a device is connected is still available after it is dropped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TrafficLog {
+    packets: Arc<Mutex<VecDeque<RecordedPacket>>>,
+}
+
+impl TrafficLog {
+    const MAX_PACKETS: usize = 100;
+
+    pub fn record(&self, direction: PacketDirection, data: &[u8]) {
+        let mut packets = self.lock();
+        if packets.len() >= Self::MAX_PACKETS {
+            packets.pop_front();
+        }
+        packets.push_back(RecordedPacket {
+            direction,
+            bytes: data.to_vec(),
+        });
+    }
+
+    pub fn packets(&self) -> Vec<RecordedPacket> {
+        self.lock().iter().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<RecordedPacket>> {
+        self.packets
+            .lock()
+            .expect("lock is never held across an await or while panicking")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PacketDirection, TrafficLog};
+
+    #[test]
+    fn it_keeps_only_the_most_recent_packets() {
+        let log = TrafficLog::default();
+        for i in 0..=TrafficLog::MAX_PACKETS {
+            log.record(PacketDirection::Inbound, &[i as u8]);
+        }
+        let packets = log.clone().packets();
+        assert_eq!(TrafficLog::MAX_PACKETS, packets.len());
+        assert_eq!(vec![1], packets[0].bytes);
+        assert_eq!(
+            vec![TrafficLog::MAX_PACKETS as u8],
+            packets.last().unwrap().bytes
+        );
+    }
+}
@@ -7,10 +7,12 @@ use uuid::Uuid;
 use crate::{
     api::{connection::ConnectionStatus, device::Device},
     device_profile::{
-        DeviceFeatures, NoiseCancelingModeType, SoundModeProfile, TransparencyModeType,
+        ButtonCapabilities, DeviceFeatures, NoiseCancelingModeType, SoundModeProfile,
+        TransparencyModeType,
     },
     devices::standard::{state::DeviceState, structures::*},
     futures::Futures,
+    soundcore_device::device::DegradedMode,
 };
 
 pub struct DemoDevice<FuturesType> {
@@ -36,7 +38,7 @@ where
                 }),
                 has_hear_id: true,
                 num_equalizer_channels: 2,
-                num_equalizer_bands: 10,
+                num_equalizer_bands: 8,
                 has_dynamic_range_compression: true,
                 dynamic_range_compression_min_firmware_version: None,
                 has_custom_button_model: true,
@@ -44,6 +46,7 @@ where
                 has_touch_tone: true,
                 has_auto_power_off: true,
                 has_ambient_sound_mode_cycle: true,
+                button_capabilities: ButtonCapabilities::STANDARD,
             },
             battery: SingleBattery {
                 is_charging: IsBatteryCharging::No,
@@ -59,8 +62,8 @@ where
                 transparency_mode: Default::default(),
                 custom_noise_canceling: Default::default(),
             }),
-            gender: Some(Gender(0)),
-            age_range: Some(AgeRange(0)),
+            gender: Some(Gender::Female),
+            age_range: Some(AgeRange::Under20),
             custom_button_model: Some(CustomButtonModel {
                 left_double_click: TwsButtonAction {
                     tws_connected_action: ButtonAction::NextSong,
@@ -103,6 +106,7 @@ where
             serial_number: Some(SerialNumber("0123456789ABCDEF".into())),
             ambient_sound_mode_cycle: Some(AmbientSoundModeCycle::default()),
             sound_modes_type_two: Some(SoundModesTypeTwo::default()),
+            is_dynamic_range_compression_enabled: true,
         });
 
         let (connection_status_sender, _) = watch::channel(ConnectionStatus::Connected);
@@ -145,17 +149,24 @@ where
         self.state_sender.lock().await.borrow().to_owned()
     }
 
+    async fn degraded_mode(&self) -> Option<DegradedMode> {
+        None
+    }
+
     async fn set_sound_modes(&self, sound_modes: SoundModes) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
-        if state.sound_modes.is_none() {
+        let (Some(sound_mode_profile), Some(prev_sound_modes)) =
+            (state.device_features.sound_mode, state.sound_modes)
+        else {
             return Err(crate::Error::FeatureNotSupported {
                 feature_name: "sound modes",
             });
-        }
-        if state.sound_modes == Some(sound_modes) {
+        };
+        if prev_sound_modes == sound_modes {
             return Ok(());
         }
+        sound_mode_profile.validate_sound_modes(&prev_sound_modes, &sound_modes)?;
         tracing::info!("set sound modes to {sound_modes:?}");
         state_sender.send_replace(DeviceState {
             sound_modes: Some(sound_modes),
@@ -211,6 +222,19 @@ where
     ) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
+        let num_bands = equalizer_configuration
+            .volume_adjustments()
+            .adjustments()
+            .len();
+        if num_bands != state.device_features.num_equalizer_bands {
+            return Err(crate::Error::InvalidValue {
+                name: "number of equalizer bands",
+                value: format!(
+                    "{num_bands}, expected {}",
+                    state.device_features.num_equalizer_bands
+                ),
+            });
+        }
         if state.equalizer_configuration == equalizer_configuration {
             return Ok(());
         }
@@ -222,6 +246,25 @@ where
         Ok(())
     }
 
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> crate::Result<()> {
+        let state_sender = self.state_sender.lock().await;
+        let state = state_sender.borrow().to_owned();
+        if !state.supports_dynamic_range_compression() {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "dynamic range compression",
+            });
+        }
+        if state.is_dynamic_range_compression_enabled == is_enabled {
+            return Ok(());
+        }
+        tracing::info!("set dynamic range compression to {is_enabled}");
+        state_sender.send_replace(DeviceState {
+            is_dynamic_range_compression_enabled: is_enabled,
+            ..state
+        });
+        Ok(())
+    }
+
     async fn set_hear_id(&self, hear_id: HearId) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
@@ -241,20 +284,48 @@ where
         Ok(())
     }
 
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> crate::Result<()> {
+        let state_sender = self.state_sender.lock().await;
+        let state = state_sender.borrow().to_owned();
+        if state.hear_id.is_none() {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "hear id",
+            });
+        }
+        if state.gender == Some(gender) && state.age_range == Some(age_range) {
+            return Ok(());
+        }
+        tracing::info!("set gender to {gender:?} and age range to {age_range:?}");
+        state_sender.send_replace(DeviceState {
+            gender: Some(gender),
+            age_range: Some(age_range),
+            ..state
+        });
+        Ok(())
+    }
+
     async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
     ) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
-        if state.custom_button_model.is_none() {
+        let Some(prev_custom_button_model) = state.custom_button_model else {
             return Err(crate::Error::FeatureNotSupported {
                 feature_name: "custom button model",
             });
-        }
-        if state.custom_button_model == Some(custom_button_model) {
+        };
+        if prev_custom_button_model == custom_button_model {
             return Ok(());
         }
+        state
+            .device_features
+            .button_capabilities
+            .validate(&prev_custom_button_model, &custom_button_model)?;
         tracing::info!("set custom button model to {custom_button_model:?}");
         state_sender.send_replace(DeviceState {
             custom_button_model: Some(custom_button_model),
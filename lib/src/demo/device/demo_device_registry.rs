@@ -1,10 +1,12 @@
-use std::{marker::PhantomData, rc::Rc};
+use std::{marker::PhantomData, rc::Rc, sync::Mutex};
 
 use macaddr::MacAddr6;
 
 use crate::{
-    api::device::{DeviceRegistry, GenericDeviceDescriptor},
+    api::device::{Device, DeviceRegistry, GenericDeviceDescriptor},
+    bug_report::BugReport,
     futures::Futures,
+    hear_id_history::HearIdHistory,
 };
 
 use super::demo_device::DemoDevice;
@@ -14,6 +16,9 @@ pub struct DemoDeviceRegistry<FuturesType>
 where
     FuturesType: Futures,
 {
+    // Demo devices aren't real, so nothing is recorded here, but loaded history is kept so that
+    // frontends behave the same as with real devices.
+    hear_id_history: Mutex<HearIdHistory>,
     futures: PhantomData<FuturesType>,
 }
 
@@ -26,6 +31,7 @@ where
 
     pub fn new() -> Self {
         Self {
+            hear_id_history: Default::default(),
             futures: PhantomData,
         }
     }
@@ -54,4 +60,27 @@ where
             Ok(None)
         }
     }
+
+    async fn bug_report(&self, mac_address: MacAddr6) -> crate::Result<Option<BugReport>> {
+        match self.device(mac_address).await? {
+            Some(device) => Ok(Some(BugReport::from_state(&device.state().await))),
+            None => Ok(None),
+        }
+    }
+
+    async fn hear_id_history(&self) -> crate::Result<HearIdHistory> {
+        Ok(self
+            .hear_id_history
+            .lock()
+            .expect("lock is never held across an await")
+            .to_owned())
+    }
+
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> crate::Result<()> {
+        self.hear_id_history
+            .lock()
+            .expect("lock is never held across an await")
+            .merge(history);
+        Ok(())
+    }
 }
@@ -16,7 +16,10 @@ use crate::{
         a3936::device_profile::A3936_DEVICE_PROFILE,
         a3945::device_profile::A3945_DEVICE_PROFILE,
         a3951::device_profile::A3951_DEVICE_PROFILE,
-        standard::structures::{FirmwareVersion, SerialNumber},
+        standard::structures::{
+            ButtonAction, CustomButtonModel, FirmwareVersion, NoTwsButtonAction,
+            NoiseCancelingMode, SerialNumber, SoundModes, TwsButtonAction,
+        },
     },
     soundcore_device::{
         device::device_implementation::DeviceImplementation, device_model::DeviceModel,
@@ -45,6 +48,7 @@ pub struct DeviceFeatures {
     pub has_auto_power_off: bool,
     pub has_ambient_sound_mode_cycle: bool,
     pub dynamic_range_compression_min_firmware_version: Option<FirmwareVersion>,
+    pub button_capabilities: ButtonCapabilities,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
@@ -55,6 +59,42 @@ pub struct SoundModeProfile {
     pub transparency_mode_type: TransparencyModeType,
 }
 
+impl SoundModeProfile {
+    /// Checks that going from `previous` to `sound_modes` only changes settings the device has.
+    /// Settings that are left unchanged are accepted regardless, since devices report values even
+    /// for settings they don't have.
+    pub fn validate_sound_modes(
+        &self,
+        previous: &SoundModes,
+        sound_modes: &SoundModes,
+    ) -> crate::Result<()> {
+        if self.transparency_mode_type != TransparencyModeType::Custom
+            && previous.transparency_mode != sound_modes.transparency_mode
+        {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "custom transparency",
+            });
+        }
+        if self.noise_canceling_mode_type == NoiseCancelingModeType::None
+            && previous.noise_canceling_mode != sound_modes.noise_canceling_mode
+        {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "noise canceling",
+            });
+        }
+        if self.noise_canceling_mode_type != NoiseCancelingModeType::Custom
+            && (previous.custom_noise_canceling != sound_modes.custom_noise_canceling
+                || (previous.noise_canceling_mode != sound_modes.noise_canceling_mode
+                    && sound_modes.noise_canceling_mode == NoiseCancelingMode::Custom))
+        {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "custom noise canceling",
+            });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
@@ -74,14 +114,142 @@ pub enum TransparencyModeType {
     Custom,
 }
 
+/// Which gestures can be assigned an action and which actions can be assigned to them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ButtonCapabilities {
+    pub has_single_click: bool,
+    pub has_double_click: bool,
+    pub has_long_press: bool,
+    /// Bit n is set if the action with id n is supported
+    pub supported_actions: u16,
+}
+
+impl ButtonCapabilities {
+    pub const NONE: Self = Self {
+        has_single_click: false,
+        has_double_click: false,
+        has_long_press: false,
+        supported_actions: 0,
+    };
+    pub const STANDARD: Self = Self {
+        has_single_click: true,
+        has_double_click: true,
+        has_long_press: true,
+        supported_actions: 0b111_1111,
+    };
+    /// For devices without ambient sound modes, which have nothing for the ambient sound mode
+    /// action to cycle through
+    pub const WITHOUT_AMBIENT_SOUND_MODE: Self = Self {
+        supported_actions: Self::STANDARD.supported_actions & !(1 << 4),
+        ..Self::STANDARD
+    };
+
+    pub fn supports_action(&self, action: ButtonAction) -> bool {
+        let id = u8::from(action);
+        id < 16 && self.supported_actions & (1 << id) != 0
+    }
+
+    /// Checks that every enabled gesture that differs from `previous` is supported and is
+    /// assigned a supported action. Unchanged gestures are accepted as is, since they may hold
+    /// actions the device reported that we don't know about yet.
+    pub fn validate(
+        &self,
+        previous: &CustomButtonModel,
+        custom_button_model: &CustomButtonModel,
+    ) -> crate::Result<()> {
+        let single_clicks = [
+            (
+                "left single click",
+                previous.left_single_click,
+                custom_button_model.left_single_click,
+            ),
+            (
+                "right single click",
+                previous.right_single_click,
+                custom_button_model.right_single_click,
+            ),
+        ];
+        for (gesture, previous, button_action) in single_clicks {
+            let NoTwsButtonAction { action, is_enabled } = button_action;
+            if is_enabled && button_action != previous {
+                self.validate_gesture(gesture, self.has_single_click, &[action])?;
+            }
+        }
+
+        let tws_gestures = [
+            (
+                "left double click",
+                self.has_double_click,
+                previous.left_double_click,
+                custom_button_model.left_double_click,
+            ),
+            (
+                "left long press",
+                self.has_long_press,
+                previous.left_long_press,
+                custom_button_model.left_long_press,
+            ),
+            (
+                "right double click",
+                self.has_double_click,
+                previous.right_double_click,
+                custom_button_model.right_double_click,
+            ),
+            (
+                "right long press",
+                self.has_long_press,
+                previous.right_long_press,
+                custom_button_model.right_long_press,
+            ),
+        ];
+        for (gesture, is_supported, previous, button_action) in tws_gestures {
+            let TwsButtonAction {
+                tws_connected_action,
+                tws_disconnected_action,
+                is_enabled,
+            } = button_action;
+            if is_enabled && button_action != previous {
+                self.validate_gesture(
+                    gesture,
+                    is_supported,
+                    &[tws_connected_action, tws_disconnected_action],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_gesture(
+        &self,
+        gesture: &'static str,
+        is_supported: bool,
+        actions: &[ButtonAction],
+    ) -> crate::Result<()> {
+        if !is_supported {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: gesture,
+            });
+        }
+        if let Some(action) = actions
+            .iter()
+            .find(|action| !self.supports_action(**action))
+        {
+            return Err(crate::Error::InvalidValue {
+                name: gesture,
+                value: format!("{action} is not supported by this device"),
+            });
+        }
+        Ok(())
+    }
+}
+
 pub enum ToDeviceProfileError {
     ModelDoesNotExist,
     ProfileDoesNotExist,
 }
 
-// TODO decide if this is worth keeping, since it's non-trivial to determine a device's serial
-// number without knowing in advance what device it is
-#[allow(dead_code)]
 const DEVICE_PROFILES: &[&DeviceProfile] = &[
     &A3027_DEVICE_PROFILE,
     &A3028_DEVICE_PROFILE,
@@ -96,7 +264,6 @@ const DEVICE_PROFILES: &[&DeviceProfile] = &[
     &A3951_DEVICE_PROFILE,
 ];
 
-#[allow(dead_code)]
 impl DeviceProfile {
     pub fn from_serial_number(serial_number: &SerialNumber) -> Option<&'static DeviceProfile> {
         let model = DeviceModel::from_serial_number(serial_number)?;
@@ -116,6 +283,9 @@ mod tests {
     use std::collections::HashSet;
 
     use super::*;
+    use crate::devices::standard::structures::{
+        AmbientSoundMode, CustomNoiseCanceling, TransparencyMode,
+    };
 
     #[test]
     fn test_device_models_to_profiles_is_one_to_one() {
@@ -129,4 +299,92 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_button_capabilities_match_features() {
+        for profile in DEVICE_PROFILES {
+            let features = profile.features;
+            let capabilities = features.button_capabilities;
+            assert_eq!(
+                features.has_custom_button_model,
+                capabilities != ButtonCapabilities::NONE,
+                "{:?}",
+                profile.compatible_models,
+            );
+            // A3936 only has type two sound modes, which the ambient sound mode cycle applies to
+            let has_ambient_sound_modes =
+                features.sound_mode.is_some() || features.has_ambient_sound_mode_cycle;
+            assert_eq!(
+                features.has_custom_button_model && has_ambient_sound_modes,
+                capabilities.supports_action(ButtonAction::AmbientSoundMode),
+                "{:?}",
+                profile.compatible_models,
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_sound_modes_allows_unchanged_settings() {
+        let profile = SoundModeProfile {
+            noise_canceling_mode_type: NoiseCancelingModeType::Basic,
+            transparency_mode_type: TransparencyModeType::Basic,
+        };
+        let previous = SoundModes {
+            custom_noise_canceling: CustomNoiseCanceling::ADAPTIVE,
+            ..Default::default()
+        };
+        let sound_modes = SoundModes {
+            ambient_sound_mode: AmbientSoundMode::Transparency,
+            ..previous
+        };
+        assert!(profile
+            .validate_sound_modes(&previous, &sound_modes)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_sound_modes_rejects_unsupported_changes() {
+        let profile = SoundModeProfile {
+            noise_canceling_mode_type: NoiseCancelingModeType::Basic,
+            transparency_mode_type: TransparencyModeType::Basic,
+        };
+        let previous = SoundModes::default();
+        for sound_modes in [
+            SoundModes {
+                transparency_mode: TransparencyMode::FullyTransparent,
+                ..previous
+            },
+            SoundModes {
+                custom_noise_canceling: CustomNoiseCanceling::new(5),
+                ..previous
+            },
+            SoundModes {
+                noise_canceling_mode: NoiseCancelingMode::Custom,
+                ..previous
+            },
+        ] {
+            assert!(matches!(
+                profile.validate_sound_modes(&previous, &sound_modes),
+                Err(crate::Error::FeatureNotSupported { .. }),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_sound_modes_allows_custom_changes() {
+        let profile = SoundModeProfile {
+            noise_canceling_mode_type: NoiseCancelingModeType::Custom,
+            transparency_mode_type: TransparencyModeType::Custom,
+        };
+        let previous = SoundModes::default();
+        let sound_modes = SoundModes {
+            noise_canceling_mode: NoiseCancelingMode::Custom,
+            transparency_mode: TransparencyMode::FullyTransparent,
+            custom_noise_canceling: CustomNoiseCanceling::ADAPTIVE,
+            ..previous
+        };
+        assert!(profile
+            .validate_sound_modes(&previous, &sound_modes)
+            .is_ok());
+    }
 }
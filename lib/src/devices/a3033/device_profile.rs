@@ -1,5 +1,5 @@
 use crate::{
-    device_profile::{DeviceFeatures, DeviceProfile},
+    device_profile::{ButtonCapabilities, DeviceFeatures, DeviceProfile},
     devices::standard::implementation::StandardImplementation,
     soundcore_device::device_model::DeviceModel,
 };
@@ -19,6 +19,7 @@ pub(crate) const A3033_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: false,
         has_auto_power_off: false,
         has_ambient_sound_mode_cycle: false,
+        button_capabilities: ButtonCapabilities::NONE,
     },
     compatible_models: &[DeviceModel::A3033],
     implementation: || StandardImplementation::new::<A3033StateUpdatePacket>(),
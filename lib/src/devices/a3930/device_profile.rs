@@ -1,7 +1,7 @@
 use crate::{
     device_profile::{
-        DeviceFeatures, DeviceProfile, NoiseCancelingModeType, SoundModeProfile,
-        TransparencyModeType,
+        ButtonCapabilities, DeviceFeatures, DeviceProfile, NoiseCancelingModeType,
+        SoundModeProfile, TransparencyModeType,
     },
     devices::standard::implementation::StandardImplementation,
     soundcore_device::device_model::DeviceModel,
@@ -25,6 +25,7 @@ pub(crate) const A3930_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: false,
         has_auto_power_off: false,
         has_ambient_sound_mode_cycle: false,
+        button_capabilities: ButtonCapabilities::NONE,
     },
     compatible_models: &[DeviceModel::A3930],
     implementation: || StandardImplementation::new::<A3930StateUpdatePacket>(),
@@ -1,7 +1,7 @@
 use crate::{
     device_profile::{
-        DeviceFeatures, DeviceProfile, NoiseCancelingModeType, SoundModeProfile,
-        TransparencyModeType,
+        ButtonCapabilities, DeviceFeatures, DeviceProfile, NoiseCancelingModeType,
+        SoundModeProfile, TransparencyModeType,
     },
     devices::standard::{implementation::StandardImplementation, structures::FirmwareVersion},
     soundcore_device::device_model::DeviceModel,
@@ -25,6 +25,7 @@ pub(crate) const A3931_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: true,
         has_auto_power_off: true,
         has_ambient_sound_mode_cycle: false,
+        button_capabilities: ButtonCapabilities::STANDARD,
     },
     compatible_models: &[DeviceModel::A3931, DeviceModel::A3935],
     implementation: || StandardImplementation::new::<A3931StateUpdatePacket>(),
@@ -4,8 +4,8 @@ use nom::error::VerboseError;
 
 use crate::{
     device_profile::{
-        DeviceFeatures, DeviceProfile, NoiseCancelingModeType, SoundModeProfile,
-        TransparencyModeType,
+        ButtonCapabilities, DeviceFeatures, DeviceProfile, NoiseCancelingModeType,
+        SoundModeProfile, TransparencyModeType,
     },
     devices::standard::{
         self,
@@ -38,6 +38,7 @@ pub(crate) const A3933_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: false,
         has_auto_power_off: false,
         has_ambient_sound_mode_cycle: true,
+        button_capabilities: ButtonCapabilities::STANDARD,
     },
     compatible_models: &[DeviceModel::A3933, DeviceModel::A3939],
     implementation: || Arc::new(A3933Implementation::default()),
@@ -73,7 +74,11 @@ impl DeviceImplementation for A3933Implementation {
                 };
                 extra_bands.set_values(packet.extra_band_values);
 
-                StateUpdatePacket::from(packet).into()
+                DeviceState {
+                    is_dynamic_range_compression_enabled: state
+                        .is_dynamic_range_compression_enabled,
+                    ..StateUpdatePacket::from(packet).into()
+                }
             }),
         );
 
@@ -83,9 +88,7 @@ impl DeviceImplementation for A3933Implementation {
     fn initialize(&self, packet: &[u8]) -> crate::Result<DeviceState> {
         let packet = A3933StateUpdatePacket::take::<VerboseError<_>>(packet)
             .map(|(_, packet)| packet)
-            .map_err(|err| crate::Error::ParseError {
-                message: format!("{err:?}"),
-            })?;
+            .map_err(|err| crate::Error::from_verbose_error(packet, err))?;
         Ok(StateUpdatePacket::from(packet).into())
     }
 
@@ -3,7 +3,7 @@ use std::{collections::HashMap, sync::Arc};
 use nom::error::VerboseError;
 
 use crate::{
-    device_profile::{DeviceFeatures, DeviceProfile},
+    device_profile::{ButtonCapabilities, DeviceFeatures, DeviceProfile},
     devices::standard::{
         self,
         packets::inbound::{
@@ -34,6 +34,7 @@ pub(crate) const A3936_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: false,
         has_auto_power_off: false,
         has_ambient_sound_mode_cycle: true,
+        button_capabilities: ButtonCapabilities::STANDARD,
     },
     compatible_models: &[DeviceModel::A3936],
     implementation: || Arc::new(A3936Implementation::default()),
@@ -69,7 +70,11 @@ impl DeviceImplementation for A3936Implementation {
                 };
                 extra_bands.set_values(packet.extra_bands);
 
-                StateUpdatePacket::from(packet).into()
+                DeviceState {
+                    is_dynamic_range_compression_enabled: state
+                        .is_dynamic_range_compression_enabled,
+                    ..StateUpdatePacket::from(packet).into()
+                }
             }),
         );
         handlers.insert(
@@ -96,9 +101,7 @@ impl DeviceImplementation for A3936Implementation {
     fn initialize(&self, packet: &[u8]) -> crate::Result<DeviceState> {
         let packet = A3936StateUpdatePacket::take::<VerboseError<_>>(packet)
             .map(|(_, packet)| packet)
-            .map_err(|err| crate::Error::ParseError {
-                message: format!("{err:?}"),
-            })?;
+            .map_err(|err| crate::Error::from_verbose_error(packet, err))?;
         Ok(StateUpdatePacket::from(packet).into())
     }
 
@@ -3,7 +3,7 @@ use std::{collections::HashMap, sync::Arc};
 use nom::error::VerboseError;
 
 use crate::{
-    device_profile::{DeviceFeatures, DeviceProfile},
+    device_profile::{ButtonCapabilities, DeviceFeatures, DeviceProfile},
     devices::standard::{
         self,
         packets::inbound::state_update_packet::StateUpdatePacket,
@@ -32,6 +32,7 @@ pub(crate) const A3945_DEVICE_PROFILE: DeviceProfile = DeviceProfile {
         has_touch_tone: false,
         has_auto_power_off: false,
         has_ambient_sound_mode_cycle: false,
+        button_capabilities: ButtonCapabilities::WITHOUT_AMBIENT_SOUND_MODE,
     },
     compatible_models: &[DeviceModel::A3945],
     implementation: || Arc::new(A3945Implementation::default()),
@@ -63,7 +64,11 @@ impl DeviceImplementation for A3945Implementation {
                 };
                 extra_bands.set_values(packet.extra_band_values);
 
-                StateUpdatePacket::from(packet).into()
+                DeviceState {
+                    is_dynamic_range_compression_enabled: state
+                        .is_dynamic_range_compression_enabled,
+                    ..StateUpdatePacket::from(packet).into()
+                }
             }),
         );
 
@@ -73,9 +78,7 @@ impl DeviceImplementation for A3945Implementation {
     fn initialize(&self, packet: &[u8]) -> crate::Result<DeviceState> {
         let packet = A3945StateUpdatePacket::take::<VerboseError<_>>(packet)
             .map(|(_, packet)| packet)
-            .map_err(|err| crate::Error::ParseError {
-                message: format!("{err:?}"),
-            })?;
+            .map_err(|err| crate::Error::from_verbose_error(packet, err))?;
         Ok(StateUpdatePacket::from(packet).into())
     }
 
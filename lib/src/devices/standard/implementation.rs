@@ -38,9 +38,7 @@ impl StandardImplementation {
             initializer: Box::new(|input| {
                 T::take::<VerboseError<_>>(input)
                     .map(|(_, packet)| StateUpdatePacket::from(packet).into())
-                    .map_err(|err| crate::Error::ParseError {
-                        message: format!("{err:?}"),
-                    })
+                    .map_err(|err| crate::Error::from_verbose_error(input, err))
             }),
         })
     }
@@ -26,6 +26,11 @@ pub fn set_custom_button_model(
         });
     }
 
+    state
+        .device_features
+        .button_capabilities
+        .validate(&prev_custom_button_model, &custom_button_model)?;
+
     let packet = SetCustomButtonModelPacket::new(custom_button_model);
     Ok(CommandResponse {
         packets: vec![packet.into()],
@@ -35,3 +40,117 @@ pub fn set_custom_button_model(
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::set_custom_button_model;
+    use crate::{
+        device_profile::{ButtonCapabilities, DeviceFeatures},
+        devices::standard::{
+            state::DeviceState,
+            structures::{ButtonAction, CustomButtonModel, NoTwsButtonAction, TwsButtonAction},
+        },
+    };
+
+    fn state(button_capabilities: ButtonCapabilities) -> DeviceState {
+        DeviceState {
+            device_features: DeviceFeatures {
+                has_custom_button_model: true,
+                button_capabilities,
+                ..Default::default()
+            },
+            custom_button_model: Some(model_with_left_double_click(ButtonAction::PlayPause)),
+            ..Default::default()
+        }
+    }
+
+    fn model_with_left_double_click(action: ButtonAction) -> CustomButtonModel {
+        let disabled = TwsButtonAction {
+            tws_connected_action: ButtonAction::PlayPause,
+            tws_disconnected_action: ButtonAction::PlayPause,
+            is_enabled: false,
+        };
+        let disabled_single_click = NoTwsButtonAction {
+            action: ButtonAction::PlayPause,
+            is_enabled: false,
+        };
+        CustomButtonModel {
+            left_single_click: disabled_single_click,
+            left_double_click: TwsButtonAction {
+                tws_connected_action: action,
+                is_enabled: true,
+                ..disabled
+            },
+            left_long_press: disabled,
+            right_single_click: disabled_single_click,
+            right_double_click: disabled,
+            right_long_press: disabled,
+        }
+    }
+
+    #[test]
+    fn it_accepts_supported_combinations() {
+        let response = set_custom_button_model(
+            state(ButtonCapabilities::STANDARD),
+            model_with_left_double_click(ButtonAction::NextSong),
+        )
+        .unwrap();
+        assert_eq!(1, response.packets.len());
+    }
+
+    #[test]
+    fn it_rejects_unsupported_gestures() {
+        let capabilities = ButtonCapabilities {
+            has_double_click: false,
+            ..ButtonCapabilities::STANDARD
+        };
+        let result = set_custom_button_model(
+            state(capabilities),
+            model_with_left_double_click(ButtonAction::NextSong),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::Error::FeatureNotSupported {
+                feature_name: "left double click"
+            })
+        ));
+    }
+
+    #[test]
+    fn it_accepts_unchanged_unknown_actions() {
+        let unknown_model = model_with_left_double_click(ButtonAction::Unknown(0x7e));
+        let new_model = CustomButtonModel {
+            right_double_click: unknown_model.left_double_click,
+            ..unknown_model
+        };
+        let mut state = state(ButtonCapabilities::STANDARD);
+        state.custom_button_model = Some(unknown_model);
+        // right double click is a new gesture with an unknown action, so it is rejected
+        assert!(set_custom_button_model(state.to_owned(), new_model).is_err());
+
+        let new_model = CustomButtonModel {
+            right_double_click: TwsButtonAction {
+                tws_connected_action: ButtonAction::NextSong,
+                ..unknown_model.left_double_click
+            },
+            ..unknown_model
+        };
+        let response = set_custom_button_model(state, new_model).unwrap();
+        assert_eq!(1, response.packets.len());
+    }
+
+    #[test]
+    fn it_rejects_unsupported_actions() {
+        let result = set_custom_button_model(
+            state(ButtonCapabilities::STANDARD),
+            model_with_left_double_click(ButtonAction::Unknown(0x7e)),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidValue {
+                name: "left double click",
+                ..
+            })
+        ));
+    }
+}
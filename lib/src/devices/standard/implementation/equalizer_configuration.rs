@@ -21,7 +21,7 @@ pub fn set_equalizer_configuration(
     };
 
     let packet: Packet = if let Some(HearId::Custom(custom_hear_id)) = &state.hear_id {
-        SetEqualizerAndCustomHearIdPacket {
+        let packet = SetEqualizerAndCustomHearIdPacket {
             equalizer_configuration: &equalizer_configuration,
             age_range: state.age_range.ok_or(crate::Error::IncompleteStateError {
                 message: "age range not set",
@@ -30,9 +30,10 @@ pub fn set_equalizer_configuration(
             gender: state.gender.ok_or(crate::Error::IncompleteStateError {
                 message: "gender not set",
             })?,
-        }
-        .into()
-    } else if state.supports_dynamic_range_compression() {
+        };
+        packet.validate()?;
+        packet.into()
+    } else if state.is_dynamic_range_compression_active() {
         SetEqualizerWithDrcPacket::new(left_channel, right_channel).into()
     } else {
         SetEqualizerPacket::new(left_channel, right_channel).into()
@@ -45,3 +46,87 @@ pub fn set_equalizer_configuration(
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::set_equalizer_configuration;
+    use crate::{
+        device_profile::DeviceFeatures,
+        devices::standard::{
+            packets::outbound::{SetEqualizerPacket, SetEqualizerWithDrcPacket},
+            state::DeviceState,
+            structures::{
+                AgeRange, CustomHearId, EqualizerConfiguration, Gender, HearId, HearIdType,
+                PresetEqualizerProfile,
+            },
+        },
+        soundcore_device::device::Packet,
+    };
+
+    fn drc_state(is_dynamic_range_compression_enabled: bool) -> DeviceState {
+        DeviceState {
+            device_features: DeviceFeatures {
+                num_equalizer_channels: 1,
+                num_equalizer_bands: 8,
+                has_dynamic_range_compression: true,
+                ..Default::default()
+            },
+            is_dynamic_range_compression_enabled,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_sends_drc_packet_when_enabled() {
+        let configuration =
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster);
+        let response =
+            set_equalizer_configuration(drc_state(true), configuration.to_owned()).unwrap();
+        let expected: Packet = SetEqualizerWithDrcPacket::new(&configuration, None).into();
+        assert_eq!(vec![expected], response.packets);
+    }
+
+    #[test]
+    fn it_sends_packet_without_drc_when_disabled() {
+        let configuration =
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster);
+        let response =
+            set_equalizer_configuration(drc_state(false), configuration.to_owned()).unwrap();
+        let expected: Packet = SetEqualizerPacket::new(&configuration, None).into();
+        assert_eq!(vec![expected], response.packets);
+        assert_eq!(
+            None,
+            response
+                .new_state
+                .dynamic_range_compressed_volume_adjustments()
+        );
+    }
+
+    #[test]
+    fn it_validates_hear_id_like_set_hear_id() {
+        let state = DeviceState {
+            gender: Some(Gender::Female),
+            age_range: Some(AgeRange::Under20),
+            hear_id: Some(HearId::Custom(CustomHearId {
+                is_enabled: true,
+                volume_adjustments: Default::default(),
+                time: 0,
+                hear_id_type: HearIdType::Unknown(5),
+                hear_id_music_type: Default::default(),
+                custom_volume_adjustments: None,
+            })),
+            ..drc_state(true)
+        };
+        let result = set_equalizer_configuration(
+            state,
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidValue {
+                name: "hear id type",
+                ..
+            })
+        ));
+    }
+}
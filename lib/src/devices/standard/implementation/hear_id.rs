@@ -40,6 +40,7 @@ fn set_basic_hear_id(
         age_range,
         custom_hear_id: &custom_hear_id,
     };
+    packet.validate()?;
 
     Ok(CommandResponse {
         packets: vec![packet.into()],
@@ -63,6 +64,7 @@ fn set_custom_hear_id(state: DeviceState, hear_id: CustomHearId) -> crate::Resul
         age_range,
         custom_hear_id: &hear_id,
     };
+    packet.validate()?;
 
     Ok(CommandResponse {
         packets: vec![packet.into()],
@@ -39,5 +39,6 @@ pub fn state_update_handler(input: &[u8], state: DeviceState) -> DeviceState {
         ambient_sound_mode_cycle: packet
             .ambient_sound_mode_cycle
             .or(state.ambient_sound_mode_cycle),
+        is_dynamic_range_compression_enabled: state.is_dynamic_range_compression_enabled,
     }
 }
@@ -18,6 +18,12 @@ pub fn set_sound_modes_type_two(
     };
     // Adaptive noise canceling strength should only be modified by the device, not us. Ensure it doesn't change.
     sound_modes.adaptive_noise_canceling = prev_sound_modes.adaptive_noise_canceling;
+    if sound_modes == prev_sound_modes {
+        return Ok(CommandResponse {
+            packets: Vec::new(),
+            new_state: state,
+        });
+    }
 
     // It will bug and put us in noise canceling mode without changing the ambient sound mode id if we change the
     // noise canceling mode with the ambient sound mode being normal or transparency. To work around this, we must
@@ -75,3 +81,75 @@ pub fn set_sound_modes_type_two(
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::devices::standard::{
+        state::DeviceState,
+        structures::{
+            AdaptiveNoiseCanceling, AmbientSoundMode, NoiseCancelingAdaptiveSensitivityLevel,
+            NoiseCancelingModeTypeTwo, SoundModesTypeTwo,
+        },
+    };
+
+    use super::set_sound_modes_type_two;
+
+    fn state(sound_modes: SoundModesTypeTwo) -> DeviceState {
+        DeviceState {
+            sound_modes_type_two: Some(sound_modes),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_sends_nothing_when_only_adaptive_noise_canceling_changes() {
+        let sound_modes = SoundModesTypeTwo::default();
+        let response = set_sound_modes_type_two(
+            state(sound_modes),
+            SoundModesTypeTwo {
+                adaptive_noise_canceling: AdaptiveNoiseCanceling::HighNoise,
+                ..sound_modes
+            },
+        )
+        .unwrap();
+        assert!(response.packets.is_empty());
+    }
+
+    #[test]
+    fn it_sends_one_packet_for_sensitivity_change() {
+        let sound_modes = SoundModesTypeTwo {
+            ambient_sound_mode: AmbientSoundMode::Normal,
+            ..Default::default()
+        };
+        let new_sound_modes = SoundModesTypeTwo {
+            noise_canceling_adaptive_sensitivity_level:
+                NoiseCancelingAdaptiveSensitivityLevel::new(3).unwrap(),
+            wind_noise_suppression: true,
+            ..sound_modes
+        };
+        let response = set_sound_modes_type_two(state(sound_modes), new_sound_modes).unwrap();
+        assert_eq!(1, response.packets.len());
+        assert_eq!(
+            Some(new_sound_modes),
+            response.new_state.sound_modes_type_two
+        );
+    }
+
+    #[test]
+    fn it_works_around_noise_canceling_mode_bug() {
+        let sound_modes = SoundModesTypeTwo {
+            ambient_sound_mode: AmbientSoundMode::Normal,
+            noise_canceling_mode: NoiseCancelingModeTypeTwo::Adaptive,
+            ..Default::default()
+        };
+        let response = set_sound_modes_type_two(
+            state(sound_modes),
+            SoundModesTypeTwo {
+                noise_canceling_mode: NoiseCancelingModeTypeTwo::Manual,
+                ..sound_modes
+            },
+        )
+        .unwrap();
+        assert_eq!(3, response.packets.len());
+    }
+}
@@ -19,15 +19,27 @@ where
 }
 
 #[derive(Debug, thiserror::Error)]
-#[error("{message}")]
+#[error("failed to parse {command:?}: {source}")]
 pub struct TryIntoInboundPacketError {
-    message: String,
+    command: Command,
+    source: crate::Error,
 }
 
 impl From<TryIntoInboundPacketError> for crate::Error {
     fn from(error: TryIntoInboundPacketError) -> Self {
-        Self::Other {
-            source: Box::new(error),
+        match error.source {
+            crate::Error::ParseError {
+                message,
+                offset,
+                context,
+                command: None,
+            } => crate::Error::ParseError {
+                message,
+                offset,
+                context,
+                command: Some(error.command),
+            },
+            source => source,
         }
     }
 }
@@ -47,7 +59,8 @@ where
     fn try_into_inbound_packet(&self) -> Result<T, TryIntoInboundPacketError> {
         self.try_into_inbound_packet_raw_error::<VerboseError<_>>()
             .map_err(|err| TryIntoInboundPacketError {
-                message: format!("{err:?}"),
+                command: self.command,
+                source: crate::Error::from_verbose_error(&self.body, err),
             })
     }
 
@@ -70,10 +83,28 @@ pub(crate) fn take_inbound_packet_header<'a, E: ParseError<&'a [u8]> + ContextEr
 mod tests {
     use nom::error::VerboseError;
 
-    use crate::devices::standard::packets::inbound::take_inbound_packet_header;
+    use crate::{
+        devices::standard::packets::inbound::{
+            state_update_packet::StateUpdatePacket, take_inbound_packet_header, InboundPacket,
+            TryIntoInboundPacket,
+        },
+        soundcore_device::device::Packet,
+    };
+
     #[test]
     fn it_errors_when_nothing_matches() {
         let result = take_inbound_packet_header::<VerboseError<_>>(&[1, 2, 3]);
         assert_eq!(true, result.is_err());
     }
+
+    #[test]
+    fn it_keeps_command_when_converting_to_error() {
+        let packet = Packet {
+            command: StateUpdatePacket::command(),
+            body: vec![1, 2, 3],
+        };
+        let result: Result<StateUpdatePacket, _> = packet.try_into_inbound_packet();
+        let error: crate::Error = result.unwrap_err().into();
+        assert_eq!(Some(StateUpdatePacket::command()), error.details().command);
+    }
 }
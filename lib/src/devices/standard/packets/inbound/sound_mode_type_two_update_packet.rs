@@ -75,6 +75,11 @@ mod tests {
         );
         assert_eq!(TransparencyMode::VocalMode, sound_modes.transparency_mode);
         assert_eq!(true, sound_modes.wind_noise_suppression);
-        assert_eq!(5, sound_modes.noise_canceling_adaptive_sensitivity_level);
+        assert_eq!(
+            5,
+            sound_modes
+                .noise_canceling_adaptive_sensitivity_level
+                .value()
+        );
     }
 }
@@ -1,7 +1,7 @@
 use nom::{
     branch::alt,
     combinator::map,
-    error::{ContextError, ParseError},
+    error::{ContextError, ErrorKind, ParseError, VerboseError},
 };
 
 use crate::{
@@ -15,6 +15,7 @@ use crate::{
         a3930::packets::A3930StateUpdatePacket,
         a3931::packets::A3931StateUpdatePacket,
         a3933::packets::inbound::A3933StateUpdatePacket,
+        a3936::packets::A3936StateUpdatePacket,
         a3945::packets::A3945StateUpdatePacket,
         a3951::packets::A3951StateUpdatePacket,
         standard::{
@@ -26,6 +27,7 @@ use crate::{
             },
         },
     },
+    soundcore_device::device_model::DeviceModel,
 };
 
 use super::InboundPacket;
@@ -46,6 +48,76 @@ pub(crate) struct StateUpdatePacket {
     pub ambient_sound_mode_cycle: Option<AmbientSoundModeCycle>,
 }
 
+impl StateUpdatePacket {
+    /// Parses the longest prefix of `input` that is a valid state update packet for the model
+    /// indicated by its serial number, returning the bytes that follow it. This lets a device keep
+    /// working when a firmware update appends fields that we don't know about yet.
+    pub(crate) fn take_known_prefix(input: &[u8]) -> Option<(StateUpdatePacket, &[u8])> {
+        Self::known_prefix_lengths(input)
+            .into_iter()
+            .find_map(|length| {
+                let (_, packet) = Self::take::<VerboseError<_>>(&input[..length]).ok()?;
+                let model = DeviceModel::from_serial_number(packet.serial_number.as_ref()?)?;
+                packet
+                    .device_profile
+                    .compatible_models
+                    .contains(&model)
+                    .then_some((packet, &input[length..]))
+            })
+    }
+
+    /// Lengths, longest first, at which some model's parser stopped because there was input left
+    /// over. Only these prefixes need to be tried, rather than every possible length.
+    pub(crate) fn known_prefix_lengths(input: &[u8]) -> Vec<usize> {
+        let mut remaining_lengths = Vec::new();
+        if let Err(nom::Err::Error(err) | nom::Err::Failure(err)) =
+            Self::take::<TrailingInput>(input)
+        {
+            remaining_lengths.extend(err.remaining_lengths);
+        }
+        // Not one of the alternatives in take, but its implementation still parses it
+        if let Err(nom::Err::Error(err) | nom::Err::Failure(err)) =
+            A3936StateUpdatePacket::take::<TrailingInput>(input)
+        {
+            remaining_lengths.extend(err.remaining_lengths);
+        }
+        remaining_lengths.sort_unstable();
+        remaining_lengths.dedup();
+        remaining_lengths
+            .into_iter()
+            .map(|remaining_length| input.len() - remaining_length)
+            .collect()
+    }
+}
+
+/// Records where parsers failed due to unconsumed input, which is where the known part of a
+/// packet ends if unknown bytes were appended to it.
+#[derive(Debug, Default)]
+struct TrailingInput {
+    remaining_lengths: Vec<usize>,
+}
+
+impl ParseError<&[u8]> for TrailingInput {
+    fn from_error_kind(input: &[u8], kind: ErrorKind) -> Self {
+        let mut error = Self::default();
+        if kind == ErrorKind::Eof && !input.is_empty() {
+            error.remaining_lengths.push(input.len());
+        }
+        error
+    }
+
+    fn append(_input: &[u8], _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        self.remaining_lengths.extend(other.remaining_lengths);
+        self
+    }
+}
+
+impl ContextError<&[u8]> for TrailingInput {}
+
 impl InboundPacket for StateUpdatePacket {
     fn command() -> Command {
         Command::new([0x09, 0xff, 0x00, 0x00, 0x01, 0x01, 0x01])
@@ -12,6 +12,21 @@ pub struct SetEqualizerAndCustomHearIdPacket<'a> {
     pub custom_hear_id: &'a CustomHearId,
 }
 
+impl<'a> SetEqualizerAndCustomHearIdPacket<'a> {
+    /// Checks that the hear id type is one the device accepts. Unknown genders and age ranges
+    /// aren't rejected, since they may have been reported by the device and are sent back
+    /// unchanged. Hear id values aren't sent at all if the age range doesn't support hear id.
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.age_range.supports_hear_id() && !self.custom_hear_id.hear_id_type.is_valid() {
+            return Err(crate::Error::InvalidValue {
+                name: "hear id type",
+                value: self.custom_hear_id.hear_id_type.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
 impl<'a> OutboundPacket for SetEqualizerAndCustomHearIdPacket<'a> {
     fn command(&self) -> Command {
         if self.age_range.supports_hear_id() {
@@ -41,12 +56,12 @@ impl<'a> OutboundPacket for SetEqualizerAndCustomHearIdPacket<'a> {
         bytes.extend(eq.bytes()); // left
         bytes.extend(eq.bytes()); // right
         bytes.push(if supports_hear_id {
-            self.gender.0
+            self.gender.into()
         } else {
             u8::MAX
         });
         bytes.push(if supports_hear_id {
-            self.age_range.0
+            self.age_range.into()
         } else {
             u8::MAX
         });
@@ -62,7 +77,7 @@ impl<'a> OutboundPacket for SetEqualizerAndCustomHearIdPacket<'a> {
             [0, 0, 0, 0]
         });
         bytes.push(if supports_hear_id {
-            self.custom_hear_id.hear_id_type.0
+            self.custom_hear_id.hear_id_type.into()
         } else {
             0
         });
@@ -108,8 +123,8 @@ mod tests {
             equalizer_configuration: &EqualizerConfiguration::new_custom_profile(
                 VolumeAdjustments::new([-5.2, -6.6, -6.4, -6.7, -10.8, -2.2, -4.9, -10.1]).unwrap(),
             ),
-            gender: Gender(1),
-            age_range: AgeRange(2),
+            gender: Gender::Male,
+            age_range: AgeRange::From30To39,
             custom_hear_id: &CustomHearId {
                 is_enabled: true,
                 volume_adjustments: StereoVolumeAdjustments {
@@ -123,8 +138,8 @@ mod tests {
                     .unwrap(),
                 },
                 time: 100000,
-                hear_id_type: HearIdType(5),
-                hear_id_music_type: HearIdMusicType(0),
+                hear_id_type: HearIdType::Unknown(5),
+                hear_id_music_type: HearIdMusicType::None,
                 custom_volume_adjustments: Some(StereoVolumeAdjustments {
                     left: VolumeAdjustments::new([
                         -10.9, -5.2, -7.3, -0.2, -10.1, -11.6, -11.8, -3.9,
@@ -156,8 +171,8 @@ mod tests {
             equalizer_configuration: &EqualizerConfiguration::new_from_preset_profile(
                 PresetEqualizerProfile::SoundcoreSignature,
             ),
-            gender: Gender(1),
-            age_range: AgeRange(255),
+            gender: Gender::Male,
+            age_range: AgeRange::Unset,
             custom_hear_id: &CustomHearId {
                 is_enabled: true,
                 volume_adjustments: StereoVolumeAdjustments {
@@ -169,8 +184,8 @@ mod tests {
                     .unwrap(),
                 },
                 time: 100000,
-                hear_id_type: HearIdType(5),
-                hear_id_music_type: HearIdMusicType(0),
+                hear_id_type: HearIdType::Unknown(5),
+                hear_id_music_type: HearIdMusicType::None,
                 custom_volume_adjustments: Some(StereoVolumeAdjustments {
                     left: VolumeAdjustments::new([-9.0, -6.7, -5.3, -7.9, -1.3, -1.2, -7.3, -9.9])
                         .unwrap(),
@@ -185,4 +200,54 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    fn custom_hear_id(hear_id_type: HearIdType) -> CustomHearId {
+        CustomHearId {
+            is_enabled: true,
+            volume_adjustments: Default::default(),
+            time: 0,
+            hear_id_type,
+            hear_id_music_type: HearIdMusicType::None,
+            custom_volume_adjustments: None,
+        }
+    }
+
+    #[test]
+    fn it_rejects_unknown_hear_id_type() {
+        let packet = SetEqualizerAndCustomHearIdPacket {
+            equalizer_configuration: &Default::default(),
+            gender: Gender::Female,
+            age_range: AgeRange::Under20,
+            custom_hear_id: &custom_hear_id(HearIdType::Unknown(5)),
+        };
+        assert!(matches!(
+            packet.validate(),
+            Err(crate::Error::InvalidValue {
+                name: "hear id type",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn it_accepts_unknown_gender() {
+        let packet = SetEqualizerAndCustomHearIdPacket {
+            equalizer_configuration: &Default::default(),
+            gender: Gender::Unknown(9),
+            age_range: AgeRange::Under20,
+            custom_hear_id: &custom_hear_id(HearIdType::HearingTest),
+        };
+        packet.validate().unwrap();
+    }
+
+    #[test]
+    fn it_ignores_hear_id_values_when_age_range_is_unset() {
+        let packet = SetEqualizerAndCustomHearIdPacket {
+            equalizer_configuration: &Default::default(),
+            gender: Gender::Unknown(9),
+            age_range: AgeRange::Unset,
+            custom_hear_id: &custom_hear_id(HearIdType::Unknown(5)),
+        };
+        packet.validate().unwrap();
+    }
 }
@@ -20,7 +20,9 @@ impl OutboundPacket for SetSoundModeTypeTwoPacket {
             self.sound_modes.transparency_mode.id(),
             self.sound_modes.noise_canceling_mode.id(), // ANC automation mode?
             self.sound_modes.wind_noise_suppression.into(),
-            self.sound_modes.noise_canceling_adaptive_sensitivity_level,
+            self.sound_modes
+                .noise_canceling_adaptive_sensitivity_level
+                .value(),
         ]
     }
 }
@@ -31,7 +33,8 @@ mod tests {
         packets::outbound::{OutboundPacketBytesExt, SetSoundModeTypeTwoPacket},
         structures::{
             AdaptiveNoiseCanceling, AmbientSoundMode, ManualNoiseCanceling,
-            NoiseCancelingModeTypeTwo, SoundModesTypeTwo, TransparencyMode,
+            NoiseCancelingAdaptiveSensitivityLevel, NoiseCancelingModeTypeTwo, SoundModesTypeTwo,
+            TransparencyMode,
         },
     };
 
@@ -49,7 +52,8 @@ mod tests {
                 transparency_mode: TransparencyMode::FullyTransparent,
                 noise_canceling_mode: NoiseCancelingModeTypeTwo::Manual,
                 wind_noise_suppression: true,
-                noise_canceling_adaptive_sensitivity_level: 2,
+                noise_canceling_adaptive_sensitivity_level:
+                    NoiseCancelingAdaptiveSensitivityLevel::new(2).unwrap(),
             },
         };
         assert_eq!(EXPECTED, packet.bytes());
@@ -7,7 +7,7 @@ use crate::{
         packets::inbound::state_update_packet::StateUpdatePacket,
         structures::{
             AgeRange, Battery, CustomButtonModel, EqualizerConfiguration, FirmwareVersion, Gender,
-            HearId, SerialNumber, SoundModes,
+            HearId, SerialNumber, SoundModes, VolumeAdjustments,
         },
     },
 };
@@ -30,6 +30,11 @@ pub struct DeviceState {
     pub firmware_version: Option<FirmwareVersion>,
     pub serial_number: Option<SerialNumber>,
     pub ambient_sound_mode_cycle: Option<AmbientSoundModeCycle>,
+    /// Whether the user wants dynamic range compression applied to the equalizer. Has no effect
+    /// if the device doesn't support it. Devices don't report this, so it starts out enabled,
+    /// which is what was always sent before it could be turned off. Frontends persist the user's
+    /// choice and restore it with `Device::set_dynamic_range_compression` after connecting.
+    pub is_dynamic_range_compression_enabled: bool,
 }
 
 impl From<StateUpdatePacket> for DeviceState {
@@ -47,6 +52,8 @@ impl From<StateUpdatePacket> for DeviceState {
             firmware_version: packet.firmware_version,
             serial_number: packet.serial_number.clone(),
             ambient_sound_mode_cycle: packet.ambient_sound_mode_cycle,
+            // Not part of the packet, see the field's documentation
+            is_dynamic_range_compression_enabled: true,
         }
     }
 }
@@ -62,6 +69,20 @@ impl DeviceState {
         }
     }
 
+    pub fn is_dynamic_range_compression_active(&self) -> bool {
+        self.is_dynamic_range_compression_enabled && self.supports_dynamic_range_compression()
+    }
+
+    /// The volume adjustments that are sent to the device after dynamic range compression, or
+    /// None if it is not active.
+    pub fn dynamic_range_compressed_volume_adjustments(&self) -> Option<VolumeAdjustments> {
+        self.is_dynamic_range_compression_active().then(|| {
+            self.equalizer_configuration
+                .volume_adjustments()
+                .apply_drc()
+        })
+    }
+
     fn does_firmware_version_support_drc(&self, firmware_version: FirmwareVersion) -> bool {
         firmware_version
             >= self
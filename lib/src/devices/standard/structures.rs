@@ -23,6 +23,7 @@ mod stereo_equalizer_configuration;
 mod stereo_volume_adjustments;
 mod transparency_mode;
 mod volume_adjustments;
+mod volume_adjustments_analysis;
 
 pub use age_range::*;
 pub use ambient_sound_mode::*;
@@ -49,3 +50,4 @@ pub use stereo_equalizer_configuration::*;
 pub use stereo_volume_adjustments::*;
 pub use transparency_mode::*;
 pub use volume_adjustments::*;
+pub use volume_adjustments_analysis::*;
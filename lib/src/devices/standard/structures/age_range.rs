@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use nom::{
     combinator::map,
     error::{context, ContextError, ParseError},
@@ -5,21 +7,96 @@ use nom::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EnumIter)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct AgeRange(pub u8);
+#[cfg_attr(feature = "serde", serde(from = "u8", into = "u8"))]
+pub enum AgeRange {
+    Under20,
+    From20To29,
+    From30To39,
+    From40To49,
+    Over50,
+    /// The device does not have a hearing profile, so HearID is unavailable
+    #[strum(disabled)]
+    Unset,
+    /// A value we don't know the meaning of. It is kept as is so that it can be written back
+    /// unchanged.
+    #[strum(disabled)]
+    Unknown(u8),
+}
 
 impl AgeRange {
     pub fn supports_hear_id(&self) -> bool {
-        self.0 != u8::MAX
+        *self != Self::Unset
     }
 
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
-    ) -> ParseResult<AgeRange, E> {
-        context("age range", map(le_u8, AgeRange))(input)
+    ) -> ParseResult<'a, AgeRange, E> {
+        context("age range", map(le_u8, AgeRange::from))(input)
+    }
+}
+
+impl Display for AgeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Under20 => f.write_str("Under 20"),
+            Self::From20To29 => f.write_str("20 to 29"),
+            Self::From30To39 => f.write_str("30 to 39"),
+            Self::From40To49 => f.write_str("40 to 49"),
+            Self::Over50 => f.write_str("Over 50"),
+            Self::Unset => f.write_str("Unset"),
+            Self::Unknown(value) => write!(f, "Unknown ({value})"),
+        }
+    }
+}
+
+impl From<u8> for AgeRange {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Under20,
+            1 => Self::From20To29,
+            2 => Self::From30To39,
+            3 => Self::From40To49,
+            4 => Self::Over50,
+            u8::MAX => Self::Unset,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<AgeRange> for u8 {
+    fn from(age_range: AgeRange) -> Self {
+        match age_range {
+            AgeRange::Under20 => 0,
+            AgeRange::From20To29 => 1,
+            AgeRange::From30To39 => 2,
+            AgeRange::From40To49 => 3,
+            AgeRange::Over50 => 4,
+            AgeRange::Unset => u8::MAX,
+            AgeRange::Unknown(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AgeRange;
+
+    #[test]
+    fn it_round_trips_unknown_values() {
+        for value in 0..=u8::MAX {
+            assert_eq!(value, u8::from(AgeRange::from(value)));
+        }
+    }
+
+    #[test]
+    fn it_does_not_support_hear_id_when_unset() {
+        assert!(!AgeRange::from(u8::MAX).supports_hear_id());
+        assert!(AgeRange::Unknown(10).supports_hear_id());
     }
 }
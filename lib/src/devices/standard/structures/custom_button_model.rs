@@ -1,12 +1,14 @@
+use std::fmt::Display;
+
 use nom::{
-    combinator::{map, map_opt},
+    combinator::map,
     error::{context, ContextError, ParseError},
     number::complete::le_u8,
     sequence::{pair, tuple},
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use strum::{AsRefStr, EnumIter, FromRepr};
+use strum::EnumIter;
 
 use crate::devices::standard::packets::parsing::{take_bool, ParseResult};
 
@@ -90,12 +92,10 @@ impl TwsButtonAction {
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
     ) -> ParseResult<TwsButtonAction, E> {
-        map_opt(pair(take_bool, le_u8), |(switch, num)| {
-            Some(TwsButtonAction {
-                tws_connected_action: ButtonAction::from_repr(num & 0x0F)?,
-                tws_disconnected_action: ButtonAction::from_repr((num & 0xF0) >> 4)?,
-                is_enabled: switch,
-            })
+        map(pair(take_bool, le_u8), |(switch, num)| TwsButtonAction {
+            tws_connected_action: ButtonAction::from(num & 0x0F),
+            tws_disconnected_action: ButtonAction::from((num & 0xF0) >> 4),
+            is_enabled: switch,
         })(input)
     }
 }
@@ -110,39 +110,102 @@ pub struct NoTwsButtonAction {
 
 impl NoTwsButtonAction {
     pub fn bytes(&self) -> [u8; 2] {
-        [self.is_enabled.into(), u8::from(self.action) & 0x0f]
+        // Unlike TwsButtonAction, the whole byte is the action
+        [self.is_enabled.into(), u8::from(self.action)]
     }
 
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
     ) -> ParseResult<NoTwsButtonAction, E> {
-        map_opt(pair(take_bool, le_u8), |(switch, num)| {
-            Some(NoTwsButtonAction {
-                action: ButtonAction::from_repr(num)?,
-                is_enabled: switch,
-            })
+        map(pair(take_bool, le_u8), |(switch, num)| NoTwsButtonAction {
+            action: ButtonAction::from(num),
+            is_enabled: switch,
         })(input)
     }
 }
 
-#[derive(
-    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, FromRepr, EnumIter, AsRefStr,
-)]
-#[repr(u8)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, EnumIter)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum ButtonAction {
-    VolumeUp = 0,
-    VolumeDown = 1,
-    PreviousSong = 2,
-    NextSong = 3,
-    AmbientSoundMode = 4,
-    VoiceAssistant = 5,
-    PlayPause = 6,
+    VolumeUp,
+    VolumeDown,
+    PreviousSong,
+    NextSong,
+    AmbientSoundMode,
+    VoiceAssistant,
+    PlayPause,
+    /// An action reported by a newer device that we don't know about yet. It is kept as is so
+    /// that it can be written back unchanged.
+    #[strum(disabled)]
+    Unknown(u8),
+}
+
+impl Display for ButtonAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::VolumeUp => f.write_str("VolumeUp"),
+            Self::VolumeDown => f.write_str("VolumeDown"),
+            Self::PreviousSong => f.write_str("PreviousSong"),
+            Self::NextSong => f.write_str("NextSong"),
+            Self::AmbientSoundMode => f.write_str("AmbientSoundMode"),
+            Self::VoiceAssistant => f.write_str("VoiceAssistant"),
+            Self::PlayPause => f.write_str("PlayPause"),
+            Self::Unknown(value) => write!(f, "Unknown ({value})"),
+        }
+    }
+}
+
+impl From<u8> for ButtonAction {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::VolumeUp,
+            1 => Self::VolumeDown,
+            2 => Self::PreviousSong,
+            3 => Self::NextSong,
+            4 => Self::AmbientSoundMode,
+            5 => Self::VoiceAssistant,
+            6 => Self::PlayPause,
+            _ => Self::Unknown(value),
+        }
+    }
 }
 
 impl From<ButtonAction> for u8 {
     fn from(value: ButtonAction) -> Self {
-        value as Self
+        match value {
+            ButtonAction::VolumeUp => 0,
+            ButtonAction::VolumeDown => 1,
+            ButtonAction::PreviousSong => 2,
+            ButtonAction::NextSong => 3,
+            ButtonAction::AmbientSoundMode => 4,
+            ButtonAction::VoiceAssistant => 5,
+            ButtonAction::PlayPause => 6,
+            ButtonAction::Unknown(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::error::VerboseError;
+
+    use super::{ButtonAction, NoTwsButtonAction, TwsButtonAction};
+
+    #[test]
+    fn it_round_trips_unknown_actions() {
+        let bytes = [1, 0x7e];
+        let (_, action) = TwsButtonAction::take::<VerboseError<_>>(&bytes).unwrap();
+        assert_eq!(ButtonAction::Unknown(0xe), action.tws_connected_action);
+        assert_eq!(ButtonAction::Unknown(7), action.tws_disconnected_action);
+        assert_eq!(bytes, action.bytes());
+    }
+
+    #[test]
+    fn it_round_trips_unknown_single_click_actions() {
+        let bytes = [1, 0x20];
+        let (_, action) = NoTwsButtonAction::take::<VerboseError<_>>(&bytes).unwrap();
+        assert_eq!(ButtonAction::Unknown(0x20), action.action);
+        assert_eq!(bytes, action.bytes());
     }
 }
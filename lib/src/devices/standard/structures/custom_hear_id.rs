@@ -101,7 +101,7 @@ impl CustomHearId {
                         volume_adjustments,
                         time,
                         hear_id_type,
-                        hear_id_music_type: HearIdMusicType(0),
+                        hear_id_music_type: HearIdMusicType::None,
                         custom_volume_adjustments: Some(custom_volume_adjustments),
                     }
                 },
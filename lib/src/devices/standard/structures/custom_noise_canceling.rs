@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use nom::{
     combinator::map,
     error::{context, ContextError, ParseError},
@@ -8,6 +10,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
+/// Noise canceling strength used when the noise canceling mode is
+/// [`NoiseCancelingMode::Custom`](super::NoiseCancelingMode::Custom). Either a manual level from
+/// [`CustomNoiseCanceling::MIN_LEVEL`] to [`CustomNoiseCanceling::MAX_LEVEL`], or
+/// [`CustomNoiseCanceling::ADAPTIVE`] to let the device pick the level itself.
 #[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(transparent))]
@@ -16,22 +22,59 @@ pub struct CustomNoiseCanceling {
 }
 
 impl CustomNoiseCanceling {
+    pub const MIN_LEVEL: u8 = 0;
+    pub const MAX_LEVEL: u8 = 10;
+    /// The raw value the device uses for adaptive noise canceling.
+    pub const ADAPTIVE_VALUE: u8 = 255;
+    pub const ADAPTIVE: Self = Self {
+        value: Self::ADAPTIVE_VALUE,
+    };
+
+    /// Clamps levels above [`CustomNoiseCanceling::MAX_LEVEL`], except for
+    /// [`CustomNoiseCanceling::ADAPTIVE_VALUE`].
     pub fn new(value: u8) -> Self {
-        // Not sure what 255 means here, but it is allowed in addition to 0-10
-        let clamped_value = if value == 255 {
+        let clamped_value = if value == Self::ADAPTIVE_VALUE {
             value
         } else {
-            value.clamp(0, 10)
+            value.clamp(Self::MIN_LEVEL, Self::MAX_LEVEL)
         };
         Self {
             value: clamped_value,
         }
     }
 
+    /// Like [`CustomNoiseCanceling::new`], but rejects out of range values rather than clamping
+    /// them.
+    pub fn try_new(value: u8) -> crate::Result<Self> {
+        if value == Self::ADAPTIVE_VALUE || (Self::MIN_LEVEL..=Self::MAX_LEVEL).contains(&value) {
+            Ok(Self { value })
+        } else {
+            Err(crate::Error::InvalidValue {
+                name: "custom noise canceling",
+                value: format!(
+                    "{value} is not within {}-{} or {} (adaptive)",
+                    Self::MIN_LEVEL,
+                    Self::MAX_LEVEL,
+                    Self::ADAPTIVE_VALUE,
+                ),
+            })
+        }
+    }
+
+    /// The raw value sent to the device, including [`CustomNoiseCanceling::ADAPTIVE_VALUE`].
     pub fn value(&self) -> u8 {
         self.value
     }
 
+    pub fn is_adaptive(&self) -> bool {
+        self.value == Self::ADAPTIVE_VALUE
+    }
+
+    /// The manual level, or None if adaptive.
+    pub fn level(&self) -> Option<u8> {
+        (!self.is_adaptive()).then_some(self.value)
+    }
+
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
     ) -> ParseResult<CustomNoiseCanceling, E> {
@@ -43,3 +86,45 @@ impl CustomNoiseCanceling {
         )(input)
     }
 }
+
+impl Display for CustomNoiseCanceling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.level() {
+            Some(level) => write!(f, "{level}"),
+            None => f.write_str("adaptive"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CustomNoiseCanceling;
+
+    #[test]
+    fn it_treats_255_as_adaptive() {
+        let custom_noise_canceling = CustomNoiseCanceling::new(255);
+        assert_eq!(CustomNoiseCanceling::ADAPTIVE, custom_noise_canceling);
+        assert!(custom_noise_canceling.is_adaptive());
+        assert_eq!(None, custom_noise_canceling.level());
+        assert_eq!("adaptive", custom_noise_canceling.to_string());
+    }
+
+    #[test]
+    fn it_clamps_levels() {
+        assert_eq!(Some(10), CustomNoiseCanceling::new(11).level());
+        assert_eq!(Some(3), CustomNoiseCanceling::new(3).level());
+    }
+
+    #[test]
+    fn it_rejects_out_of_range_levels() {
+        assert!(matches!(
+            CustomNoiseCanceling::try_new(11),
+            Err(crate::Error::InvalidValue { .. })
+        ));
+        assert_eq!(
+            CustomNoiseCanceling::ADAPTIVE,
+            CustomNoiseCanceling::try_new(255).unwrap(),
+        );
+        assert_eq!(Some(10), CustomNoiseCanceling::try_new(10).unwrap().level());
+    }
+}
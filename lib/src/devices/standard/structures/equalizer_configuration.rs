@@ -9,7 +9,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
-use super::{preset_equalizer_profile::PresetEqualizerProfile, VolumeAdjustments};
+use super::{
+    preset_equalizer_profile::PresetEqualizerProfile, VolumeAdjustments, VolumeAdjustmentsError,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -75,4 +77,13 @@ impl EqualizerConfiguration {
     pub fn volume_adjustments(&self) -> &VolumeAdjustments {
         &self.volume_adjustments
     }
+
+    /// Returns a copy with the volume adjustments resampled to `num_bands` bands. The preset
+    /// profile, if any, is kept since the device identifies presets by id.
+    pub fn resample(&self, num_bands: usize) -> Result<Self, VolumeAdjustmentsError> {
+        Ok(Self {
+            preset_profile: self.preset_profile,
+            volume_adjustments: self.volume_adjustments.resample(num_bands)?,
+        })
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use nom::{
     combinator::map,
     error::{context, ContextError, ParseError},
@@ -5,17 +7,69 @@ use nom::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EnumIter)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Gender(pub u8);
+#[cfg_attr(feature = "serde", serde(from = "u8", into = "u8"))]
+pub enum Gender {
+    Female,
+    Male,
+    /// A value we don't know the meaning of. It is kept as is so that it can be written back
+    /// unchanged.
+    #[strum(disabled)]
+    Unknown(u8),
+}
 
 impl Gender {
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
-    ) -> ParseResult<Gender, E> {
-        context("gender", map(le_u8, Gender))(input)
+    ) -> ParseResult<'a, Gender, E> {
+        context("gender", map(le_u8, Gender::from))(input)
+    }
+}
+
+impl Display for Gender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Female => f.write_str("Female"),
+            Self::Male => f.write_str("Male"),
+            Self::Unknown(value) => write!(f, "Unknown ({value})"),
+        }
+    }
+}
+
+impl From<u8> for Gender {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Female,
+            1 => Self::Male,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<Gender> for u8 {
+    fn from(gender: Gender) -> Self {
+        match gender {
+            Gender::Female => 0,
+            Gender::Male => 1,
+            Gender::Unknown(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gender;
+
+    #[test]
+    fn it_round_trips_unknown_values() {
+        for value in 0..=u8::MAX {
+            assert_eq!(value, u8::from(Gender::from(value)));
+        }
+        assert_eq!(Gender::Unknown(5), Gender::from(5));
     }
 }
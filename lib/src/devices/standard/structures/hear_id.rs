@@ -1,3 +1,8 @@
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use nom::{
     combinator::map,
     error::{context, ContextError, ParseError},
@@ -5,10 +10,11 @@ use nom::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
-use super::{BasicHearId, CustomHearId};
+use super::{BasicHearId, CustomHearId, StereoVolumeAdjustments};
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -18,6 +24,37 @@ pub enum HearId {
     Custom(CustomHearId),
 }
 
+impl HearId {
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            Self::Basic(hear_id) => hear_id.is_enabled,
+            Self::Custom(hear_id) => hear_id.is_enabled,
+        }
+    }
+
+    pub fn volume_adjustments(&self) -> &StereoVolumeAdjustments {
+        match self {
+            Self::Basic(hear_id) => &hear_id.volume_adjustments,
+            Self::Custom(hear_id) => &hear_id.volume_adjustments,
+        }
+    }
+
+    pub fn time(&self) -> i32 {
+        match self {
+            Self::Basic(hear_id) => hear_id.time,
+            Self::Custom(hear_id) => hear_id.time,
+        }
+    }
+
+    /// Interprets `time` as a Unix timestamp in seconds. Returns None if it is not set.
+    pub fn tested_at(&self) -> Option<SystemTime> {
+        u64::try_from(self.time())
+            .ok()
+            .filter(|time| *time != 0)
+            .map(|time| UNIX_EPOCH + Duration::from_secs(time))
+    }
+}
+
 impl From<BasicHearId> for HearId {
     fn from(basic_hear_id: BasicHearId) -> Self {
         Self::Basic(basic_hear_id)
@@ -30,27 +67,149 @@ impl From<CustomHearId> for HearId {
     }
 }
 
-// unsure what this is. values 0 to 2 are allowed. maybe switch to an enum when the meanings are determined.
-#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+/// Only values 0 to 2 are accepted by the device.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, EnumIter)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct HearIdType(pub u8);
+#[cfg_attr(feature = "serde", serde(from = "u8", into = "u8"))]
+pub enum HearIdType {
+    /// No hearing test was taken
+    #[default]
+    Basic,
+    /// Volume adjustments were tweaked by hand after a hearing test
+    Custom,
+    /// Produced by a hearing test, such as [`crate::hear_id_test::HearIdTest`], which tests both
+    /// ears
+    HearingTest,
+    /// A value we don't know the meaning of. It is kept as is so that it can be written back
+    /// unchanged, but the device won't accept it.
+    #[strum(disabled)]
+    Unknown(u8),
+}
 
 impl HearIdType {
+    pub fn is_valid(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
-    ) -> ParseResult<HearIdType, E> {
-        context("hear id type", map(le_u8, HearIdType))(input)
+    ) -> ParseResult<'a, HearIdType, E> {
+        context("hear id type", map(le_u8, HearIdType::from))(input)
+    }
+}
+
+impl Display for HearIdType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic => f.write_str("Basic"),
+            Self::Custom => f.write_str("Custom"),
+            Self::HearingTest => f.write_str("HearingTest"),
+            Self::Unknown(value) => write!(f, "Unknown ({value})"),
+        }
+    }
+}
+
+impl From<u8> for HearIdType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Basic,
+            1 => Self::Custom,
+            2 => Self::HearingTest,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<HearIdType> for u8 {
+    fn from(hear_id_type: HearIdType) -> Self {
+        match hear_id_type {
+            HearIdType::Basic => 0,
+            HearIdType::Custom => 1,
+            HearIdType::HearingTest => 2,
+            HearIdType::Unknown(value) => value,
+        }
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, EnumIter)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct HearIdMusicType(pub u8);
+#[cfg_attr(feature = "serde", serde(from = "u8", into = "u8"))]
+pub enum HearIdMusicType {
+    #[default]
+    None,
+    /// A value we don't know the meaning of. It is kept as is so that it can be written back
+    /// unchanged.
+    #[strum(disabled)]
+    Unknown(u8),
+}
 
 impl HearIdMusicType {
     pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         input: &'a [u8],
-    ) -> ParseResult<HearIdMusicType, E> {
-        context("hear id music type", map(le_u8, HearIdMusicType))(input)
+    ) -> ParseResult<'a, HearIdMusicType, E> {
+        context("hear id music type", map(le_u8, HearIdMusicType::from))(input)
+    }
+}
+
+impl Display for HearIdMusicType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Unknown(value) => write!(f, "Unknown ({value})"),
+        }
+    }
+}
+
+impl From<u8> for HearIdMusicType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::None,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<HearIdMusicType> for u8 {
+    fn from(music_type: HearIdMusicType) -> Self {
+        match music_type {
+            HearIdMusicType::None => 0,
+            HearIdMusicType::Unknown(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{HearId, HearIdMusicType, HearIdType};
+    use crate::devices::standard::structures::BasicHearId;
+
+    #[test]
+    fn it_only_accepts_hear_id_types_up_to_two() {
+        assert!(HearIdType::from(2).is_valid());
+        assert!(!HearIdType::from(3).is_valid());
+    }
+
+    #[test]
+    fn it_round_trips_unknown_values() {
+        for value in 0..=u8::MAX {
+            assert_eq!(value, u8::from(HearIdType::from(value)));
+            assert_eq!(value, u8::from(HearIdMusicType::from(value)));
+        }
+        assert_eq!(HearIdType::Unknown(5), HearIdType::from(5));
+    }
+
+    #[test]
+    fn it_interprets_time_as_unix_timestamp() {
+        let hear_id = HearId::Basic(BasicHearId {
+            is_enabled: true,
+            volume_adjustments: Default::default(),
+            time: 1_700_000_000,
+        });
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            hear_id.tested_at(),
+        );
     }
 }
@@ -22,7 +22,7 @@ pub struct SoundModesTypeTwo {
     pub manual_noise_canceling: ManualNoiseCanceling,
     pub noise_canceling_mode: NoiseCancelingModeTypeTwo,
     pub wind_noise_suppression: bool,
-    pub noise_canceling_adaptive_sensitivity_level: u8,
+    pub noise_canceling_adaptive_sensitivity_level: NoiseCancelingAdaptiveSensitivityLevel,
 }
 
 impl SoundModesTypeTwo {
@@ -38,7 +38,7 @@ impl SoundModesTypeTwo {
                     TransparencyMode::take,
                     NoiseCancelingModeTypeTwo::take,
                     WindNoise::take,
-                    le_u8,
+                    NoiseCancelingAdaptiveSensitivityLevel::take,
                 )),
                 |(
                     ambient_sound_mode,
@@ -63,6 +63,64 @@ impl SoundModesTypeTwo {
     }
 }
 
+/// How strongly adaptive noise canceling reacts to changes in ambient noise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+// Out of range levels are kept as is, like when parsing, so that they round trip. Loading settings
+// clamps them where needed.
+#[cfg_attr(feature = "serde", serde(from = "u8", into = "u8"))]
+pub struct NoiseCancelingAdaptiveSensitivityLevel(u8);
+
+impl NoiseCancelingAdaptiveSensitivityLevel {
+    pub const MIN: u8 = 0;
+    pub const MAX: u8 = 5;
+
+    pub fn new(level: u8) -> crate::Result<Self> {
+        if (Self::MIN..=Self::MAX).contains(&level) {
+            Ok(Self(level))
+        } else {
+            Err(crate::Error::InvalidValue {
+                name: "noise canceling adaptive sensitivity level",
+                value: format!("{level} is not within {}-{}", Self::MIN, Self::MAX),
+            })
+        }
+    }
+
+    /// For values from sources that can't be rejected, such as saved settings.
+    pub fn clamped(level: u8) -> Self {
+        Self(level.clamp(Self::MIN, Self::MAX))
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    pub(crate) fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> ParseResult<'a, NoiseCancelingAdaptiveSensitivityLevel, E> {
+        // Whatever the device reports is kept as is, even if it is out of range, so that resending
+        // the rest of the sound modes doesn't change it.
+        context(
+            "noise canceling adaptive sensitivity level",
+            map(le_u8, NoiseCancelingAdaptiveSensitivityLevel),
+        )(input)
+    }
+}
+
+impl From<u8> for NoiseCancelingAdaptiveSensitivityLevel {
+    /// Keeps out of range levels. Use [`Self::new`] to reject them or [`Self::clamped`] to clamp
+    /// them.
+    fn from(level: u8) -> Self {
+        Self(level)
+    }
+}
+
+impl From<NoiseCancelingAdaptiveSensitivityLevel> for u8 {
+    fn from(level: NoiseCancelingAdaptiveSensitivityLevel) -> Self {
+        level.0
+    }
+}
+
 #[repr(u8)]
 #[derive(FromRepr, Clone, Copy, Debug, PartialEq, Eq, Hash, Display, Default, AsRefStr)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -160,3 +218,42 @@ impl WindNoise {
         )(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::NoiseCancelingAdaptiveSensitivityLevel;
+
+    #[test]
+    fn it_rejects_out_of_range_sensitivity_level() {
+        assert!(NoiseCancelingAdaptiveSensitivityLevel::new(
+            NoiseCancelingAdaptiveSensitivityLevel::MAX
+        )
+        .is_ok());
+        assert!(NoiseCancelingAdaptiveSensitivityLevel::new(
+            NoiseCancelingAdaptiveSensitivityLevel::MAX + 1
+        )
+        .is_err());
+        assert_eq!(
+            NoiseCancelingAdaptiveSensitivityLevel::MAX,
+            NoiseCancelingAdaptiveSensitivityLevel::clamped(u8::MAX).value()
+        );
+    }
+
+    #[test]
+    fn it_keeps_out_of_range_sensitivity_level_converted_from_u8() {
+        // used when deserializing
+        assert_eq!(10, NoiseCancelingAdaptiveSensitivityLevel::from(10).value());
+        assert_eq!(
+            10u8,
+            NoiseCancelingAdaptiveSensitivityLevel::from(10).into()
+        );
+    }
+
+    #[test]
+    fn it_keeps_out_of_range_sensitivity_level_from_device() {
+        let (_, level) =
+            NoiseCancelingAdaptiveSensitivityLevel::take::<nom::error::VerboseError<_>>(&[10])
+                .unwrap();
+        assert_eq!(10, level.value());
+    }
+}
@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::devices::standard::packets::parsing::ParseResult;
 
+use super::VolumeAdjustmentsAnalysis;
+
 #[derive(Clone, Debug, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(transparent))]
@@ -51,6 +53,10 @@ impl VolumeAdjustments {
         ulps: 4,
     };
     pub const VALID_NUMBER_OF_BANDS: Range<usize> = 8..11;
+    /// Center frequency of the first band in Hz
+    pub const FIRST_BAND_FREQUENCY: f64 = 100.0;
+    /// Center frequency of the last band in Hz
+    pub const LAST_BAND_FREQUENCY: f64 = 12800.0;
 
     pub fn new(
         volume_adjustments: impl IntoIterator<Item = f64>,
@@ -110,6 +116,78 @@ impl VolumeAdjustments {
             .collect::<Arc<[f64]>>()
     }
 
+    /// Center frequencies in Hz of each band for a curve with `num_bands` bands. The official app
+    /// uses octaves from 100 Hz to 12.8 kHz for 8 bands. The centers used by devices with 9 or 10
+    /// bands are not known, so they are assumed to cover the same range, evenly spaced on a log
+    /// scale.
+    pub fn band_frequencies(num_bands: usize) -> impl Iterator<Item = f64> {
+        let octaves_per_band = Self::octaves_covered() / (num_bands.max(2) - 1) as f64;
+        (0..num_bands)
+            .map(move |band| Self::FIRST_BAND_FREQUENCY * 2f64.powf(band as f64 * octaves_per_band))
+    }
+
+    fn octaves_covered() -> f64 {
+        (Self::LAST_BAND_FREQUENCY / Self::FIRST_BAND_FREQUENCY).log2()
+    }
+
+    /// Estimates the adjustment at an arbitrary frequency by interpolating linearly between the
+    /// two closest bands on a logarithmic frequency scale. Frequencies outside of the range
+    /// covered by the bands take the value of the nearest band.
+    pub fn adjustment_at_frequency(&self, frequency: f64) -> f64 {
+        let adjustments = &self.volume_adjustments;
+        let last_index = (adjustments.len() - 1) as f64;
+        let position = ((frequency / Self::FIRST_BAND_FREQUENCY).log2() / Self::octaves_covered()
+            * last_index)
+            .clamp(0.0, last_index);
+        let lower_index = position.floor() as usize;
+        let upper_index = position.ceil() as usize;
+        let lower = adjustments[lower_index].into_inner();
+        let upper = adjustments[upper_index].into_inner();
+        lower + (upper - lower) * (position - lower_index as f64)
+    }
+
+    /// Converts the curve to a different number of bands by sampling it at the new band
+    /// frequencies, so that a profile made for one device can be applied to another.
+    pub fn resample(&self, num_bands: usize) -> Result<Self, VolumeAdjustmentsError> {
+        Self::new(
+            Self::band_frequencies(num_bands)
+                .map(|frequency| self.adjustment_at_frequency(frequency)),
+        )
+    }
+
+    pub fn analyze(&self) -> VolumeAdjustmentsAnalysis {
+        VolumeAdjustmentsAnalysis::new(self)
+    }
+
+    /// Lowers the whole curve so that no band is boosted, which avoids clipping at the cost of
+    /// overall volume. If shifting would push a band below [`Self::MIN_VOLUME`], the curve is
+    /// scaled down instead so that its shape is preserved. The result is rounded to [`Self::STEP`],
+    /// since that is the precision the device uses.
+    pub fn normalize(&self) -> VolumeAdjustments {
+        let adjustments = self.adjustments();
+        let peak_gain = self.analyze().peak_gain;
+        if peak_gain <= 0.0 {
+            return self.to_owned();
+        }
+        let lowest = adjustments.iter().cloned().fold(Self::MAX_VOLUME, f64::min);
+        let shifted_lowest = lowest - peak_gain;
+        let scale = if shifted_lowest < Self::MIN_VOLUME {
+            Self::MIN_VOLUME / shifted_lowest
+        } else {
+            1.0
+        };
+        Self::new(
+            adjustments
+                .iter()
+                .map(|adjustment| Self::round_to_step((adjustment - peak_gain) * scale)),
+        )
+        .expect("number of bands is unchanged")
+    }
+
+    fn round_to_step(adjustment: f64) -> f64 {
+        (adjustment / Self::STEP).round() * Self::STEP
+    }
+
     fn signed_adjustment_to_packet_byte(adjustment: f64) -> u8 {
         let clamped = adjustment.clamp(Self::MIN_VOLUME, Self::MAX_VOLUME);
         let shifted = (clamped - Self::MIN_VOLUME) * 10.0;
@@ -317,6 +395,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_interpolates_when_resampling_to_more_bands() {
+        let volume_adjustments = VolumeAdjustments::new(TEST_ADJUSTMENTS).unwrap();
+        let resampled = volume_adjustments.resample(10).unwrap();
+        let expected = [
+            -12.0, -5.778, -2.889, -1.333, 0.222, 1.778, 3.333, 4.889, 7.333, 12.0,
+        ];
+        assert_eq!(10, resampled.adjustments().len());
+        for (expected, actual) in expected.iter().zip(resampled.adjustments().iter()) {
+            assert!((expected - actual).abs() < 0.001, "{expected} != {actual}");
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_end_bands_when_resampling_to_fewer_bands() {
+        let volume_adjustments =
+            VolumeAdjustments::new([-12.0, -4.0, -2.0, 0.0, 2.0, 4.0, 6.0, 12.0, 1.0, 2.0])
+                .unwrap();
+        let resampled = volume_adjustments.resample(8).unwrap();
+        let adjustments = resampled.adjustments();
+        assert_eq!(8, adjustments.len());
+        assert_eq!(-12.0, adjustments[0]);
+        assert_eq!(2.0, adjustments[7]);
+    }
+
+    #[test]
+    fn it_uses_octaves_for_eight_bands() {
+        assert_eq!(
+            vec![100.0, 200.0, 400.0, 800.0, 1600.0, 3200.0, 6400.0, 12800.0],
+            VolumeAdjustments::band_frequencies(8).collect::<Vec<_>>(),
+        );
+        let ten_bands = VolumeAdjustments::band_frequencies(10).collect::<Vec<_>>();
+        assert_eq!(100.0, ten_bands[0]);
+        assert!((ten_bands[9] - 12800.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn it_fails_to_resample_to_invalid_number_of_bands() {
+        let volume_adjustments = VolumeAdjustments::new(TEST_ADJUSTMENTS).unwrap();
+        assert!(volume_adjustments.resample(7).is_err());
+        assert!(volume_adjustments.resample(11).is_err());
+    }
+
+    #[test]
+    fn it_interpolates_between_bands_on_a_log_scale() {
+        let volume_adjustments = VolumeAdjustments::new(TEST_ADJUSTMENTS).unwrap();
+        // halfway between 100hz and 200hz on a log scale
+        let frequency = 100.0 * 2f64.sqrt();
+        assert!((volume_adjustments.adjustment_at_frequency(frequency) - -8.0).abs() < 0.0001);
+        assert_eq!(-12.0, volume_adjustments.adjustment_at_frequency(20.0));
+        assert_eq!(12.0, volume_adjustments.adjustment_at_frequency(20000.0));
+    }
+
+    #[test]
+    fn it_does_not_normalize_curve_without_boost() {
+        let volume_adjustments =
+            VolumeAdjustments::new([-1.0, -2.0, 0.0, 0.0, -3.0, 0.0, -4.0, 0.0]).unwrap();
+        assert_eq!(volume_adjustments, volume_adjustments.normalize());
+    }
+
+    #[test]
+    fn it_normalizes_by_shifting_curve_down() {
+        let volume_adjustments =
+            VolumeAdjustments::new([0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0]).unwrap();
+        assert_eq!(
+            VolumeAdjustments::new([-6.0, -5.0, -4.0, -3.0, -2.0, -1.0, 0.0, -6.0]).unwrap(),
+            volume_adjustments.normalize(),
+        );
+    }
+
+    #[test]
+    fn it_normalizes_by_scaling_when_shifting_would_go_out_of_range() {
+        let volume_adjustments = VolumeAdjustments::new(TEST_ADJUSTMENTS).unwrap();
+        let normalized = volume_adjustments.normalize();
+        assert_eq!(
+            VolumeAdjustments::new([-12.0, -8.0, -7.0, -6.0, -5.0, -4.0, -3.0, 0.0]).unwrap(),
+            normalized,
+        );
+    }
+
+    #[test]
+    fn it_rounds_normalized_curve_to_step() {
+        let volume_adjustments =
+            VolumeAdjustments::new([-12.0, -11.9, -5.5, 0.0, 0.3, 0.0, 0.0, 1.0]).unwrap();
+        for adjustment in volume_adjustments.normalize().adjustments().iter() {
+            let steps = adjustment / VolumeAdjustments::STEP;
+            assert!(
+                (steps - steps.round()).abs() < 0.000001,
+                "{adjustment} is not a multiple of the step"
+            );
+        }
+    }
+
     #[test]
     fn it_matches_expected_drc_values() {
         let examples = [
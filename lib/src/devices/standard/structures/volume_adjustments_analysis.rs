@@ -0,0 +1,70 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::VolumeAdjustments;
+
+/// Rough estimate of how an equalizer curve changes the output level. All values are in dB.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct VolumeAdjustmentsAnalysis {
+    /// Largest boost applied to any band
+    pub peak_gain: f64,
+    /// Change in overall loudness, assuming content with equal energy in every band
+    pub loudness_change: f64,
+}
+
+impl VolumeAdjustmentsAnalysis {
+    /// Boosts above this are likely to clip with loud, full scale content.
+    pub const CLIPPING_THRESHOLD: f64 = 6.0;
+
+    pub fn new(volume_adjustments: &VolumeAdjustments) -> Self {
+        let adjustments = volume_adjustments.adjustments();
+        let peak_gain = adjustments
+            .iter()
+            .cloned()
+            .fold(VolumeAdjustments::MIN_VOLUME, f64::max);
+        let mean_power = adjustments
+            .iter()
+            .map(|adjustment| 10f64.powf(adjustment / 10.0))
+            .sum::<f64>()
+            / adjustments.len() as f64;
+        Self {
+            peak_gain,
+            loudness_change: 10.0 * mean_power.log10(),
+        }
+    }
+
+    pub fn is_clipping_likely(&self) -> bool {
+        self.peak_gain > Self::CLIPPING_THRESHOLD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VolumeAdjustmentsAnalysis;
+    use crate::devices::standard::structures::VolumeAdjustments;
+
+    #[test]
+    fn it_has_no_change_for_flat_curve() {
+        let analysis = VolumeAdjustments::default().analyze();
+        assert_eq!(0.0, analysis.peak_gain);
+        assert_eq!(0.0, analysis.loudness_change);
+        assert!(!analysis.is_clipping_likely());
+    }
+
+    #[test]
+    fn it_finds_peak_gain() {
+        let analysis = VolumeAdjustments::new([-12.0, -4.0, -2.0, 0.0, 2.0, 4.0, 6.0, 9.0])
+            .unwrap()
+            .analyze();
+        assert_eq!(9.0, analysis.peak_gain);
+        assert!(analysis.is_clipping_likely());
+    }
+
+    #[test]
+    fn it_estimates_loudness_change_of_uniform_boost() {
+        let analysis = VolumeAdjustmentsAnalysis::new(&VolumeAdjustments::new([3.0; 8]).unwrap());
+        assert!((analysis.loudness_change - 3.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+    rc::Rc,
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+use crate::{
+    api::device::Device, devices::standard::structures::EqualizerConfiguration, futures::Futures,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ComparisonSide {
+    A,
+    B,
+}
+
+impl ComparisonSide {
+    pub fn other(&self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+
+    fn random() -> Self {
+        // RandomState is seeded randomly, which is plenty for picking a side
+        if RandomState::new().build_hasher().finish() & 1 == 0 {
+            Self::A
+        } else {
+            Self::B
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ComparisonPreference {
+    pub preferred: ComparisonSide,
+    /// Whether the preference was recorded without knowing which side was playing
+    pub is_blind: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ComparisonTally {
+    pub a: usize,
+    pub b: usize,
+}
+
+/// Switches a device between two equalizer configurations for A/B listening tests.
+///
+/// Switching updates the active side immediately, but the write to the device is debounced so
+/// that rapidly flipping back and forth only sends the configuration that was settled on.
+pub struct EqualizerComparison<DeviceType, FuturesType>
+where
+    DeviceType: Device,
+    FuturesType: Futures,
+{
+    device: Rc<DeviceType>,
+    a: EqualizerConfiguration,
+    b: EqualizerConfiguration,
+    active_side: Cell<ComparisonSide>,
+    is_blind: Cell<bool>,
+    write_generation: Cell<u64>,
+    preferences: RefCell<Vec<ComparisonPreference>>,
+    futures: PhantomData<FuturesType>,
+}
+
+impl<DeviceType, FuturesType> EqualizerComparison<DeviceType, FuturesType>
+where
+    DeviceType: Device,
+    FuturesType: Futures,
+{
+    pub const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
+
+    pub fn new(
+        device: Rc<DeviceType>,
+        a: EqualizerConfiguration,
+        b: EqualizerConfiguration,
+    ) -> Self {
+        Self {
+            device,
+            a,
+            b,
+            active_side: Cell::new(ComparisonSide::A),
+            is_blind: Cell::new(false),
+            write_generation: Cell::new(0),
+            preferences: Default::default(),
+            futures: PhantomData,
+        }
+    }
+
+    pub fn configuration(&self, side: ComparisonSide) -> &EqualizerConfiguration {
+        match side {
+            ComparisonSide::A => &self.a,
+            ComparisonSide::B => &self.b,
+        }
+    }
+
+    /// The side that is currently applied, or None in blind mode so that it can't be peeked at.
+    pub fn active_side(&self) -> Option<ComparisonSide> {
+        if self.is_blind.get() {
+            None
+        } else {
+            Some(self.active_side.get())
+        }
+    }
+
+    pub fn is_blind(&self) -> bool {
+        self.is_blind.get()
+    }
+
+    pub fn set_blind(&self, is_blind: bool) {
+        self.is_blind.set(is_blind);
+    }
+
+    /// Applies the configuration for `side`. If another switch happens before the debounce
+    /// duration elapses, this returns without writing anything.
+    pub async fn select(&self, side: ComparisonSide) -> crate::Result<()> {
+        self.active_side.set(side);
+        let generation = self.write_generation.get().wrapping_add(1);
+        self.write_generation.set(generation);
+
+        FuturesType::sleep(Self::DEBOUNCE_DURATION).await;
+        if self.write_generation.get() != generation {
+            return Ok(());
+        }
+        self.device
+            .set_equalizer_configuration(self.configuration(side).to_owned())
+            .await
+    }
+
+    pub async fn toggle(&self) -> crate::Result<()> {
+        self.select(self.active_side.get().other()).await
+    }
+
+    /// Switches to a randomly chosen side. Intended for starting a new trial in blind mode.
+    pub async fn shuffle(&self) -> crate::Result<()> {
+        self.select(ComparisonSide::random()).await
+    }
+
+    /// Records that the side currently being listened to is preferred.
+    pub fn prefer_active(&self) -> ComparisonPreference {
+        let preference = ComparisonPreference {
+            preferred: self.active_side.get(),
+            is_blind: self.is_blind.get(),
+        };
+        self.preferences.borrow_mut().push(preference);
+        preference
+    }
+
+    pub fn preferences(&self) -> Vec<ComparisonPreference> {
+        self.preferences.borrow().to_owned()
+    }
+
+    pub fn tally(&self) -> ComparisonTally {
+        self.preferences.borrow().iter().fold(
+            ComparisonTally::default(),
+            |mut tally, preference| {
+                match preference.preferred {
+                    ComparisonSide::A => tally.a += 1,
+                    ComparisonSide::B => tally.b += 1,
+                }
+                tally
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use macaddr::MacAddr6;
+
+    use super::{ComparisonSide, EqualizerComparison};
+    use crate::{
+        api::device::Device,
+        demo::device::DemoDevice,
+        devices::standard::structures::{EqualizerConfiguration, PresetEqualizerProfile},
+        futures::TokioFutures,
+    };
+
+    async fn create_comparison() -> EqualizerComparison<DemoDevice<TokioFutures>, TokioFutures> {
+        let device = Rc::new(DemoDevice::<TokioFutures>::new("Demo", MacAddr6::nil()).await);
+        EqualizerComparison::new(
+            device,
+            EqualizerConfiguration::new_from_preset_profile(
+                PresetEqualizerProfile::SoundcoreSignature,
+            ),
+            EqualizerConfiguration::new_from_preset_profile(PresetEqualizerProfile::BassBooster),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_toggle_writes_other_configuration() {
+        let comparison = create_comparison().await;
+        comparison.toggle().await.unwrap();
+        assert_eq!(Some(ComparisonSide::B), comparison.active_side());
+        assert_eq!(
+            Some(PresetEqualizerProfile::BassBooster),
+            comparison
+                .device
+                .state()
+                .await
+                .equalizer_configuration
+                .preset_profile(),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_only_last_of_rapid_switches_is_written() {
+        let comparison = create_comparison().await;
+        let receiver = comparison.device.subscribe_to_state_updates().await;
+        // A matches the device's initial state, so B must never be written for the state to
+        // remain unchanged
+        let (first, second) = tokio::join!(
+            comparison.select(ComparisonSide::B),
+            comparison.select(ComparisonSide::A),
+        );
+        first.unwrap();
+        second.unwrap();
+        assert!(!receiver.has_changed().unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_blind_mode_hides_active_side() {
+        let comparison = create_comparison().await;
+        comparison.set_blind(true);
+        comparison.shuffle().await.unwrap();
+        assert_eq!(None, comparison.active_side());
+        let preference = comparison.prefer_active();
+        assert!(preference.is_blind);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tally() {
+        let comparison = create_comparison().await;
+        comparison.prefer_active();
+        comparison.toggle().await.unwrap();
+        comparison.prefer_active();
+        comparison.prefer_active();
+        let tally = comparison.tally();
+        assert_eq!(1, tally.a);
+        assert_eq!(2, tally.b);
+    }
+}
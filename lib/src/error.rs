@@ -1,6 +1,10 @@
+use nom::error::{VerboseError, VerboseErrorKind};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter};
 use uuid::Uuid;
 
-use crate::devices::standard::structures::SerialNumber;
+use crate::devices::standard::structures::{Command, SerialNumber};
 
 type InnerError = Box<dyn std::error::Error + Send + Sync>;
 
@@ -48,11 +52,269 @@ pub enum Error {
 
     #[error("incomplete state: {message:?}")]
     IncompleteStateError { message: &'static str },
+
     #[error("timed out: {action}")]
-    TimedOut { action: &'static str },
+    TimedOut {
+        action: &'static str,
+        /// The command that was sent, if the timeout was waiting for a response to a packet
+        command: Option<Command>,
+    },
+
+    #[error("invalid {name}: {value}")]
+    InvalidValue { name: &'static str, value: String },
 
     #[error("parse error: {message}")]
-    ParseError { message: String },
+    ParseError {
+        message: String,
+        /// Number of bytes from the start of the input to where parsing failed
+        offset: Option<usize>,
+        /// Parser contexts that the failure occurred in, from innermost to outermost
+        context: Vec<&'static str>,
+        /// The command of the packet that failed to parse, if the input was a packet body
+        command: Option<Command>,
+    },
+
+    /// An error that was passed along as [`ErrorDetails`], such as one that happened in another
+    /// process like the daemon, or one shared by several callers of a coalesced write. Its code and
+    /// details are those of the original error.
+    #[error("{}", details.message)]
+    Remote { details: Box<ErrorDetails> },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Identifies the kind of error independently of its message, so that frontends can localize
+/// errors and react to specific ones. The string representations are stable and must not be
+/// changed once added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, Display, EnumIter)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[strum(serialize_all = "camelCase")]
+pub enum ErrorCode {
+    DeviceNotFound,
+    DeviceNotSupported,
+    NotConnected,
+    NameNotFound,
+    CharacteristicNotFound,
+    ServiceNotFound,
+    Other,
+    NoResponse,
+    FeatureNotSupported,
+    MissingData,
+    WriteFailed,
+    IncompleteState,
+    TimedOut,
+    InvalidValue,
+    ParseError,
+}
+
+/// Owned, serializable form of [`Error`] for passing across language boundaries. Only the fields
+/// relevant to `code` are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ErrorDetails {
+    pub code: ErrorCode,
+    pub message: String,
+    pub serial_number: Option<SerialNumber>,
+    pub mac_address: Option<String>,
+    pub uuid: Option<String>,
+    /// Name of the unsupported feature, missing data, or invalid value
+    pub name: Option<String>,
+    pub value: Option<String>,
+    /// What was being attempted when a timeout or lack of response occurred
+    pub action: Option<String>,
+    pub command: Option<Command>,
+    pub parse_offset: Option<usize>,
+    pub parse_context: Vec<String>,
+}
+
+impl Error {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::DeviceNotFound { .. } => ErrorCode::DeviceNotFound,
+            Error::DeviceNotSupported { .. } => ErrorCode::DeviceNotSupported,
+            Error::NotConnected { .. } => ErrorCode::NotConnected,
+            Error::NameNotFound { .. } => ErrorCode::NameNotFound,
+            Error::CharacteristicNotFound { .. } => ErrorCode::CharacteristicNotFound,
+            Error::ServiceNotFound { .. } => ErrorCode::ServiceNotFound,
+            Error::Other { .. } => ErrorCode::Other,
+            Error::NoResponse { .. } => ErrorCode::NoResponse,
+            Error::FeatureNotSupported { .. } => ErrorCode::FeatureNotSupported,
+            Error::MissingData { .. } => ErrorCode::MissingData,
+            Error::WriteFailed { .. } => ErrorCode::WriteFailed,
+            Error::IncompleteStateError { .. } => ErrorCode::IncompleteState,
+            Error::TimedOut { .. } => ErrorCode::TimedOut,
+            Error::InvalidValue { .. } => ErrorCode::InvalidValue,
+            Error::ParseError { .. } => ErrorCode::ParseError,
+            Error::Remote { details } => details.code,
+        }
+    }
+
+    pub fn details(&self) -> ErrorDetails {
+        if let Error::Remote { details } = self {
+            return details.as_ref().to_owned();
+        }
+        let mut details = ErrorDetails {
+            code: self.code(),
+            message: self.to_string(),
+            serial_number: None,
+            mac_address: None,
+            uuid: None,
+            name: None,
+            value: None,
+            action: None,
+            command: None,
+            parse_offset: None,
+            parse_context: Vec::new(),
+        };
+        match self {
+            Error::DeviceNotSupported { serial_number } => {
+                details.serial_number = Some(serial_number.to_owned())
+            }
+            Error::NameNotFound { mac_address } => {
+                details.mac_address = Some(mac_address.to_owned())
+            }
+            Error::CharacteristicNotFound { uuid, .. } | Error::ServiceNotFound { uuid, .. } => {
+                details.uuid = Some(uuid.to_string())
+            }
+            Error::NoResponse { request } => details.action = Some(request.to_string()),
+            Error::FeatureNotSupported { feature_name } => {
+                details.name = Some(feature_name.to_string())
+            }
+            Error::MissingData { name } => details.name = Some(name.to_string()),
+            Error::IncompleteStateError { message } => details.name = Some(message.to_string()),
+            Error::TimedOut { action, command } => {
+                details.action = Some(action.to_string());
+                details.command = *command;
+            }
+            Error::InvalidValue { name, value } => {
+                details.name = Some(name.to_string());
+                details.value = Some(value.to_owned());
+            }
+            Error::ParseError {
+                offset,
+                context,
+                command,
+                ..
+            } => {
+                details.command = *command;
+                details.parse_offset = *offset;
+                details.parse_context = context.iter().map(ToString::to_string).collect();
+            }
+            Error::DeviceNotFound { .. }
+            | Error::NotConnected { .. }
+            | Error::Other { .. }
+            | Error::WriteFailed { .. }
+            | Error::Remote { .. } => (),
+        }
+        details
+    }
+
+    /// Keeps the position and context of a nom error rather than only its debug representation.
+    /// `input` must be the complete input that was passed to the parser.
+    pub(crate) fn from_verbose_error(input: &[u8], err: nom::Err<VerboseError<&[u8]>>) -> Self {
+        match err {
+            nom::Err::Incomplete(needed) => Error::ParseError {
+                message: format!("incomplete input: {needed:?}"),
+                offset: Some(input.len()),
+                context: Vec::new(),
+                command: None,
+            },
+            nom::Err::Error(err) | nom::Err::Failure(err) => {
+                let offset = err
+                    .errors
+                    .first()
+                    .map(|(remaining, _)| input.len() - remaining.len());
+                let context = err
+                    .errors
+                    .iter()
+                    .filter_map(|(_, kind)| match kind {
+                        VerboseErrorKind::Context(context) => Some(*context),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let message = match err.errors.first() {
+                    Some((_, VerboseErrorKind::Char(c))) => format!("expected '{c}'"),
+                    Some((_, VerboseErrorKind::Nom(kind))) => kind.description().to_owned(),
+                    Some((_, VerboseErrorKind::Context(context))) => context.to_string(),
+                    None => "unknown".to_owned(),
+                };
+                Error::ParseError {
+                    message: match offset {
+                        Some(offset) => format!("{message} at byte {offset}"),
+                        None => message,
+                    },
+                    offset,
+                    context,
+                    command: None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use nom::{
+        bytes::complete::tag,
+        error::{context, VerboseError},
+        sequence::preceded,
+    };
+    use strum::IntoEnumIterator;
+
+    use super::{Error, ErrorCode};
+    use crate::devices::standard::structures::Command;
+
+    #[test]
+    fn error_codes_are_unique() {
+        let codes = ErrorCode::iter()
+            .map(|code| code.to_string())
+            .collect::<HashSet<_>>();
+        assert_eq!(ErrorCode::iter().count(), codes.len());
+        assert_eq!("timedOut", ErrorCode::TimedOut.as_ref());
+    }
+
+    #[test]
+    fn it_keeps_parse_error_position_and_context() {
+        let input: &[u8] = &[1, 2, 3];
+        let err = context("outer", preceded(tag([1u8]), context("inner", tag([3u8]))))(input)
+            .map(|(_, output): (_, &[u8])| output)
+            .unwrap_err();
+        let err: nom::Err<VerboseError<&[u8]>> = err;
+
+        let details = Error::from_verbose_error(input, err).details();
+        assert_eq!(ErrorCode::ParseError, details.code);
+        assert_eq!(Some(1), details.parse_offset);
+        assert_eq!(vec!["inner", "outer"], details.parse_context);
+    }
+
+    #[test]
+    fn it_includes_timed_out_command() {
+        let command = Command::new([1, 2, 3, 4, 5, 6, 7]);
+        let details = Error::TimedOut {
+            action: "test",
+            command: Some(command),
+        }
+        .details();
+        assert_eq!(ErrorCode::TimedOut, details.code);
+        assert_eq!(Some("test".to_owned()), details.action);
+        assert_eq!(Some(command), details.command);
+    }
+
+    #[test]
+    fn remote_error_keeps_original_details() {
+        let original = Error::FeatureNotSupported {
+            feature_name: "test",
+        }
+        .details();
+        let remote = Error::Remote {
+            details: Box::new(original.to_owned()),
+        };
+        assert_eq!(ErrorCode::FeatureNotSupported, remote.code());
+        assert_eq!(original, remote.details());
+        assert_eq!(original.message, remote.to_string());
+    }
+}
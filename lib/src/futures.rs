@@ -9,7 +9,7 @@ pub use futures_tokio::*;
 pub use futures_wasm::*;
 
 use futures::Future;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 pub trait JoinHandle {
     fn abort(&self);
@@ -24,4 +24,7 @@ pub trait Futures {
         R: Send + 'static;
     fn spawn_local(future: impl Future + 'static) -> Self::JoinHandleType;
     async fn sleep(duration: Duration);
+    fn now() -> SystemTime {
+        SystemTime::now()
+    }
 }
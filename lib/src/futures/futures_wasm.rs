@@ -1,4 +1,7 @@
-use std::{rc::Rc, time::Duration};
+use std::{
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use futures::Future;
 use tokio::{select, sync::Notify};
@@ -45,6 +48,11 @@ impl Futures for WasmFutures {
         .await
         .unwrap();
     }
+
+    // SystemTime::now panics on wasm32-unknown-unknown
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,299 @@
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::device::Device,
+    devices::standard::{
+        state::DeviceState,
+        structures::{HearId, SerialNumber, VolumeAdjustments},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct HearIdHistoryEntry {
+    /// Unix timestamp in seconds of when the profile was first seen on the device
+    pub recorded_at: u64,
+    pub hear_id: HearId,
+}
+
+/// Change in volume adjustments from one profile to another, in dB. Positive values mean the
+/// newer profile boosts that band more.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct HearIdDiff {
+    pub left: Vec<f64>,
+    pub right: Vec<f64>,
+    pub is_enabled_changed: bool,
+}
+
+impl HearIdDiff {
+    pub fn new(from: &HearId, to: &HearId) -> Self {
+        let difference = |from: &VolumeAdjustments, to: &VolumeAdjustments| {
+            from.adjustments()
+                .iter()
+                .zip(to.adjustments().iter())
+                .map(|(from, to)| to - from)
+                .collect()
+        };
+        Self {
+            left: difference(
+                &from.volume_adjustments().left,
+                &to.volume_adjustments().left,
+            ),
+            right: difference(
+                &from.volume_adjustments().right,
+                &to.volume_adjustments().right,
+            ),
+            is_enabled_changed: from.is_enabled() != to.is_enabled(),
+        }
+    }
+}
+
+/// Local record of every HearID profile seen on each device, keyed by serial number. Device
+/// registries record to it as profiles are read from and written to devices. The library doesn't
+/// persist it anywhere, so frontends are expected to save
+/// [`DeviceRegistry::hear_id_history`](crate::api::device::DeviceRegistry::hear_id_history) and
+/// load it back with
+/// [`DeviceRegistry::load_hear_id_history`](crate::api::device::DeviceRegistry::load_hear_id_history).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct HearIdHistory {
+    entries: BTreeMap<SerialNumber, Vec<HearIdHistoryEntry>>,
+}
+
+impl HearIdHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `hear_id` to the device's history, unless it is the same as the most recent entry.
+    /// Returns whether an entry was added.
+    pub fn record(
+        &mut self,
+        serial_number: SerialNumber,
+        hear_id: HearId,
+        recorded_at: SystemTime,
+    ) -> bool {
+        let entries = self.entries.entry(serial_number).or_default();
+        if entries.last().map(|entry| &entry.hear_id) == Some(&hear_id) {
+            return false;
+        }
+        entries.push(HearIdHistoryEntry {
+            recorded_at: recorded_at
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            hear_id,
+        });
+        true
+    }
+
+    /// Records the hear id from a state update. Since writes are reflected in the state, calling
+    /// this for every state update covers both profiles read from and written to the device.
+    pub fn record_state(&mut self, state: &DeviceState, recorded_at: SystemTime) -> bool {
+        match (&state.serial_number, &state.hear_id) {
+            (Some(serial_number), Some(hear_id)) => {
+                self.record(serial_number.to_owned(), hear_id.to_owned(), recorded_at)
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds entries from `other`, such as a history that was saved earlier. Entries are kept in
+    /// the order they were recorded, and entries that both histories have are only kept once.
+    pub fn merge(&mut self, other: HearIdHistory) {
+        for (serial_number, other_entries) in other.entries {
+            let entries = self.entries.entry(serial_number).or_default();
+            for entry in other_entries {
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+            entries.sort_by_key(|entry| entry.recorded_at);
+        }
+    }
+
+    /// Profiles from oldest to newest.
+    pub fn list(&self, serial_number: &SerialNumber) -> &[HearIdHistoryEntry] {
+        self.entries
+            .get(serial_number)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn serial_numbers(&self) -> impl Iterator<Item = &SerialNumber> {
+        self.entries.keys()
+    }
+
+    /// Returns None if either index is out of range.
+    pub fn diff(
+        &self,
+        serial_number: &SerialNumber,
+        from_index: usize,
+        to_index: usize,
+    ) -> Option<HearIdDiff> {
+        let entries = self.list(serial_number);
+        Some(HearIdDiff::new(
+            &entries.get(from_index)?.hear_id,
+            &entries.get(to_index)?.hear_id,
+        ))
+    }
+
+    pub fn clear(&mut self, serial_number: &SerialNumber) {
+        self.entries.remove(serial_number);
+    }
+
+    /// Writes a previous profile back to the device. It must be the device the profile was
+    /// recorded from.
+    pub async fn restore(&self, device: &impl Device, index: usize) -> crate::Result<()> {
+        let serial_number =
+            device
+                .state()
+                .await
+                .serial_number
+                .ok_or(crate::Error::MissingData {
+                    name: "serial number",
+                })?;
+        let entry = self
+            .list(&serial_number)
+            .get(index)
+            .ok_or(crate::Error::MissingData {
+                name: "hear id history entry",
+            })?;
+        device.set_hear_id(entry.hear_id.to_owned()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use macaddr::MacAddr6;
+
+    use super::HearIdHistory;
+    use crate::{
+        api::device::{Device, DeviceRegistry},
+        demo::device::{DemoDevice, DemoDeviceRegistry},
+        devices::standard::structures::{
+            BasicHearId, HearId, SerialNumber, StereoVolumeAdjustments, VolumeAdjustments,
+        },
+        futures::TokioFutures,
+    };
+
+    fn hear_id(left: f64, right: f64) -> HearId {
+        BasicHearId {
+            is_enabled: true,
+            volume_adjustments: StereoVolumeAdjustments {
+                left: VolumeAdjustments::new([left; 8]).unwrap(),
+                right: VolumeAdjustments::new([right; 8]).unwrap(),
+            },
+            time: 0,
+        }
+        .into()
+    }
+
+    #[test]
+    fn it_skips_duplicate_entries() {
+        let mut history = HearIdHistory::new();
+        let serial_number = SerialNumber::from("0123456789ABCDEF");
+        assert!(history.record(serial_number.to_owned(), hear_id(1.0, 1.0), UNIX_EPOCH));
+        assert!(!history.record(serial_number.to_owned(), hear_id(1.0, 1.0), UNIX_EPOCH));
+        assert!(history.record(serial_number.to_owned(), hear_id(2.0, 1.0), UNIX_EPOCH));
+        assert_eq!(2, history.list(&serial_number).len());
+    }
+
+    #[test]
+    fn it_keeps_devices_separate() {
+        let mut history = HearIdHistory::new();
+        let first = SerialNumber::from("0123456789ABCDEF");
+        let second = SerialNumber::from("FEDCBA9876543210");
+        history.record(first.to_owned(), hear_id(1.0, 1.0), UNIX_EPOCH);
+        history.record(
+            second.to_owned(),
+            hear_id(1.0, 1.0),
+            UNIX_EPOCH + Duration::from_secs(100),
+        );
+        assert_eq!(1, history.list(&first).len());
+        assert_eq!(100, history.list(&second)[0].recorded_at);
+    }
+
+    #[test]
+    fn it_diffs_entries() {
+        let mut history = HearIdHistory::new();
+        let serial_number = SerialNumber::from("0123456789ABCDEF");
+        history.record(serial_number.to_owned(), hear_id(1.0, 2.0), UNIX_EPOCH);
+        history.record(serial_number.to_owned(), hear_id(3.0, 1.5), UNIX_EPOCH);
+        let diff = history.diff(&serial_number, 0, 1).unwrap();
+        assert_eq!(vec![2.0; 8], diff.left);
+        assert_eq!(vec![-0.5; 8], diff.right);
+        assert!(!diff.is_enabled_changed);
+        assert_eq!(None, history.diff(&serial_number, 0, 2));
+    }
+
+    #[test]
+    fn it_merges_histories_without_duplicates() {
+        let serial_number = SerialNumber::from("0123456789ABCDEF");
+        let mut saved = HearIdHistory::new();
+        saved.record(serial_number.to_owned(), hear_id(1.0, 1.0), UNIX_EPOCH);
+        saved.record(
+            serial_number.to_owned(),
+            hear_id(2.0, 2.0),
+            UNIX_EPOCH + Duration::from_secs(200),
+        );
+        let mut history = saved.to_owned();
+        history.record(
+            serial_number.to_owned(),
+            hear_id(3.0, 3.0),
+            UNIX_EPOCH + Duration::from_secs(100),
+        );
+
+        history.merge(saved);
+        assert_eq!(
+            vec![0, 100, 200],
+            history
+                .list(&serial_number)
+                .iter()
+                .map(|entry| entry.recorded_at)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_restores_previous_profile() {
+        let device = DemoDevice::<TokioFutures>::new("Demo", MacAddr6::nil()).await;
+        let mut history = HearIdHistory::new();
+        let original = device.state().await.hear_id.unwrap();
+        history.record_state(&device.state().await, UNIX_EPOCH);
+
+        device.set_hear_id(hear_id(5.0, 5.0)).await.unwrap();
+        history.record_state(&device.state().await, UNIX_EPOCH);
+
+        history.restore(&device, 0).await.unwrap();
+        assert_eq!(Some(original), device.state().await.hear_id);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_loads_history_into_registry() {
+        let registry = DemoDeviceRegistry::<TokioFutures>::new();
+        let mut history = HearIdHistory::new();
+        history.record(
+            SerialNumber::from("0123456789ABCDEF"),
+            hear_id(5.0, 5.0),
+            UNIX_EPOCH,
+        );
+        registry
+            .load_hear_id_history(history.to_owned())
+            .await
+            .unwrap();
+        assert_eq!(history, registry.hear_id_history().await.unwrap());
+    }
+}
@@ -0,0 +1,332 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::devices::standard::structures::{
+    CustomHearId, HearIdMusicType, HearIdType, StereoVolumeAdjustments, VolumeAdjustments,
+    VolumeAdjustmentsError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Ear {
+    Left,
+    Right,
+}
+
+/// A tone to be played to the listener. `level` is in dB HL, so 0 is the quietest level that a
+/// person with typical hearing can hear at that frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Tone {
+    pub ear: Ear,
+    pub frequency: f64,
+    pub level: f64,
+}
+
+/// Plays test tones. Frontends implement this with a real audio sink.
+pub trait TonePlayer {
+    async fn play(&self, tone: Tone, duration: Duration) -> crate::Result<()>;
+}
+
+/// Finds the hearing threshold for one band of one ear using a simplified Hughson-Westlake
+/// staircase: go down 10 dB after each heard tone and up 5 dB after each missed one. The
+/// threshold is the first level heard twice while ascending.
+#[derive(Debug, Clone)]
+struct Staircase {
+    level: i32,
+    was_last_heard: bool,
+    ascending_hits: Vec<i32>,
+    num_presentations: usize,
+    threshold: Option<i32>,
+}
+
+impl Staircase {
+    fn new() -> Self {
+        Self {
+            level: HearIdTest::START_LEVEL,
+            was_last_heard: true,
+            ascending_hits: Vec::new(),
+            num_presentations: 0,
+            threshold: None,
+        }
+    }
+
+    fn respond(&mut self, is_heard: bool) {
+        self.num_presentations += 1;
+        if is_heard {
+            if !self.was_last_heard {
+                if self.ascending_hits.contains(&self.level) {
+                    self.threshold = Some(self.level);
+                    return;
+                }
+                self.ascending_hits.push(self.level);
+            }
+            if self.level == HearIdTest::MIN_LEVEL {
+                self.threshold = Some(self.level);
+                return;
+            }
+            self.level = (self.level - 10).max(HearIdTest::MIN_LEVEL);
+        } else {
+            if self.level == HearIdTest::MAX_LEVEL {
+                self.threshold = Some(self.level);
+                return;
+            }
+            self.level = (self.level + 5).min(HearIdTest::MAX_LEVEL);
+        }
+        self.was_last_heard = is_heard;
+
+        if self.num_presentations >= HearIdTest::MAX_PRESENTATIONS_PER_BAND {
+            // The listener's responses aren't converging, so settle for the best guess
+            self.threshold = Some(
+                self.ascending_hits
+                    .iter()
+                    .cloned()
+                    .min()
+                    .unwrap_or(self.level),
+            );
+        }
+    }
+}
+
+/// Measures hearing thresholds for each band of each ear and converts them into a
+/// [`CustomHearId`].
+///
+/// The test is driven one tone at a time: play [`HearIdTest::current_tone`] (for example with
+/// [`HearIdTest::play_current_tone`]), then report whether the listener heard it with
+/// [`HearIdTest::respond`], until [`HearIdTest::is_complete`] returns true.
+#[derive(Debug, Clone)]
+pub struct HearIdTest {
+    frequencies: Vec<f64>,
+    left: Vec<Staircase>,
+    right: Vec<Staircase>,
+}
+
+impl HearIdTest {
+    pub const MIN_LEVEL: i32 = 0;
+    pub const MAX_LEVEL: i32 = 80;
+    pub const START_LEVEL: i32 = 40;
+    pub const TONE_DURATION: Duration = Duration::from_millis(1000);
+    /// Thresholds at or below this level are considered typical hearing and are not compensated.
+    pub const TYPICAL_HEARING_THRESHOLD: f64 = 20.0;
+    const MAX_PRESENTATIONS_PER_BAND: usize = 20;
+
+    pub fn new(num_bands: usize) -> Result<Self, VolumeAdjustmentsError> {
+        // Validate the number of bands up front rather than after the user has finished the test
+        VolumeAdjustments::new(vec![0.0; num_bands])?;
+        Ok(Self {
+            frequencies: VolumeAdjustments::band_frequencies(num_bands).collect(),
+            left: vec![Staircase::new(); num_bands],
+            right: vec![Staircase::new(); num_bands],
+        })
+    }
+
+    fn staircases(&self, ear: Ear) -> &[Staircase] {
+        match ear {
+            Ear::Left => &self.left,
+            Ear::Right => &self.right,
+        }
+    }
+
+    fn current_staircase(&self) -> Option<(Ear, usize)> {
+        Ear::iter().find_map(|ear| {
+            self.staircases(ear)
+                .iter()
+                .position(|staircase| staircase.threshold.is_none())
+                .map(|band| (ear, band))
+        })
+    }
+
+    /// The tone the listener should hear next, or None if the test is complete.
+    pub fn current_tone(&self) -> Option<Tone> {
+        self.current_staircase().map(|(ear, band)| Tone {
+            ear,
+            frequency: self.frequencies[band],
+            level: self.staircases(ear)[band].level as f64,
+        })
+    }
+
+    pub async fn play_current_tone(&self, player: &impl TonePlayer) -> crate::Result<()> {
+        if let Some(tone) = self.current_tone() {
+            player.play(tone, Self::TONE_DURATION).await?;
+        }
+        Ok(())
+    }
+
+    /// Records whether the listener heard the current tone and moves on to the next one.
+    pub fn respond(&mut self, is_heard: bool) {
+        if let Some((ear, band)) = self.current_staircase() {
+            let staircases = match ear {
+                Ear::Left => &mut self.left,
+                Ear::Right => &mut self.right,
+            };
+            staircases[band].respond(is_heard);
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current_staircase().is_none()
+    }
+
+    /// Fraction of bands that have a threshold, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        let num_done = self
+            .left
+            .iter()
+            .chain(self.right.iter())
+            .filter(|staircase| staircase.threshold.is_some())
+            .count();
+        num_done as f64 / (self.left.len() + self.right.len()) as f64
+    }
+
+    /// Hearing thresholds in dB HL for each band, or None if the ear has not been fully tested.
+    pub fn thresholds(&self, ear: Ear) -> Option<Vec<f64>> {
+        self.staircases(ear)
+            .iter()
+            .map(|staircase| staircase.threshold.map(f64::from))
+            .collect()
+    }
+
+    /// Converts the thresholds into volume adjustments using the half gain rule, which boosts
+    /// each band by half of the hearing loss in that band. Returns None if the test is not
+    /// complete.
+    pub fn volume_adjustments(&self) -> Option<StereoVolumeAdjustments> {
+        let to_volume_adjustments =
+            |thresholds: Vec<f64>| {
+                VolumeAdjustments::new(thresholds.into_iter().map(|threshold| {
+                    ((threshold - Self::TYPICAL_HEARING_THRESHOLD) / 2.0).max(0.0)
+                }))
+                .expect("number of bands was validated in HearIdTest::new")
+            };
+        Some(StereoVolumeAdjustments {
+            left: to_volume_adjustments(self.thresholds(Ear::Left)?),
+            right: to_volume_adjustments(self.thresholds(Ear::Right)?),
+        })
+    }
+
+    /// Builds a hear id from the results, timestamped with `completed_at`. Returns None if the
+    /// test is not complete.
+    pub fn custom_hear_id(&self, completed_at: SystemTime) -> Option<CustomHearId> {
+        let time = completed_at
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| i32::try_from(duration.as_secs()).unwrap_or(i32::MAX))
+            .unwrap_or_default();
+        Some(CustomHearId {
+            is_enabled: true,
+            volume_adjustments: self.volume_adjustments()?,
+            time,
+            hear_id_type: HearIdType::HearingTest,
+            hear_id_music_type: HearIdMusicType::default(),
+            custom_volume_adjustments: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Ear, HearIdTest, Tone};
+    use crate::{
+        devices::standard::structures::{HearIdType, VolumeAdjustments},
+        stub::tone_player::StubTonePlayer,
+    };
+
+    // Simulated listener that hears anything at or above the given thresholds
+    fn run_test(left: [i32; 8], right: [i32; 8]) -> HearIdTest {
+        let mut test = HearIdTest::new(8).unwrap();
+        while let Some(tone) = test.current_tone() {
+            let band = VolumeAdjustments::band_frequencies(8)
+                .position(|frequency| frequency == tone.frequency)
+                .unwrap();
+            let threshold = match tone.ear {
+                Ear::Left => left[band],
+                Ear::Right => right[band],
+            };
+            test.respond(tone.level >= threshold as f64);
+        }
+        test
+    }
+
+    #[test]
+    fn it_finds_thresholds() {
+        let left = [0, 10, 15, 20, 25, 35, 50, 80];
+        let right = [5, 5, 5, 5, 5, 5, 5, 5];
+        let test = run_test(left, right);
+        assert!(test.is_complete());
+        assert_eq!(1.0, test.progress());
+        assert_eq!(
+            Some(left.map(f64::from).to_vec()),
+            test.thresholds(Ear::Left)
+        );
+        assert_eq!(
+            Some(right.map(f64::from).to_vec()),
+            test.thresholds(Ear::Right)
+        );
+    }
+
+    #[test]
+    fn it_stops_at_max_level_when_nothing_is_heard() {
+        let test = run_test([1000; 8], [1000; 8]);
+        assert_eq!(
+            Some(vec![HearIdTest::MAX_LEVEL as f64; 8]),
+            test.thresholds(Ear::Left)
+        );
+    }
+
+    #[test]
+    fn it_does_not_boost_typical_hearing() {
+        let test = run_test([0, 10, 15, 20, 25, 35, 50, 80], [20; 8]);
+        let volume_adjustments = test.volume_adjustments().unwrap();
+        assert_eq!(
+            VolumeAdjustments::new([0.0, 0.0, 0.0, 0.0, 2.5, 7.5, 13.5, 13.5]).unwrap(),
+            volume_adjustments.left,
+        );
+        assert_eq!(VolumeAdjustments::default(), volume_adjustments.right);
+    }
+
+    #[test]
+    fn it_has_no_result_until_complete() {
+        let mut test = HearIdTest::new(8).unwrap();
+        test.respond(true);
+        assert!(!test.is_complete());
+        assert_eq!(None, test.thresholds(Ear::Left));
+        assert_eq!(None, test.custom_hear_id(UNIX_EPOCH));
+    }
+
+    #[test]
+    fn it_creates_custom_hear_id() {
+        let test = run_test([20; 8], [20; 8]);
+        let hear_id = test
+            .custom_hear_id(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            .unwrap();
+        assert!(hear_id.is_enabled);
+        assert_eq!(1_700_000_000, hear_id.time);
+        assert_eq!(HearIdType::HearingTest, hear_id.hear_id_type);
+        assert_eq!(None, hear_id.custom_volume_adjustments);
+    }
+
+    #[test]
+    fn it_rejects_invalid_number_of_bands() {
+        assert!(HearIdTest::new(3).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_plays_current_tone() {
+        let test = HearIdTest::new(8).unwrap();
+        let player = StubTonePlayer::new();
+        test.play_current_tone(&player).await.unwrap();
+        assert_eq!(
+            vec![Tone {
+                ear: Ear::Left,
+                frequency: 100.0,
+                level: HearIdTest::START_LEVEL as f64,
+            }],
+            player.played_tones().await,
+        );
+    }
+}
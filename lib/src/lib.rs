@@ -5,12 +5,16 @@
 // This crate should not be used outside of this git repository, so breaking api changes are fine.
 #![allow(clippy::type_complexity, async_fn_in_trait)]
 pub mod api;
+pub mod bug_report;
 pub mod demo;
 pub mod device_profile;
 pub mod device_utils;
 pub mod devices;
+pub mod equalizer_comparison;
 mod error;
 pub mod futures;
+pub mod hear_id_history;
+pub mod hear_id_test;
 pub mod soundcore_device;
 pub mod stub;
 
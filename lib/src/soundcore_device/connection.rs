@@ -5,10 +5,23 @@ pub(crate) mod btleplug;
 #[cfg(all(feature = "bluetooth", target_os = "windows"))]
 pub(crate) mod windows;
 
+#[cfg(all(feature = "bluetooth", any(target_os = "macos", target_os = "linux")))]
+pub(crate) type PlatformConnectionRegistry = btleplug::BtlePlugConnectionRegistry;
+#[cfg(all(feature = "bluetooth", target_os = "windows"))]
+pub(crate) type PlatformConnectionRegistry = windows::WindowsConnectionRegistry;
+
 #[cfg(feature = "bluetooth")]
 pub async fn new_connection_registry(
     handle: Option<tokio::runtime::Handle>,
 ) -> crate::Result<impl ConnectionRegistry> {
+    new_platform_connection_registry(handle).await
+}
+
+/// Same as [`new_connection_registry`], but with a nameable type so that it can be stored.
+#[cfg(feature = "bluetooth")]
+pub(crate) async fn new_platform_connection_registry(
+    handle: Option<tokio::runtime::Handle>,
+) -> crate::Result<PlatformConnectionRegistry> {
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     {
         btleplug::new_connection_registry(handle).await
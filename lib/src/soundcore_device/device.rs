@@ -1,11 +1,16 @@
+mod coalescing_writer;
+mod degraded_mode;
 pub(crate) mod device_implementation;
+mod model_detection;
 mod multi_queue;
 mod packet;
-mod packet_io_controller;
+pub(crate) mod packet_io_controller;
 pub(crate) mod soundcore_command;
 mod soundcore_device;
 mod soundcore_device_registry;
 
+pub use degraded_mode::*;
+pub use model_detection::*;
 pub(crate) use packet::*;
 pub use soundcore_device::*;
 pub use soundcore_device_registry::*;
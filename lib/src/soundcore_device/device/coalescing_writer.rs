@@ -0,0 +1,210 @@
+use std::future::Future;
+
+use tokio::sync::Mutex;
+
+const LOCK_HELD_ERROR: &str =
+    "lock should not already be held by current thread. was it held across an await?";
+
+/// Serializes writes of a single setting while collapsing any writes that queue up behind an
+/// in-flight one, so that only the most recent value is sent once the device is ready again.
+#[derive(Debug)]
+pub struct CoalescingWriter<T, R> {
+    queue: std::sync::Mutex<Queue<T, R>>,
+    write_lock: Mutex<()>,
+    share: fn(&R) -> R,
+}
+
+#[derive(Debug)]
+struct Queue<T, R> {
+    pending: Option<T>,
+    // Incremented for every value that is queued, so that callers can tell whether a write
+    // included their value
+    generation: u64,
+    // The latest write's result, along with the generation of the value it wrote
+    written: Option<(u64, R)>,
+}
+
+impl<T: Clone, R> CoalescingWriter<T, R> {
+    /// `share` copies the result of a write for the callers whose values it replaced.
+    pub fn new(share: fn(&R) -> R) -> Self {
+        Self {
+            queue: std::sync::Mutex::new(Queue {
+                pending: None,
+                generation: 0,
+                written: None,
+            }),
+            write_lock: Default::default(),
+            share,
+        }
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.queue.lock().expect(LOCK_HELD_ERROR).pending.is_some()
+    }
+
+    /// Queues `value`, replacing any value that is still waiting to be written, and then waits
+    /// for any in-flight write to finish. Whichever caller gets the next turn writes the latest
+    /// queued value. Every caller gets the result of the write that sent its value or a newer
+    /// one: the caller that called `write` gets it as is, and the others get a copy made with
+    /// `share`.
+    pub async fn write<F, Fut>(&self, value: T, write: F) -> R
+    where
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        let ticket = {
+            let mut queue = self.queue.lock().expect(LOCK_HELD_ERROR);
+            queue.pending = Some(value);
+            queue.generation += 1;
+            queue.generation
+        };
+        let _guard = self.write_lock.lock().await;
+        let (value, generation) = {
+            let mut queue = self.queue.lock().expect(LOCK_HELD_ERROR);
+            if let Some((written_generation, result)) = &queue.written {
+                if *written_generation >= ticket {
+                    return (self.share)(result);
+                }
+            }
+            let value = queue
+                .pending
+                .take()
+                .expect("values that weren't written yet should be pending");
+            (value, queue.generation)
+        };
+
+        let restore = RestorePendingOnDrop {
+            writer: self,
+            value: Some(value.to_owned()),
+        };
+        let output = write(value).await;
+        std::mem::forget(restore);
+
+        let mut queue = self.queue.lock().expect(LOCK_HELD_ERROR);
+        queue.written = Some((generation, (self.share)(&output)));
+        output
+    }
+}
+
+/// Puts the value back in the queue if its write is cancelled, so that callers waiting on it
+/// write it themselves rather than finding nothing to write.
+struct RestorePendingOnDrop<'a, T, R> {
+    writer: &'a CoalescingWriter<T, R>,
+    value: Option<T>,
+}
+
+impl<T, R> Drop for RestorePendingOnDrop<'_, T, R> {
+    fn drop(&mut self) {
+        let mut queue = self.writer.queue.lock().expect(LOCK_HELD_ERROR);
+        // A newer value replaces this one anyway
+        if queue.pending.is_none() {
+            queue.pending = self.value.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::sync::Mutex;
+
+    use super::CoalescingWriter;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_only_latest_pending_value_is_written() {
+        let writer = Arc::new(CoalescingWriter::new(|value: &i32| *value));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let write = |value: i32| {
+            let written = written.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                written.lock().await.push(value);
+                value
+            }
+        };
+
+        let (first, second, third) = tokio::join!(
+            writer.write(1, write),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                writer.write(2, write).await
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                writer.write(3, write).await
+            },
+        );
+
+        assert_eq!(1, first);
+        // 2 was replaced by 3 while 1 was being written, so the second call writes 3 and the
+        // third call gets the result of that write
+        assert_eq!(3, second);
+        assert_eq!(3, third);
+        assert_eq!(vec![1, 3], *written.lock().await);
+        assert!(!writer.has_pending());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_replaced_callers_get_result_of_write_that_replaced_them() {
+        let writer = CoalescingWriter::new(|result: &Result<i32, String>| result.to_owned());
+        let write = |value: i32| async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if value == 3 {
+                Err(format!("failed to write {value}"))
+            } else {
+                Ok(value)
+            }
+        };
+
+        let (first, second, third) = tokio::join!(
+            writer.write(1, write),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                writer.write(2, write).await
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                writer.write(3, write).await
+            },
+        );
+
+        assert_eq!(Ok(1), first);
+        assert_eq!(Err("failed to write 3".to_string()), second);
+        assert_eq!(Err("failed to write 3".to_string()), third);
+    }
+
+    #[tokio::test]
+    async fn test_sequential_writes_are_all_written() {
+        let writer = CoalescingWriter::new(|value: &i32| *value);
+        assert_eq!(1, writer.write(1, |value| async move { value }).await);
+        assert_eq!(2, writer.write(2, |value| async move { value }).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_write_is_written_by_next_caller() {
+        let writer = CoalescingWriter::new(|value: &i32| *value);
+        let write = |value: i32| async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            value
+        };
+
+        let (first, second, third) = tokio::join!(
+            writer.write(1, write),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                // Cancelled while writing 3 on behalf of the third call
+                tokio::time::timeout(Duration::from_millis(150), writer.write(2, write)).await
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                writer.write(3, write).await
+            },
+        );
+
+        assert_eq!(1, first);
+        assert!(second.is_err());
+        assert_eq!(3, third);
+        assert!(!writer.has_pending());
+    }
+}
@@ -0,0 +1,19 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::soundcore_device::device_model::DeviceModel;
+
+/// The device's state update packet was only partially understood, most likely because a firmware
+/// update added fields that we don't know about yet. Everything that could be parsed is still
+/// available, but anything stored in the unknown bytes is not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DegradedMode {
+    /// Model determined from the serial number, since the packet as a whole didn't match any
+    pub model: DeviceModel,
+    /// Bytes at the end of the initial state update packet that were ignored
+    pub trailing_bytes: Vec<u8>,
+    /// Why parsing the full packet failed
+    pub parse_error: String,
+}
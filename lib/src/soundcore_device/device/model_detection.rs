@@ -0,0 +1,11 @@
+use crate::soundcore_device::device_model::DeviceModel;
+
+/// How the device's model was determined while connecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelDetection {
+    /// The serial number reported by the device identified the model.
+    SerialNumber(DeviceModel),
+    /// The serial number couldn't be requested or didn't match a known model, so each model's
+    /// state update packet parser was tried until one succeeded.
+    StateUpdatePacket,
+}
@@ -5,6 +5,7 @@ use tokio::{select, sync::mpsc};
 
 use crate::{
     api::connection::Connection,
+    bug_report::{PacketDirection, TrafficLog},
     devices::standard::{packets::inbound::take_inbound_packet_header, structures::Command},
     futures::{Futures, JoinHandle},
 };
@@ -14,6 +15,7 @@ use super::{multi_queue::MultiQueue, Packet};
 pub struct PacketIOController<ConnectionType: Connection, FuturesType: Futures> {
     connection: Arc<ConnectionType>,
     packet_queues: Arc<MultiQueue<Command, Packet>>,
+    traffic_log: TrafficLog,
     handle: FuturesType::JoinHandleType,
     _futures: PhantomData<FuturesType>,
 }
@@ -29,17 +31,24 @@ impl<ConnectionType: Connection, FuturesType: Futures> Drop
 impl<ConnectionType: Connection, FuturesType: Futures>
     PacketIOController<ConnectionType, FuturesType>
 {
+    /// Records all packets sent and received, including ones with headers that fail to parse, in
+    /// `traffic_log`.
     pub async fn new(
         connection: Arc<ConnectionType>,
+        traffic_log: TrafficLog,
     ) -> crate::Result<(Self, mpsc::Receiver<Packet>)> {
         let packet_queues = Arc::new(MultiQueue::new());
         let incoming_receiver = connection.inbound_packets_channel().await?;
-        let (handle, outgoing_receiver) =
-            Self::spawn_packet_handler(packet_queues.clone(), incoming_receiver);
+        let (handle, outgoing_receiver) = Self::spawn_packet_handler(
+            packet_queues.clone(),
+            incoming_receiver,
+            traffic_log.to_owned(),
+        );
         Ok((
             Self {
                 connection,
                 packet_queues,
+                traffic_log,
                 handle,
                 _futures: PhantomData,
             },
@@ -50,10 +59,12 @@ impl<ConnectionType: Connection, FuturesType: Futures>
     fn spawn_packet_handler(
         packet_queues: Arc<MultiQueue<Command, Packet>>,
         mut incoming_receiver: mpsc::Receiver<Vec<u8>>,
+        traffic_log: TrafficLog,
     ) -> (FuturesType::JoinHandleType, mpsc::Receiver<Packet>) {
         let (outgoing_sender, outgoing_receiver) = mpsc::channel(100);
         let handle = FuturesType::spawn(async move {
             while let Some(bytes) = incoming_receiver.recv().await {
+                traffic_log.record(PacketDirection::Inbound, &bytes);
                 let (body, header) = match take_inbound_packet_header::<VerboseError<_>>(&bytes) {
                     Ok(parsed) => parsed,
                     Err(err) => {
@@ -86,8 +97,10 @@ impl<ConnectionType: Connection, FuturesType: Futures>
         handle.wait_for_start().await;
 
         // retry
+        let bytes = packet.bytes();
         for i in 1..=3 {
-            self.connection.write_with_response(&packet.bytes()).await?;
+            self.traffic_log.record(PacketDirection::Outbound, &bytes);
+            self.connection.write_with_response(&bytes).await?;
             let result = select! {
                 result = handle.wait_for_end() => result,
                 _ = FuturesType::sleep(Duration::from_millis(500 * i)) => None,
@@ -101,6 +114,7 @@ impl<ConnectionType: Connection, FuturesType: Futures>
 
         Err(crate::Error::TimedOut {
             action: "resending packet until ack received",
+            command: Some(packet.command()),
         })
     }
 }
@@ -136,7 +150,7 @@ mod tests {
             connection.push_write_return(Ok(())).await;
         }
         let controller = Arc::new(
-            PacketIOController::<_, TokioFutures>::new(connection)
+            PacketIOController::<_, TokioFutures>::new(connection, TrafficLog::default())
                 .await
                 .unwrap()
                 .0,
@@ -191,7 +205,7 @@ mod tests {
             connection.push_write_return(Ok(())).await;
         }
         let controller = Arc::new(
-            PacketIOController::<_, TokioFutures>::new(connection)
+            PacketIOController::<_, TokioFutures>::new(connection, TrafficLog::default())
                 .await
                 .unwrap()
                 .0,
@@ -10,23 +10,31 @@ use crate::{
         self,
         connection::{Connection, ConnectionStatus},
     },
+    bug_report::TrafficLog,
+    device_profile::DeviceProfile,
     devices::standard::{
         packets::{
-            inbound::{state_update_packet::StateUpdatePacket, TryIntoInboundPacket},
+            inbound::{
+                state_update_packet::StateUpdatePacket, FirmwareVersionUpdatePacket, InboundPacket,
+                TryIntoInboundPacket,
+            },
             outbound::{RequestFirmwareVersionPacket, RequestStatePacket},
         },
         state::DeviceState,
         structures::{
-            AmbientSoundModeCycle, Command, CustomButtonModel, EqualizerConfiguration, HearId,
-            SoundModes, SoundModesTypeTwo,
+            AgeRange, AmbientSoundModeCycle, Command, CustomButtonModel, EqualizerConfiguration,
+            Gender, HearId, SoundModes, SoundModesTypeTwo,
         },
     },
     futures::{Futures, JoinHandle},
+    hear_id_history::HearIdHistory,
+    soundcore_device::device_model::DeviceModel,
 };
 
 use super::{
-    device_implementation::DeviceImplementation, packet_io_controller::PacketIOController,
-    soundcore_command::CommandResponse, Packet,
+    coalescing_writer::CoalescingWriter, device_implementation::DeviceImplementation,
+    packet_io_controller::PacketIOController, soundcore_command::CommandResponse, DegradedMode,
+    ModelDetection, Packet,
 };
 
 pub struct SoundcoreDevice<ConnectionType, FuturesType>
@@ -39,6 +47,19 @@ where
     state_sender: Arc<Mutex<watch::Sender<DeviceState>>>,
     join_handle: FuturesType::JoinHandleType,
     implementation: Arc<dyn DeviceImplementation + Send + Sync>,
+    equalizer_writer: CoalescingWriter<EqualizerConfiguration, crate::Result<()>>,
+    // The last equalizer configuration known to be on the device, to roll back to if a write fails
+    written_equalizer_configuration: std::sync::Mutex<EqualizerConfiguration>,
+    degraded_mode: Option<DegradedMode>,
+    model_detection: ModelDetection,
+    hear_id_history: Arc<std::sync::Mutex<HearIdHistory>>,
+}
+
+struct InitialState {
+    state: DeviceState,
+    implementation: Arc<dyn DeviceImplementation + Send + Sync>,
+    degraded_mode: Option<DegradedMode>,
+    model_detection: ModelDetection,
 }
 
 impl<ConnectionType, FuturesType> SoundcoreDevice<ConnectionType, FuturesType>
@@ -47,27 +68,48 @@ where
     FuturesType: Futures,
 {
     pub async fn new(connection: Arc<ConnectionType>) -> crate::Result<Self> {
-        let (controller, receiver) = PacketIOController::new(connection.clone()).await?;
-        let (initial_state, implementation) = Self::fetch_initial_state(&controller).await?;
+        Self::with_hear_id_history(connection, Default::default()).await
+    }
+
+    /// Records HearID profiles read from and written to the device in `hear_id_history`, which may
+    /// be shared with other devices.
+    pub async fn with_hear_id_history(
+        connection: Arc<ConnectionType>,
+        hear_id_history: Arc<std::sync::Mutex<HearIdHistory>>,
+    ) -> crate::Result<Self> {
+        Self::with_traffic_log(connection, hear_id_history, TrafficLog::default()).await
+    }
+
+    /// Also records recent packets in `traffic_log`, so that they can be included in bug reports.
+    pub(crate) async fn with_traffic_log(
+        connection: Arc<ConnectionType>,
+        hear_id_history: Arc<std::sync::Mutex<HearIdHistory>>,
+        traffic_log: TrafficLog,
+    ) -> crate::Result<Self> {
+        let (controller, receiver) =
+            PacketIOController::new(connection.clone(), traffic_log).await?;
+        let InitialState {
+            state: initial_state,
+            implementation,
+            degraded_mode,
+            model_detection,
+        } = Self::fetch_initial_state(&controller).await?;
 
-        let is_serial_number_missing = initial_state.serial_number.is_none();
         let packet_handlers = implementation.packet_handlers();
+        let written_equalizer_configuration =
+            std::sync::Mutex::new(initial_state.equalizer_configuration.to_owned());
+        Self::record_hear_id(&hear_id_history, &initial_state);
 
         let (state_sender, _) = watch::channel(initial_state);
         let state_sender = Arc::new(Mutex::new(state_sender));
 
-        let join_handle =
-            Self::spawn_inbound_packet_handler(packet_handlers, receiver, state_sender.to_owned());
-
-        if is_serial_number_missing {
-            tracing::debug!(
-                "requesting serial number since it was not included in the state update packet"
-            );
-            // The implementation will handle the response, so we can send the request and forget about it
-            controller
-                .send(&RequestFirmwareVersionPacket::new().into())
-                .await?;
-        }
+        let join_handle = Self::spawn_inbound_packet_handler(
+            packet_handlers,
+            receiver,
+            state_sender.to_owned(),
+            hear_id_history.to_owned(),
+            degraded_mode.is_some(),
+        );
 
         Ok(Self {
             controller,
@@ -75,19 +117,180 @@ where
             join_handle,
             state_sender,
             implementation,
+            equalizer_writer: CoalescingWriter::new(|result| match result {
+                Ok(()) => Ok(()),
+                // Errors can't be cloned, so callers whose configuration was replaced get one with
+                // the same code and details
+                Err(err) => Err(crate::Error::Remote {
+                    details: Box::new(err.details()),
+                }),
+            }),
+            written_equalizer_configuration,
+            degraded_mode,
+            model_detection,
+            hear_id_history,
         })
     }
 
-    pub async fn fetch_initial_state(
+    pub fn model_detection(&self) -> ModelDetection {
+        self.model_detection
+    }
+
+    async fn fetch_initial_state(
+        controller: &PacketIOController<ConnectionType, FuturesType>,
+    ) -> crate::Result<InitialState> {
+        tracing::debug!("requesting state");
+        let state_update = controller.send(&RequestStatePacket::new().into()).await?;
+        // Devices that don't answer the firmware version request would cost us its retries, so
+        // only ask when the state update packet doesn't tell us the serial number itself.
+        let (model, firmware_version_update) = match Self::model_from_state_update(&state_update) {
+            Some(model) => (Some(model), None),
+            None => {
+                let packet = Self::request_firmware_version(controller).await;
+                let model = packet
+                    .as_ref()
+                    .and_then(|packet| DeviceModel::from_serial_number(&packet.serial_number));
+                (model, packet)
+            }
+        };
+        let detected_model =
+            model.and_then(|model| Some((model, DeviceProfile::from_model(&model)?)));
+
+        let mut initial_state = match detected_model {
+            Some((model, profile)) => {
+                tracing::debug!("detected {model} from serial number");
+                let implementation = (profile.implementation)();
+                match Self::initialize_tolerantly(&*implementation, &state_update.body, model) {
+                    Ok((state, degraded_mode)) => InitialState {
+                        state,
+                        implementation,
+                        degraded_mode,
+                        model_detection: ModelDetection::SerialNumber(model),
+                    },
+                    Err(err) => {
+                        warn!("state doesn't match {model}, guessing from state instead: {err:?}");
+                        Self::guess_initial_state(&state_update)?
+                    }
+                }
+            }
+            None => {
+                warn!("could not detect model from serial number, guessing from state instead");
+                Self::guess_initial_state(&state_update)?
+            }
+        };
+
+        if let Some(packet) = firmware_version_update {
+            let state = &mut initial_state.state;
+            state.serial_number.get_or_insert(packet.serial_number);
+            state.firmware_version.get_or_insert(
+                packet
+                    .left_firmware_version
+                    .max(packet.right_firmware_version),
+            );
+        }
+        Ok(initial_state)
+    }
+
+    /// Most state update packets include the serial number, which is enough to know the model if
+    /// any parser understands the packet, or at least its known prefix.
+    fn model_from_state_update(state_update: &Packet) -> Option<DeviceModel> {
+        let result: Result<StateUpdatePacket, _> = state_update.try_into_inbound_packet();
+        let packet = result.ok().or_else(|| {
+            StateUpdatePacket::take_known_prefix(&state_update.body).map(|(packet, _)| packet)
+        })?;
+        DeviceModel::from_serial_number(packet.serial_number.as_ref()?)
+    }
+
+    async fn request_firmware_version(
         controller: &PacketIOController<ConnectionType, FuturesType>,
-    ) -> crate::Result<(DeviceState, Arc<dyn DeviceImplementation + Send + Sync>)> {
-        tracing::debug!("requesting state to determine model");
-        let unparsed = controller.send(&RequestStatePacket::new().into()).await?;
-        let parsed: StateUpdatePacket = unparsed.try_into_inbound_packet()?;
-        let implementation = (parsed.device_profile.implementation)();
-        let state = implementation.initialize(&unparsed.body)?;
+    ) -> Option<FirmwareVersionUpdatePacket> {
+        tracing::debug!("requesting serial number to determine model");
+        let response = controller
+            .send(&RequestFirmwareVersionPacket::new().into())
+            .await
+            .inspect_err(|err| tracing::debug!("failed to request serial number: {err:?}"))
+            .ok()?;
+        response
+            .try_into_inbound_packet()
+            .inspect_err(|err| tracing::debug!("failed to parse serial number: {err:?}"))
+            .ok()
+    }
+
+    /// Picks the model by trying each model's state update packet parser, for when the serial
+    /// number is not available.
+    fn guess_initial_state(state_update: &Packet) -> crate::Result<InitialState> {
+        let result: Result<StateUpdatePacket, _> = state_update.try_into_inbound_packet();
+        let (profile, model, known_body_length) = match result {
+            Ok(parsed) => (parsed.device_profile, None, state_update.body.len()),
+            Err(err) => {
+                let Some((parsed, trailing_bytes)) =
+                    StateUpdatePacket::take_known_prefix(&state_update.body)
+                else {
+                    return Err(err.into());
+                };
+                let model = parsed
+                    .serial_number
+                    .as_ref()
+                    .and_then(DeviceModel::from_serial_number)
+                    .expect("take_known_prefix only accepts packets with a known model");
+                (
+                    parsed.device_profile,
+                    Some((model, err.to_string())),
+                    state_update.body.len() - trailing_bytes.len(),
+                )
+            }
+        };
+        let implementation = (profile.implementation)();
+        let state = implementation.initialize(&state_update.body[..known_body_length])?;
+        let degraded_mode = model.map(|(model, parse_error)| {
+            Self::degraded_mode_for(model, &state_update.body, known_body_length, parse_error)
+        });
+        Ok(InitialState {
+            state,
+            implementation,
+            degraded_mode,
+            model_detection: ModelDetection::StateUpdatePacket,
+        })
+    }
+
+    /// Initializes from the full state update packet if possible, and otherwise from the longest
+    /// prefix that can be parsed.
+    fn initialize_tolerantly(
+        implementation: &(dyn DeviceImplementation + Send + Sync),
+        body: &[u8],
+        model: DeviceModel,
+    ) -> crate::Result<(DeviceState, Option<DegradedMode>)> {
+        let err = match implementation.initialize(body) {
+            Ok(state) => return Ok((state, None)),
+            Err(err) => err,
+        };
+        StateUpdatePacket::known_prefix_lengths(body)
+            .into_iter()
+            .find_map(|length| {
+                let state = implementation.initialize(&body[..length]).ok()?;
+                let degraded_mode = Self::degraded_mode_for(model, body, length, err.to_string());
+                Some((state, Some(degraded_mode)))
+            })
+            .ok_or(err)
+    }
 
-        Ok((state, implementation))
+    fn degraded_mode_for(
+        model: DeviceModel,
+        body: &[u8],
+        known_length: usize,
+        parse_error: String,
+    ) -> DegradedMode {
+        let degraded_mode = DegradedMode {
+            model,
+            trailing_bytes: body[known_length..].to_vec(),
+            parse_error,
+        };
+        warn!(
+            "state update packet for {model} has {} unknown trailing bytes, continuing in degraded \
+            mode",
+            degraded_mode.trailing_bytes.len(),
+        );
+        degraded_mode
     }
 
     fn spawn_inbound_packet_handler(
@@ -97,17 +300,26 @@ where
         >,
         mut inbound_receiver: mpsc::Receiver<Packet>,
         state_sender_lock: Arc<Mutex<watch::Sender<DeviceState>>>,
+        hear_id_history: Arc<std::sync::Mutex<HearIdHistory>>,
+        is_degraded: bool,
     ) -> FuturesType::JoinHandleType {
         FuturesType::spawn(async move {
             while let Some(packet) = inbound_receiver.recv().await {
                 match packet_handlers.get(&packet.command()) {
                     Some(handler) => {
+                        let body =
+                            if is_degraded && packet.command() == StateUpdatePacket::command() {
+                                Self::strip_unknown_trailing_bytes(&packet.body)
+                            } else {
+                                &packet.body
+                            };
                         let state_sender = state_sender_lock.lock().await;
                         let state = state_sender.borrow();
-                        let new_state = handler(&packet.body, state.to_owned());
+                        let new_state = handler(body, state.to_owned());
                         if new_state != *state {
                             trace!(event = "state_update", old_state = ?state, new_state = ?new_state);
                             mem::drop(state);
+                            Self::record_hear_id(&hear_id_history, &new_state);
                             state_sender.send_replace(new_state);
                         }
                     }
@@ -123,6 +335,20 @@ where
         })
     }
 
+    fn record_hear_id(hear_id_history: &std::sync::Mutex<HearIdHistory>, state: &DeviceState) {
+        hear_id_history
+            .lock()
+            .expect("lock is never held across an await")
+            .record_state(state, FuturesType::now());
+    }
+
+    fn strip_unknown_trailing_bytes(body: &[u8]) -> &[u8] {
+        match StateUpdatePacket::take_known_prefix(body) {
+            Some((_, trailing_bytes)) => &body[..body.len() - trailing_bytes.len()],
+            None => body,
+        }
+    }
+
     async fn handle_response(
         &self,
         response: CommandResponse,
@@ -139,6 +365,41 @@ where
         }
         Ok(())
     }
+
+    async fn write_equalizer_configuration(
+        &self,
+        equalizer_configuration: EqualizerConfiguration,
+    ) -> crate::Result<()> {
+        // The state was already updated optimistically, but it may have changed in other ways
+        // since then, so the packets are built from the latest state.
+        let state = self.state_sender.lock().await.borrow().to_owned();
+        let response = self
+            .implementation
+            .set_equalizer_configuration(state, equalizer_configuration.to_owned())?;
+        match self.send_packets(&response.packets).await {
+            Ok(()) => {
+                *self
+                    .written_equalizer_configuration
+                    .lock()
+                    .expect("lock is never held across an await") = equalizer_configuration;
+                Ok(())
+            }
+            Err(err) => {
+                // If a newer configuration is queued, it will overwrite the state anyway
+                if !self.equalizer_writer.has_pending() {
+                    let written_equalizer_configuration = self
+                        .written_equalizer_configuration
+                        .lock()
+                        .expect("lock is never held across an await")
+                        .to_owned();
+                    self.state_sender.lock().await.send_modify(|state| {
+                        state.equalizer_configuration = written_equalizer_configuration
+                    });
+                }
+                Err(err)
+            }
+        }
+    }
 }
 
 impl<ConnectionType, FuturesType> api::device::Device
@@ -171,14 +432,18 @@ where
         self.state_sender.lock().await.borrow().to_owned()
     }
 
+    async fn degraded_mode(&self) -> Option<DegradedMode> {
+        self.degraded_mode.to_owned()
+    }
+
     async fn set_sound_modes(&self, sound_modes: SoundModes) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
-        if state.device_features.sound_mode.is_none() {
+        let Some(sound_mode_profile) = state.device_features.sound_mode else {
             return Err(crate::Error::FeatureNotSupported {
                 feature_name: "sound modes",
             });
-        }
+        };
         let Some(prev_sound_modes) = state.sound_modes else {
             return Err(crate::Error::MissingData {
                 name: "sound modes",
@@ -187,6 +452,7 @@ where
         if prev_sound_modes == sound_modes {
             return Ok(());
         }
+        sound_mode_profile.validate_sound_modes(&prev_sound_modes, &sound_modes)?;
 
         let response = self.implementation.set_sound_modes(state, sound_modes)?;
         self.handle_response(response, &state_sender).await?;
@@ -243,31 +509,71 @@ where
         &self,
         equalizer_configuration: EqualizerConfiguration,
     ) -> crate::Result<()> {
+        {
+            let state_sender = self.state_sender.lock().await;
+            let state = state_sender.borrow().to_owned();
+
+            if state.device_features.num_equalizer_channels == 0 {
+                return Err(crate::Error::FeatureNotSupported {
+                    feature_name: "equalizer",
+                });
+            }
+            let num_bands = equalizer_configuration
+                .volume_adjustments()
+                .adjustments()
+                .len();
+            if num_bands != state.device_features.num_equalizer_bands {
+                return Err(crate::Error::InvalidValue {
+                    name: "number of equalizer bands",
+                    value: format!(
+                        "{num_bands}, expected {}",
+                        state.device_features.num_equalizer_bands
+                    ),
+                });
+            }
+            if equalizer_configuration == state.equalizer_configuration
+                && !self.equalizer_writer.has_pending()
+            {
+                return Ok(());
+            }
+
+            // Rapid changes such as dragging a slider are coalesced below, so publish the new
+            // state right away rather than once the device acknowledges it.
+            let response = self
+                .implementation
+                .set_equalizer_configuration(state, equalizer_configuration.to_owned())?;
+            state_sender.send_replace(response.new_state);
+        }
+
+        self.equalizer_writer
+            .write(equalizer_configuration, |equalizer_configuration| {
+                self.write_equalizer_configuration(equalizer_configuration)
+            })
+            .await
+    }
+
+    async fn set_dynamic_range_compression(&self, is_enabled: bool) -> crate::Result<()> {
         let state_sender = self.state_sender.lock().await;
         let state = state_sender.borrow().to_owned();
 
-        if state.device_features.num_equalizer_channels == 0 {
+        if !state.supports_dynamic_range_compression() {
             return Err(crate::Error::FeatureNotSupported {
-                feature_name: "equalizer",
+                feature_name: "dynamic range compression",
             });
         }
-        if equalizer_configuration
-            .volume_adjustments()
-            .adjustments()
-            .len()
-            != state.device_features.num_equalizer_bands
-        {
-            return Err(crate::Error::FeatureNotSupported {
-                feature_name: "wrong number of equalizer bands",
-            });
-        }
-        if equalizer_configuration == state.equalizer_configuration {
+        if state.is_dynamic_range_compression_enabled == is_enabled {
             return Ok(());
         }
 
-        let response = self
-            .implementation
-            .set_equalizer_configuration(state, equalizer_configuration)?;
+        // DRC is applied as part of the equalizer packet, so the equalizer has to be resent
+        let equalizer_configuration = state.equalizer_configuration.to_owned();
+        let response = self.implementation.set_equalizer_configuration(
+            DeviceState {
+                is_dynamic_range_compression_enabled: is_enabled,
+                ..state
+            },
+            equalizer_configuration,
+        )?;
         self.handle_response(response, &state_sender).await?;
         Ok(())
     }
@@ -284,6 +590,40 @@ where
 
         let response = self.implementation.set_hear_id(state, hear_id)?;
         self.handle_response(response, &state_sender).await?;
+        Self::record_hear_id(&self.hear_id_history, &state_sender.borrow());
+        Ok(())
+    }
+
+    async fn set_gender_and_age_range(
+        &self,
+        gender: Gender,
+        age_range: AgeRange,
+    ) -> crate::Result<()> {
+        let state_sender = self.state_sender.lock().await;
+        let state = state_sender.borrow().to_owned();
+
+        if !state.device_features.has_hear_id {
+            return Err(crate::Error::FeatureNotSupported {
+                feature_name: "hear id",
+            });
+        }
+        if state.gender == Some(gender) && state.age_range == Some(age_range) {
+            return Ok(());
+        }
+
+        let hear_id = state
+            .hear_id
+            .to_owned()
+            .ok_or(crate::Error::MissingData { name: "hear id" })?;
+        let response = self.implementation.set_hear_id(
+            DeviceState {
+                gender: Some(gender),
+                age_range: Some(age_range),
+                ..state
+            },
+            hear_id,
+        )?;
+        self.handle_response(response, &state_sender).await?;
         Ok(())
     }
 
@@ -348,17 +688,26 @@ mod tests {
         api::device::Device,
         devices::standard::{
             packets::{
-                inbound::{FirmwareVersionUpdatePacket, InboundPacket},
+                inbound::{
+                    state_update_packet::StateUpdatePacket, FirmwareVersionUpdatePacket,
+                    InboundPacket,
+                },
                 outbound::{OutboundPacket, SetEqualizerPacket, SetSoundModePacket},
             },
             structures::{
-                AmbientSoundMode, CustomNoiseCanceling, EqualizerConfiguration, NoiseCancelingMode,
-                SoundModes, VolumeAdjustments,
+                AgeRange, AmbientSoundMode, Command, CustomHearId, CustomNoiseCanceling,
+                EqualizerConfiguration, Gender, HearId, NoiseCancelingMode, SoundModes,
+                VolumeAdjustments,
             },
         },
         futures::TokioFutures,
-        soundcore_device::device::Packet,
+        hear_id_history::HearIdHistory,
+        soundcore_device::{
+            device::{ModelDetection, Packet},
+            device_model::DeviceModel,
+        },
         stub::connection::StubConnection,
+        ErrorCode,
     };
 
     fn example_state_update_packet() -> Vec<u8> {
@@ -391,6 +740,196 @@ mod tests {
         (connection, sender)
     }
 
+    // The example state update packet with a serial number that identifies it as an A3028, and
+    // with bytes added to the end like a firmware update might do
+    fn state_update_body_with_trailing_bytes() -> Vec<u8> {
+        let packet = example_state_update_packet();
+        let mut body = packet[9..packet.len() - 1].to_vec();
+        let serial_number_end = body.len();
+        body[serial_number_end - 4..].copy_from_slice(b"3028");
+        body.extend([0x01, 0x02, 0x03]);
+        body
+    }
+
+    fn state_update_packet_with_trailing_bytes() -> Vec<u8> {
+        Packet {
+            command: StateUpdatePacket::command(),
+            body: state_update_body_with_trailing_bytes(),
+        }
+        .bytes()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_with_unknown_trailing_bytes_is_degraded() {
+        let (connection, sender) = create_test_connection().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(state_update_packet_with_trailing_bytes())
+                .await
+                .unwrap();
+        });
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        let degraded_mode = device.degraded_mode().await.unwrap();
+        assert_eq!(DeviceModel::A3028, degraded_mode.model);
+        assert_eq!(vec![0x01, 0x02, 0x03], degraded_mode.trailing_bytes);
+        assert!(!degraded_mode.parse_error.is_empty());
+        let state = device.state().await;
+        assert_eq!(
+            AmbientSoundMode::Normal,
+            state.sound_modes.unwrap().ambient_sound_mode
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_with_unknown_trailing_bytes_and_detected_model_is_degraded() {
+        let (connection, sender) = create_test_connection().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(state_update_packet_with_trailing_bytes())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(example_firmware_version_packet())
+                .await
+                .unwrap();
+        });
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        assert_eq!(
+            ModelDetection::SerialNumber(DeviceModel::A3028),
+            device.model_detection()
+        );
+        assert_eq!(
+            vec![0x01, 0x02, 0x03],
+            device.degraded_mode().await.unwrap().trailing_bytes
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_detects_model_from_serial_number() {
+        let (connection, sender) = create_test_connection().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(example_firmware_version_packet())
+                .await
+                .unwrap();
+        });
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        assert_eq!(
+            ModelDetection::SerialNumber(DeviceModel::A3028),
+            device.model_detection()
+        );
+        assert_eq!(None, device.degraded_mode().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_falls_back_to_guessing_model_without_serial_number() {
+        let (connection, sender) = create_test_connection().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+        });
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        assert_eq!(ModelDetection::StateUpdatePacket, device.model_detection());
+        assert!(device.state().await.sound_modes.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_does_not_request_firmware_version_when_state_has_serial_number() {
+        let (connection, sender) = create_test_connection().await;
+        let mut body = state_update_body_with_trailing_bytes();
+        body.truncate(body.len() - 3);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(
+                    Packet {
+                        command: StateUpdatePacket::command(),
+                        body,
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+        });
+        let start = tokio::time::Instant::now();
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        // The firmware version request would have been retried with sleeps if it was sent
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(
+            ModelDetection::SerialNumber(DeviceModel::A3028),
+            device.model_detection()
+        );
+        assert_eq!(None, device.degraded_mode().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_falls_back_to_guessing_model_when_detected_model_does_not_parse() {
+        let (connection, sender) = create_test_connection().await;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(
+                    Packet {
+                        command: FirmwareVersionUpdatePacket::command(),
+                        body: "02.0002.000000000000003951".as_bytes().to_vec(),
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+        });
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+        assert_eq!(ModelDetection::StateUpdatePacket, device.model_detection());
+        assert!(device.state().await.sound_modes.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_state_updates_with_unknown_trailing_bytes_are_handled_in_degraded_mode() {
+        let (connection, sender) = create_test_connection().await;
+        sender
+            .send(state_update_packet_with_trailing_bytes())
+            .await
+            .unwrap();
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection)
+            .await
+            .unwrap();
+
+        let mut body = state_update_body_with_trailing_bytes();
+        // ambient sound mode
+        body[35] = 0;
+        let packet = Packet {
+            command: StateUpdatePacket::command(),
+            body,
+        };
+        sender.send(packet.bytes()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        assert_eq!(
+            AmbientSoundMode::NoiseCanceling,
+            device.state().await.sound_modes.unwrap().ambient_sound_mode
+        );
+    }
+
     #[tokio::test(start_paused = true)]
     async fn test_new_with_example_state_update_packet() {
         let (connection, sender) = create_test_connection().await;
@@ -554,14 +1093,52 @@ mod tests {
             .await
             .unwrap();
         tokio::time::sleep(Duration::from_millis(1)).await;
+        // The A3028 doesn't support custom noise canceling, so change something it does support
         let sound_modes = SoundModes {
-            custom_noise_canceling: CustomNoiseCanceling::new(10),
-            ..Default::default()
+            ambient_sound_mode: AmbientSoundMode::Transparency,
+            ..device.state().await.sound_modes.unwrap()
         };
         device.set_sound_modes(sound_modes).await.unwrap();
         device.set_sound_modes(sound_modes).await.unwrap();
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_set_custom_noise_canceling_is_rejected_without_support() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+        // request firmware version packet
+        connection.push_write_return(Ok(())).await;
+
+        let sender_copy = sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_state_update_packet())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_firmware_version_packet())
+                .await
+                .unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let result = device
+            .set_custom_noise_canceling(CustomNoiseCanceling::new(10))
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::FeatureNotSupported {
+                feature_name: "custom noise canceling"
+            })
+        ));
+    }
+
     #[tokio::test(start_paused = true)]
     async fn test_set_equalizer_configuration_called_twice() {
         let (connection, sender) = create_test_connection().await;
@@ -618,4 +1195,302 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_dynamic_range_compression_not_supported() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        let result = device.set_dynamic_range_compression(false).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::FeatureNotSupported { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_gender_and_age_range_not_supported() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        let result = device
+            .set_gender_and_age_range(Gender::Male, AgeRange::From30To39)
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::FeatureNotSupported { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_equalizer_configuration_with_wrong_number_of_bands() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender.send(example_state_update_packet()).await.unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        let result = device
+            .set_equalizer_configuration(EqualizerConfiguration::new_custom_profile(
+                VolumeAdjustments::new([0.0; 10]).unwrap(),
+            ))
+            .await;
+        assert!(matches!(result, Err(crate::Error::InvalidValue { .. })));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_records_hear_id_history() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+        // request firmware version packet
+        connection.push_write_return(Ok(())).await;
+        // set hear id
+        connection.push_write_return(Ok(())).await;
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            // A zeroed A3951 state update packet, which has a custom hear id
+            sender
+                .send(
+                    Packet {
+                        command: StateUpdatePacket::command(),
+                        body: vec![0; 84],
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(
+                    Packet {
+                        command: FirmwareVersionUpdatePacket::command(),
+                        body: "02.0002.000000000000003951".as_bytes().to_vec(),
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender
+                .send(
+                    Packet {
+                        command: Command::new([0x08, 0xee, 0x00, 0x00, 0x00, 0x03, 0x87])
+                            .to_inbound(),
+                        body: Vec::new(),
+                    }
+                    .bytes(),
+                )
+                .await
+                .unwrap();
+        });
+
+        let hear_id_history = Arc::new(std::sync::Mutex::new(HearIdHistory::new()));
+        let device = SoundcoreDevice::<_, TokioFutures>::with_hear_id_history(
+            connection.to_owned(),
+            hear_id_history.to_owned(),
+        )
+        .await
+        .unwrap();
+        let state = device.state().await;
+        let serial_number = state.serial_number.unwrap();
+        assert_eq!(
+            1,
+            hear_id_history.lock().unwrap().list(&serial_number).len()
+        );
+
+        let Some(HearId::Custom(hear_id)) = state.hear_id else {
+            panic!("expected custom hear id");
+        };
+        let new_hear_id = HearId::Custom(CustomHearId {
+            is_enabled: true,
+            ..hear_id
+        });
+        device.set_hear_id(new_hear_id.to_owned()).await.unwrap();
+        let hear_id_history = hear_id_history.lock().unwrap();
+        let entries = hear_id_history.list(&serial_number);
+        assert_eq!(2, entries.len());
+        assert_eq!(new_hear_id, entries[1].hear_id);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_equalizer_configuration_coalesces_writes() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+        // request firmware version packet
+        connection.push_write_return(Ok(())).await;
+        // first set_equalizer_configuration
+        connection.push_write_return(Ok(())).await;
+        // the second configuration is skipped, and the third is sent once the first is acked
+        connection.push_write_return(Ok(())).await;
+        connection
+            .push_write_return(Err(crate::Error::MissingData {
+                name: "there should not be a third set eq config packet",
+            }))
+            .await;
+
+        let sender_copy = sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_state_update_packet())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_firmware_version_packet())
+                .await
+                .unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        let configurations = [1.0, 2.0, 3.0].map(|value| {
+            EqualizerConfiguration::new_custom_profile(VolumeAdjustments::new([value; 8]).unwrap())
+        });
+
+        let ack = Packet {
+            command: SetEqualizerPacket::COMMAND.to_inbound(),
+            body: Vec::new(),
+        }
+        .bytes();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                sender.send(ack.to_owned()).await.unwrap();
+            }
+        });
+
+        let (first, second, third) = tokio::join!(
+            device.set_equalizer_configuration(configurations[0].to_owned()),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                let result = device
+                    .set_equalizer_configuration(configurations[1].to_owned())
+                    .await;
+                // published before the device acknowledges anything
+                assert_eq!(
+                    configurations[2],
+                    device.state().await.equalizer_configuration,
+                );
+                result
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                device
+                    .set_equalizer_configuration(configurations[2].to_owned())
+                    .await
+            },
+        );
+        first.unwrap();
+        second.unwrap();
+        third.unwrap();
+        assert_eq!(
+            configurations[2],
+            device.state().await.equalizer_configuration
+        );
+        assert_eq!(1, connection.write_return_queue_length().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_equalizer_configuration_returns_error_of_coalesced_write() {
+        let (connection, sender) = create_test_connection().await;
+        // request state update packet
+        connection.push_write_return(Ok(())).await;
+        // request firmware version packet
+        connection.push_write_return(Ok(())).await;
+        // first set_equalizer_configuration
+        connection.push_write_return(Ok(())).await;
+        // the third configuration, which is sent on behalf of the second call as well
+        connection
+            .push_write_return(Err(crate::Error::MissingData {
+                name: "the coalesced write fails",
+            }))
+            .await;
+
+        let sender_copy = sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_state_update_packet())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            sender_copy
+                .send(example_firmware_version_packet())
+                .await
+                .unwrap();
+        });
+
+        let device = SoundcoreDevice::<_, TokioFutures>::new(connection.to_owned())
+            .await
+            .unwrap();
+        let configurations = [1.0, 2.0, 3.0].map(|value| {
+            EqualizerConfiguration::new_custom_profile(VolumeAdjustments::new([value; 8]).unwrap())
+        });
+
+        let ack = Packet {
+            command: SetEqualizerPacket::COMMAND.to_inbound(),
+            body: Vec::new(),
+        }
+        .bytes();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            sender.send(ack).await.unwrap();
+        });
+
+        let (first, second, third) = tokio::join!(
+            device.set_equalizer_configuration(configurations[0].to_owned()),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                device
+                    .set_equalizer_configuration(configurations[1].to_owned())
+                    .await
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                device
+                    .set_equalizer_configuration(configurations[2].to_owned())
+                    .await
+            },
+        );
+        first.unwrap();
+        assert!(matches!(
+            second.unwrap_err(),
+            crate::Error::MissingData { .. }
+        ));
+        assert_eq!(ErrorCode::MissingData, third.unwrap_err().code());
+        // rolled back to the last configuration that was written
+        assert_eq!(
+            configurations[0],
+            device.state().await.equalizer_configuration
+        );
+    }
 }
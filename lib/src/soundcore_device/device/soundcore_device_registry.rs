@@ -1,6 +1,8 @@
 use std::{
+    collections::HashMap,
     marker::PhantomData,
     rc::{Rc, Weak},
+    sync::Arc,
 };
 
 use macaddr::MacAddr6;
@@ -12,8 +14,10 @@ use crate::{
         connection::{ConnectionDescriptor, ConnectionRegistry},
         device::{DeviceRegistry, GenericDeviceDescriptor},
     },
+    bug_report::{BugReport, TrafficLog},
     device_utils,
     futures::Futures,
+    hear_id_history::HearIdHistory,
 };
 
 use super::soundcore_device::SoundcoreDevice;
@@ -30,6 +34,9 @@ where
             Weak<SoundcoreDevice<RegistryType::ConnectionType, FuturesType>>,
         >,
     >,
+    hear_id_history: Arc<std::sync::Mutex<HearIdHistory>>,
+    // Outlives the devices, so that bug reports include traffic from before a device was dropped
+    traffic_logs: std::sync::Mutex<HashMap<MacAddr6, TrafficLog>>,
     futures: PhantomData<FuturesType>,
 }
 
@@ -42,6 +49,8 @@ where
         Ok(Self {
             conneciton_registry: connection_registry,
             devices: Mutex::new(WeakValueHashMap::new()),
+            hear_id_history: Default::default(),
+            traffic_logs: Default::default(),
             futures: PhantomData,
         })
     }
@@ -53,7 +62,20 @@ where
         let connection = self.conneciton_registry.connection(mac_address).await?;
 
         if let Some(connection) = connection {
-            SoundcoreDevice::new(connection).await.map(Option::Some)
+            let traffic_log = self
+                .traffic_logs
+                .lock()
+                .expect("lock is never held across an await")
+                .entry(mac_address)
+                .or_default()
+                .to_owned();
+            SoundcoreDevice::with_traffic_log(
+                connection,
+                self.hear_id_history.to_owned(),
+                traffic_log,
+            )
+            .await
+            .map(Option::Some)
         } else {
             Ok(None)
         }
@@ -99,6 +121,38 @@ where
             }
         }
     }
+
+    async fn bug_report(&self, mac_address: MacAddr6) -> crate::Result<Option<BugReport>> {
+        let recent_packets = self
+            .traffic_logs
+            .lock()
+            .expect("lock is never held across an await")
+            .get(&mac_address)
+            .map(TrafficLog::packets)
+            .unwrap_or_default();
+        match self.conneciton_registry.connection(mac_address).await? {
+            Some(connection) => BugReport::collect::<_, FuturesType>(connection, recent_packets)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn hear_id_history(&self) -> crate::Result<HearIdHistory> {
+        Ok(self
+            .hear_id_history
+            .lock()
+            .expect("lock is never held across an await")
+            .to_owned())
+    }
+
+    async fn load_hear_id_history(&self, history: HearIdHistory) -> crate::Result<()> {
+        self.hear_id_history
+            .lock()
+            .expect("lock is never held across an await")
+            .merge(history);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1 +1,3 @@
 pub mod connection;
+#[cfg(test)]
+pub mod tone_player;
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+
+use crate::hear_id_test::{Tone, TonePlayer};
+
+/// Records tones instead of playing them.
+#[derive(Debug, Default)]
+pub struct StubTonePlayer {
+    played_tones: Mutex<Vec<Tone>>,
+}
+
+impl StubTonePlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn played_tones(&self) -> Vec<Tone> {
+        self.played_tones.lock().await.to_owned()
+    }
+}
+
+impl TonePlayer for StubTonePlayer {
+    async fn play(&self, tone: Tone, _duration: Duration) -> crate::Result<()> {
+        self.played_tones.lock().await.push(tone);
+        Ok(())
+    }
+}
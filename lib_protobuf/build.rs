@@ -1,6 +1,9 @@
 use std::io::Result;
 
 fn main() -> Result<()> {
-    prost_build::compile_protos(&["protobuf/device_state.proto"], &["protobuf/"])?;
+    prost_build::compile_protos(
+        &["protobuf/device_state.proto", "protobuf/error.proto"],
+        &["protobuf/"],
+    )?;
     Ok(())
 }
@@ -1,6 +1,7 @@
 use openscq30_lib::{
     device_profile::{
-        DeviceFeatures as LibDeviceFeatures, NoiseCancelingModeType as LibNoiseCancelingModeType,
+        ButtonCapabilities as LibButtonCapabilities, DeviceFeatures as LibDeviceFeatures,
+        NoiseCancelingModeType as LibNoiseCancelingModeType,
         SoundModeProfile as LibSoundModeProfile, TransparencyModeType as LibTransparencyModeType,
     },
     devices::standard::{
@@ -16,7 +17,9 @@ use openscq30_lib::{
             FirmwareVersion as LibFirmwareVersion, HearId as LibHearId,
             HearIdMusicType as LibHearIdMusicType, HearIdType as LibHearIdType,
             ManualNoiseCanceling as LibManualNoiseCanceling,
-            NoTwsButtonAction as LibNoTwsButtonAction, NoiseCancelingMode as LibNoiseCancelingMode,
+            NoTwsButtonAction as LibNoTwsButtonAction,
+            NoiseCancelingAdaptiveSensitivityLevel as LibNoiseCancelingAdaptiveSensitivityLevel,
+            NoiseCancelingMode as LibNoiseCancelingMode,
             NoiseCancelingModeTypeTwo as LibNoiseCancelingModeTypeTwo,
             PresetEqualizerProfile as LibPresetEqualizerProfile, SingleBattery as LibSingleBattery,
             SoundModes as LibSoundModes, SoundModesTypeTwo as LibSoundModesTypeTwo,
@@ -25,6 +28,7 @@ use openscq30_lib::{
             VolumeAdjustments,
         },
     },
+    ErrorCode as LibErrorCode, ErrorDetails as LibErrorDetails,
 };
 
 use crate::protobuf::*;
@@ -36,8 +40,8 @@ impl From<LibDeviceState> for DeviceState {
             battery: value.battery.into(),
             equalizer_configuration: value.equalizer_configuration.into(),
             sound_modes: value.sound_modes.map(Into::into),
-            age_range: value.age_range.map(|age_range| age_range.0.into()),
-            gender: value.gender.map(|gender| gender.0.into()),
+            age_range: value.age_range.map(|age_range| u8::from(age_range).into()),
+            gender: value.gender.map(|gender| u8::from(gender).into()),
             hear_id: value.hear_id.map(Into::into),
             firmware_version: value.firmware_version.map(Into::into),
             custom_button_model: value.custom_button_model.map(Into::into),
@@ -46,6 +50,49 @@ impl From<LibDeviceState> for DeviceState {
                 .map(|serial_number| serial_number.to_string()),
             ambient_sound_mode_cycle: value.ambient_sound_mode_cycle.map(Into::into),
             sound_modes_type_two: value.sound_modes_type_two.map(Into::into),
+            is_dynamic_range_compression_enabled: value.is_dynamic_range_compression_enabled,
+        }
+    }
+}
+
+impl From<LibErrorDetails> for Error {
+    fn from(value: LibErrorDetails) -> Self {
+        Self {
+            code: ErrorCode::from(value.code).into(),
+            message: value.message,
+            serial_number: value
+                .serial_number
+                .map(|serial_number| serial_number.to_string()),
+            mac_address: value.mac_address,
+            uuid: value.uuid,
+            name: value.name,
+            value: value.value,
+            action: value.action,
+            command: value.command.map(|command| command.bytes().to_vec()),
+            parse_offset: value.parse_offset.map(|offset| offset as u32),
+            parse_context: value.parse_context,
+        }
+    }
+}
+
+impl From<LibErrorCode> for ErrorCode {
+    fn from(value: LibErrorCode) -> Self {
+        match value {
+            LibErrorCode::Other => ErrorCode::Other,
+            LibErrorCode::DeviceNotFound => ErrorCode::DeviceNotFound,
+            LibErrorCode::DeviceNotSupported => ErrorCode::DeviceNotSupported,
+            LibErrorCode::NotConnected => ErrorCode::NotConnected,
+            LibErrorCode::NameNotFound => ErrorCode::NameNotFound,
+            LibErrorCode::CharacteristicNotFound => ErrorCode::CharacteristicNotFound,
+            LibErrorCode::ServiceNotFound => ErrorCode::ServiceNotFound,
+            LibErrorCode::NoResponse => ErrorCode::NoResponse,
+            LibErrorCode::FeatureNotSupported => ErrorCode::FeatureNotSupported,
+            LibErrorCode::MissingData => ErrorCode::MissingData,
+            LibErrorCode::WriteFailed => ErrorCode::WriteFailed,
+            LibErrorCode::IncompleteState => ErrorCode::IncompleteState,
+            LibErrorCode::TimedOut => ErrorCode::TimedOut,
+            LibErrorCode::InvalidValue => ErrorCode::InvalidValue,
+            LibErrorCode::ParseError => ErrorCode::ParseError,
         }
     }
 }
@@ -65,6 +112,18 @@ impl From<LibDeviceFeatures> for DeviceFeatures {
             dynamic_range_compression_min_firmware_version: value
                 .dynamic_range_compression_min_firmware_version
                 .map(Into::into),
+            button_capabilities: value.button_capabilities.into(),
+        }
+    }
+}
+
+impl From<LibButtonCapabilities> for ButtonCapabilities {
+    fn from(value: LibButtonCapabilities) -> Self {
+        Self {
+            has_single_click: value.has_single_click,
+            has_double_click: value.has_double_click,
+            has_long_press: value.has_long_press,
+            supported_actions: value.supported_actions.into(),
         }
     }
 }
@@ -235,9 +294,16 @@ impl From<SoundModes> for LibSoundModes {
             transparency_mode: LibTransparencyMode::from(
                 TransparencyMode::try_from(value.transparency_mode).unwrap(),
             ),
-            custom_noise_canceling: LibCustomNoiseCanceling::new(
-                value.custom_noise_canceling as u8,
-            ),
+            custom_noise_canceling: if value.is_custom_noise_canceling_adaptive() {
+                LibCustomNoiseCanceling::ADAPTIVE
+            } else {
+                LibCustomNoiseCanceling::new(
+                    value
+                        .custom_noise_canceling
+                        .try_into()
+                        .unwrap_or(LibCustomNoiseCanceling::MAX_LEVEL),
+                )
+            },
         }
     }
 }
@@ -279,6 +345,7 @@ impl From<LibSoundModes> for SoundModes {
             noise_canceling_mode: NoiseCancelingMode::from(value.noise_canceling_mode).into(),
             transparency_mode: TransparencyMode::from(value.transparency_mode).into(),
             custom_noise_canceling: value.custom_noise_canceling.value().into(),
+            is_custom_noise_canceling_adaptive: Some(value.custom_noise_canceling.is_adaptive()),
         }
     }
 }
@@ -362,8 +429,8 @@ impl From<LibCustomHearId> for CustomHearId {
             is_enabled: value.is_enabled,
             volume_adjustments: value.volume_adjustments.into(),
             time: value.time,
-            hear_id_type: value.hear_id_type.0.into(),
-            hear_id_music_type: value.hear_id_music_type.0.into(),
+            hear_id_type: u8::from(value.hear_id_type).into(),
+            hear_id_music_type: u8::from(value.hear_id_music_type).into(),
             custom_volume_adjustments: value.custom_volume_adjustments.map(Into::into),
         }
     }
@@ -403,8 +470,8 @@ impl From<CustomHearId> for LibCustomHearId {
             is_enabled: value.is_enabled,
             volume_adjustments: value.volume_adjustments.into(),
             time: value.time,
-            hear_id_type: LibHearIdType(value.hear_id_type as u8),
-            hear_id_music_type: LibHearIdMusicType(value.hear_id_music_type as u8),
+            hear_id_type: LibHearIdType::from(value.hear_id_type as u8),
+            hear_id_music_type: LibHearIdMusicType::from(value.hear_id_music_type as u8),
             custom_volume_adjustments: value.custom_volume_adjustments.map(Into::into),
         }
     }
@@ -436,7 +503,7 @@ impl From<LibNoTwsButtonAction> for NoTwsButtonAction {
     fn from(value: LibNoTwsButtonAction) -> Self {
         Self {
             is_enabled: value.is_enabled,
-            action: ButtonAction::from(value.action).into(),
+            action: u8::from(value.action).into(),
         }
     }
 }
@@ -445,22 +512,8 @@ impl From<LibTwsButtonAction> for TwsButtonAction {
     fn from(value: LibTwsButtonAction) -> Self {
         Self {
             is_enabled: value.is_enabled,
-            tws_connected_action: ButtonAction::from(value.tws_connected_action).into(),
-            tws_disconnected_action: ButtonAction::from(value.tws_disconnected_action).into(),
-        }
-    }
-}
-
-impl From<LibButtonAction> for ButtonAction {
-    fn from(value: LibButtonAction) -> Self {
-        match value {
-            LibButtonAction::VolumeUp => Self::VolumeUp,
-            LibButtonAction::VolumeDown => Self::VolumeDown,
-            LibButtonAction::PreviousSong => Self::PreviousSong,
-            LibButtonAction::NextSong => Self::NextSong,
-            LibButtonAction::AmbientSoundMode => Self::AmbientSoundMode,
-            LibButtonAction::VoiceAssistant => Self::VoiceAssistant,
-            LibButtonAction::PlayPause => Self::PlayPause,
+            tws_connected_action: u8::from(value.tws_connected_action).into(),
+            tws_disconnected_action: u8::from(value.tws_disconnected_action).into(),
         }
     }
 }
@@ -482,7 +535,7 @@ impl From<NoTwsButtonAction> for LibNoTwsButtonAction {
     fn from(value: NoTwsButtonAction) -> Self {
         Self {
             is_enabled: value.is_enabled,
-            action: ButtonAction::try_from(value.action).unwrap().into(),
+            action: button_action_from_protobuf(value.action),
         }
     }
 }
@@ -491,28 +544,16 @@ impl From<TwsButtonAction> for LibTwsButtonAction {
     fn from(value: TwsButtonAction) -> Self {
         Self {
             is_enabled: value.is_enabled,
-            tws_connected_action: ButtonAction::try_from(value.tws_connected_action)
-                .unwrap()
-                .into(),
-            tws_disconnected_action: ButtonAction::try_from(value.tws_disconnected_action)
-                .unwrap()
-                .into(),
+            tws_connected_action: button_action_from_protobuf(value.tws_connected_action),
+            tws_disconnected_action: button_action_from_protobuf(value.tws_disconnected_action),
         }
     }
 }
 
-impl From<ButtonAction> for LibButtonAction {
-    fn from(value: ButtonAction) -> Self {
-        match value {
-            ButtonAction::VolumeUp => Self::VolumeUp,
-            ButtonAction::VolumeDown => Self::VolumeDown,
-            ButtonAction::PreviousSong => Self::PreviousSong,
-            ButtonAction::NextSong => Self::NextSong,
-            ButtonAction::AmbientSoundMode => Self::AmbientSoundMode,
-            ButtonAction::VoiceAssistant => Self::VoiceAssistant,
-            ButtonAction::PlayPause => Self::PlayPause,
-        }
-    }
+// Button actions are passed through as their raw ids so that actions unknown to the library
+// survive a round trip
+fn button_action_from_protobuf(value: i32) -> LibButtonAction {
+    LibButtonAction::from(u8::try_from(value).unwrap_or(u8::MAX))
 }
 
 impl From<LibSoundModesTypeTwo> for SoundModesTypeTwo {
@@ -528,6 +569,7 @@ impl From<LibSoundModesTypeTwo> for SoundModesTypeTwo {
             wind_noise_suppression: value.wind_noise_suppression,
             noise_canceling_adaptive_sensitivity_level: value
                 .noise_canceling_adaptive_sensitivity_level
+                .value()
                 .into(),
         }
     }
@@ -583,9 +625,11 @@ impl From<SoundModesTypeTwo> for LibSoundModesTypeTwo {
                 .unwrap()
                 .into(),
             wind_noise_suppression: value.wind_noise_suppression,
-            noise_canceling_adaptive_sensitivity_level: value
-                .noise_canceling_adaptive_sensitivity_level
-                as u8,
+            noise_canceling_adaptive_sensitivity_level:
+                LibNoiseCancelingAdaptiveSensitivityLevel::from(
+                    u8::try_from(value.noise_canceling_adaptive_sensitivity_level)
+                        .unwrap_or(u8::MAX),
+                ),
         }
     }
 }
@@ -1,9 +1,12 @@
-use openscq30_lib::devices::standard::{
-    state::DeviceState,
-    structures::{
-        AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, HearId,
-        PresetEqualizerProfile, SoundModes, SoundModesTypeTwo,
+use openscq30_lib::{
+    devices::standard::{
+        state::DeviceState,
+        structures::{
+            AmbientSoundModeCycle, CustomButtonModel, EqualizerConfiguration, HearId,
+            PresetEqualizerProfile, SoundModes, SoundModesTypeTwo,
+        },
     },
+    Error,
 };
 use prost::{DecodeError, Message};
 
@@ -14,6 +17,10 @@ pub fn serialize_device_state(device_state: DeviceState) -> Vec<u8> {
     protobuf::DeviceState::from(device_state).encode_to_vec()
 }
 
+pub fn serialize_error(error: &Error) -> Vec<u8> {
+    protobuf::Error::from(error.details()).encode_to_vec()
+}
+
 pub fn serialize_equalizer_configuration(configuration: EqualizerConfiguration) -> Vec<u8> {
     protobuf::EqualizerConfiguration::from(configuration).encode_to_vec()
 }
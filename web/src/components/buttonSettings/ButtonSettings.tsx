@@ -8,7 +8,10 @@ import {
 } from "@mui/material";
 import React from "react";
 import { useTranslation } from "react-i18next";
-import { ButtonAction, CustomButtonModel } from "../../libTypes/DeviceState";
+import {
+  CustomButtonModel,
+  KnownButtonAction,
+} from "../../libTypes/DeviceState";
 
 export const ButtonSettings = React.memo(function ({
   buttonModel,
@@ -42,7 +45,7 @@ export const ButtonSettings = React.memo(function ({
             buttonKey={key}
             label={label}
             action={getButtonAction(buttonModel[key])}
-            setAction={(action: ButtonAction | "disabled") => {
+            setAction={(action: KnownButtonAction | "disabled") => {
               setButtonModel({
                 ...buttonModel,
                 [key]: setButtonAction(buttonModel[key], action),
@@ -57,15 +60,18 @@ export const ButtonSettings = React.memo(function ({
 
 function getButtonAction(
   button: CustomButtonModel[keyof CustomButtonModel],
-): ButtonAction | "disabled" {
+): KnownButtonAction | "disabled" | "unknown" {
   if (button.isEnabled) {
-    return "action" in button ? button.action : button.twsConnectedAction;
+    const action =
+      "action" in button ? button.action : button.twsConnectedAction;
+    // Actions that the library doesn't know about can be displayed but not selected
+    return typeof action == "string" ? action : "unknown";
   }
   return "disabled";
 }
 function setButtonAction<
   ActionType extends CustomButtonModel[keyof CustomButtonModel],
->(button: ActionType, action: ButtonAction | "disabled"): ActionType {
+>(button: ActionType, action: KnownButtonAction | "disabled"): ActionType {
   if (action == "disabled") {
     return {
       ...button,
@@ -96,14 +102,14 @@ const ButtonActionSelection = React.memo(function ({
 }: {
   label: string;
   buttonKey: string;
-  action: ButtonAction | "disabled";
-  setAction: (action: ButtonAction | "disabled") => void;
+  action: KnownButtonAction | "disabled" | "unknown";
+  setAction: (action: KnownButtonAction | "disabled") => void;
 }) {
   const { t } = useTranslation();
 
   const options: {
     label: string;
-    value: ButtonAction | "disabled";
+    value: KnownButtonAction | "disabled";
   }[] = [
     { label: t("buttonActions.disabled"), value: "disabled" },
     { label: t("buttonActions.volumeUp"), value: "volumeUp" },
@@ -123,13 +129,20 @@ const ButtonActionSelection = React.memo(function ({
         labelId={labelId}
         value={action}
         label={label}
-        onChange={(action) => setAction(action.target.value as ButtonAction)}
+        onChange={(action) =>
+          setAction(action.target.value as KnownButtonAction | "disabled")
+        }
       >
         {options.map(({ label, value }) => (
           <MenuItem value={value} key={value}>
             {label}
           </MenuItem>
         ))}
+        {action == "unknown" && (
+          <MenuItem value="unknown" disabled>
+            {t("buttonActions.unknown")}
+          </MenuItem>
+        )}
       </Select>
     </FormControl>
   );
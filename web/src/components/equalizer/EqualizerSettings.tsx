@@ -1,13 +1,21 @@
 import { Add, Delete } from "@mui/icons-material";
-import { IconButton, Stack, SxProps, Typography } from "@mui/material";
+import {
+  Alert,
+  Button,
+  IconButton,
+  Stack,
+  SxProps,
+  Typography,
+} from "@mui/material";
 import { isEqual } from "lodash-es";
-import React, { useCallback } from "react";
+import React, { useCallback, useMemo } from "react";
 import { useTranslation } from "react-i18next";
 import { CustomEqualizerProfile } from "../../storage/db";
 import { CustomProfiles } from "./CustomProfiles";
 import { Equalizer } from "./Equalizer";
 import { PresetProfiles } from "./PresetProfiles";
 import { PresetEqualizerProfile } from "../../libTypes/DeviceState";
+import { EqualizerHelper } from "../../../wasm/pkg/openscq30_web_wasm";
 
 interface Props {
   profile: PresetEqualizerProfile | "custom";
@@ -44,6 +52,20 @@ export const EqualizerSettings = React.memo(function (props: Props) {
     [onValueChange],
   );
 
+  const peakGain = useMemo(() => {
+    const values = new Float64Array(props.values);
+    return EqualizerHelper.isClippingLikely(values)
+      ? EqualizerHelper.getPeakGain(values)
+      : undefined;
+  }, [props.values]);
+
+  const normalize = useCallback(() => {
+    const normalized = EqualizerHelper.normalizeVolumeAdjustments(
+      new Float64Array(props.values),
+    );
+    normalized.forEach((value, index) => onValueChange(index, value));
+  }, [props.values, onValueChange]);
+
   const deleteSelectedCustomProfile = useCallback(() => {
     if (selectedCustomProfile) {
       onDeleteCustomProfile(selectedCustomProfile);
@@ -85,6 +107,20 @@ export const EqualizerSettings = React.memo(function (props: Props) {
           ))}
       </Stack>
       <Equalizer values={props.values} onValueChange={onValueChange} />
+      {peakGain != undefined && (
+        <Alert
+          severity="warning"
+          action={
+            <Button color="inherit" size="small" onClick={normalize}>
+              {t("equalizer.normalize")}
+            </Button>
+          }
+        >
+          {t("equalizer.clippingWarning", {
+            peakGain: peakGain.toFixed(1),
+          })}
+        </Alert>
+      )}
     </Stack>
   );
 });
@@ -1,15 +1,19 @@
 import { useCallback } from "react";
+import { useTranslation } from "react-i18next";
+import { knownLibErrorCode } from "../libTypes/LibError";
 import { useToasts } from "./useToasts";
 
 export function useToastErrorHandler(message: string) {
+  const { t } = useTranslation();
   const toasts = useToasts();
   return useCallback(
     (err: Error) => {
       console.error(err);
+      const code = knownLibErrorCode(err);
       toasts.addToast({
-        message,
+        message: code ? `${message} ${t(`errors.codes.${code}`)}` : message,
       });
     },
-    [message, toasts],
+    [message, toasts, t],
   );
 }
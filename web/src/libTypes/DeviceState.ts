@@ -101,7 +101,7 @@ export type PresetEqualizerProfile = NonNullable<
   EqualizerConfiguration["presetProfile"]
 >;
 
-const buttonActionSchema = Type.Union([
+const knownButtonActionSchema = Type.Union([
   Type.Literal("volumeUp"),
   Type.Literal("volumeDown"),
   Type.Literal("previousSong"),
@@ -110,6 +110,11 @@ const buttonActionSchema = Type.Union([
   Type.Literal("voiceAssistant"),
   Type.Literal("playPause"),
 ]);
+export type KnownButtonAction = Static<typeof knownButtonActionSchema>;
+const buttonActionSchema = Type.Union([
+  knownButtonActionSchema,
+  Type.Object({ unknown: Type.Number({ minimum: 0, maximum: 255 }) }),
+]);
 export type ButtonAction = Static<typeof buttonActionSchema>;
 
 const twsButtonActionSchema = Type.Object({
@@ -151,6 +156,12 @@ const deviceFeaturesSchema = Type.Object({
   hasAutoPowerOff: Type.Boolean(),
   hasAmbientSoundModeCycle: Type.Boolean(),
   dynamicRangeCompressionMinFirmwareVersion: Nullable(firmwareVersionSchema),
+  buttonCapabilities: Type.Object({
+    hasSingleClick: Type.Boolean(),
+    hasDoubleClick: Type.Boolean(),
+    hasLongPress: Type.Boolean(),
+    supportedActions: Type.Number({ minimum: 0 }),
+  }),
 });
 export type DeviceFeatures = Static<typeof deviceFeaturesSchema>;
 
@@ -216,6 +227,7 @@ const deviceStateSchema = Type.Object({
   customButtonModel: Nullable(customButtonModelSchema),
   serialNumber: Nullable(Type.String()),
   ambientSoundModeCycle: Nullable(ambientSoundModeCycleSchema),
+  isDynamicRangeCompressionEnabled: Type.Boolean(),
 });
 export type DeviceState = Static<typeof deviceStateSchema>;
 export const DeviceStateValidator = TypeCompiler.Compile(deviceStateSchema);
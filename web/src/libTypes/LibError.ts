@@ -0,0 +1,53 @@
+import { Static, Type } from "@sinclair/typebox";
+import { TypeCompiler } from "@sinclair/typebox/compiler";
+
+// Must match ErrorCode in the rust library. Codes are stable, but new ones may be added.
+const knownLibErrorCodes = [
+  "deviceNotFound",
+  "deviceNotSupported",
+  "notConnected",
+  "nameNotFound",
+  "characteristicNotFound",
+  "serviceNotFound",
+  "other",
+  "noResponse",
+  "featureNotSupported",
+  "missingData",
+  "writeFailed",
+  "incompleteState",
+  "timedOut",
+  "invalidValue",
+  "parseError",
+] as const;
+export type KnownLibErrorCode = (typeof knownLibErrorCodes)[number];
+
+const libErrorSchema = Type.Object({
+  code: Type.String(),
+  details: Type.Object({
+    code: Type.String(),
+    message: Type.String(),
+    serialNumber: Type.Union([Type.String(), Type.Null()]),
+    macAddress: Type.Union([Type.String(), Type.Null()]),
+    uuid: Type.Union([Type.String(), Type.Null()]),
+    name: Type.Union([Type.String(), Type.Null()]),
+    value: Type.Union([Type.String(), Type.Null()]),
+    action: Type.Union([Type.String(), Type.Null()]),
+    command: Type.Union([Type.Array(Type.Number()), Type.Null()]),
+    parseOffset: Type.Union([Type.Number(), Type.Null()]),
+    parseContext: Type.Array(Type.String()),
+  }),
+});
+export type LibError = Static<typeof libErrorSchema>;
+
+const libErrorValidator = TypeCompiler.Compile(libErrorSchema);
+
+export function isLibError(err: unknown): err is LibError {
+  return libErrorValidator.Check(err);
+}
+
+export function knownLibErrorCode(err: unknown): KnownLibErrorCode | undefined {
+  if (!isLibError(err)) {
+    return undefined;
+  }
+  return knownLibErrorCodes.find((code) => code === err.code);
+}
@@ -35,6 +35,12 @@ describe("App", () => {
                 hasAutoPowerOff: false,
                 dynamicRangeCompressionMinFirmwareVersion: null,
                 hasAmbientSoundModeCycle: false,
+                buttonCapabilities: {
+                  hasSingleClick: false,
+                  hasDoubleClick: false,
+                  hasLongPress: false,
+                  supportedActions: 0,
+                },
               },
               battery: {
                 type: "singleBattery",
@@ -63,6 +69,7 @@ describe("App", () => {
               firmwareVersion: null,
               serialNumber: null,
               ambientSoundModeCycle: null,
+              isDynamicRangeCompressionEnabled: true,
             }),
             connect: vi.fn<() => void>(),
             async setSoundModes(soundModes: SoundModes) {
@@ -56,6 +56,12 @@ describe("Device Settings", () => {
           hasAutoPowerOff: true,
           dynamicRangeCompressionMinFirmwareVersion: null,
           hasAmbientSoundModeCycle: false,
+          buttonCapabilities: {
+            hasSingleClick: false,
+            hasDoubleClick: false,
+            hasLongPress: false,
+            supportedActions: 0,
+          },
         },
         battery: {
           type: "singleBattery",
@@ -84,6 +90,7 @@ describe("Device Settings", () => {
         firmwareVersion: null,
         serialNumber: null,
         ambientSoundModeCycle: null,
+        isDynamicRangeCompressionEnabled: true,
       }),
       connect: vi.fn<() => void>(),
       async setSoundModes(soundModes: SoundModes) {
@@ -258,6 +265,29 @@ describe("Device Settings", () => {
     expect(Number(sliders[0].value)).toEqual(12);
   });
 
+  it("should warn about clipping and normalize", async () => {
+    const renderResult = render(
+      <DeviceSettings
+        device={device}
+        // eslint-disable-next-line @typescript-eslint/no-empty-function
+        disconnect={() => {}}
+      />,
+    );
+
+    expect(renderResult.queryByText("equalizer.clippingWarning")).toBeFalsy();
+    const numberInputs: NodeListOf<HTMLInputElement> =
+      renderResult.baseElement.querySelectorAll("input[type='number']");
+    await user.type(numberInputs[0], "9");
+    expect(renderResult.queryByText("equalizer.clippingWarning")).toBeTruthy();
+
+    await user.click(
+      renderResult.getByRole("button", { name: "equalizer.normalize" }),
+    );
+    expect(renderResult.queryByText("equalizer.clippingWarning")).toBeFalsy();
+    expect(Number(numberInputs[0].value)).toEqual(0);
+    expect(Number(numberInputs[1].value)).toEqual(-9);
+  });
+
   it("should debounce equalizer updates", async () => {
     const renderResult = render(
       <DeviceSettings
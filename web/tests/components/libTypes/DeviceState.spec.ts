@@ -17,6 +17,12 @@ describe("libTypes", () => {
         hasAutoPowerOff: false,
         dynamicRangeCompressionMinFirmwareVersion: null,
         hasAmbientSoundModeCycle: false,
+        buttonCapabilities: {
+          hasSingleClick: false,
+          hasDoubleClick: false,
+          hasLongPress: false,
+          supportedActions: 0,
+        },
       },
       ageRange: null,
       gender: null,
@@ -36,6 +42,7 @@ describe("libTypes", () => {
       soundModes: null,
       soundModesTypeTwo: null,
       ambientSoundModeCycle: null,
+      isDynamicRangeCompressionEnabled: true,
     };
     const actual: unknown = JSON.parse(
       WasmTest.deserializeAndReserializeForTests(JSON.stringify(expected)),
@@ -62,6 +69,12 @@ describe("libTypes", () => {
           minor: 3,
         },
         hasAmbientSoundModeCycle: true,
+        buttonCapabilities: {
+          hasSingleClick: false,
+          hasDoubleClick: false,
+          hasLongPress: false,
+          supportedActions: 0,
+        },
       },
       ageRange: 1,
       gender: 2,
@@ -150,6 +163,7 @@ describe("libTypes", () => {
         transparencyMode: true,
         normalMode: true,
       },
+      isDynamicRangeCompressionEnabled: false,
     };
     const actual: unknown = JSON.parse(
       WasmTest.deserializeAndReserializeForTests(JSON.stringify(expected)),
@@ -0,0 +1,22 @@
+import { describe, expect, it } from "vitest";
+import { isLibError, knownLibErrorCode } from "../../../src/libTypes/LibError";
+import { WasmTest } from "../../../wasm/pkg/openscq30_web_wasm";
+
+describe("LibError", () => {
+  it("should accept an error with unset details", () => {
+    const err: unknown = WasmTest.invalidValueErrorForTests();
+    expect(isLibError(err)).toBe(true);
+    expect(knownLibErrorCode(err)).toBe("invalidValue");
+  });
+
+  it("should accept an error with parse details", () => {
+    const err: unknown = WasmTest.parseErrorForTests();
+    expect(isLibError(err)).toBe(true);
+    expect(knownLibErrorCode(err)).toBe("parseError");
+    if (isLibError(err)) {
+      expect(err.details.parseOffset).toBe(1);
+      expect(err.details.parseContext).toEqual(["test"]);
+      expect(err.details.command).toEqual([1, 2, 3, 4, 5, 6, 7]);
+    }
+  });
+});
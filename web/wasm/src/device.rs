@@ -16,7 +16,7 @@ use tokio::sync::watch;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 use web_sys::BluetoothDevice;
 
-use crate::web_bluetooth_connection::WebBluetoothConnection;
+use crate::{lib_error::lib_error_to_js, web_bluetooth_connection::WebBluetoothConnection};
 
 #[wasm_bindgen]
 pub struct Device {
@@ -32,7 +32,7 @@ impl Device {
         #[allow(clippy::arc_with_non_send_sync)]
         let device = SoundcoreDevice::<_, WasmFutures>::new(Arc::new(connection))
             .await
-            .map_err(|err| format!("{err:?}"))?;
+            .map_err(lib_error_to_js)?;
         Ok(Self {
             inner: DeviceImplementation::WebBluetooth(device),
         })
@@ -47,8 +47,8 @@ impl Device {
     }
 
     #[wasm_bindgen(js_name = "getName")]
-    pub async fn name(&self) -> Result<String, String> {
-        self.inner.name().await.map_err(|err| format!("{err:?}"))
+    pub async fn name(&self) -> Result<String, JsValue> {
+        self.inner.name().await.map_err(lib_error_to_js)
     }
 
     #[wasm_bindgen(js_name = "setSoundModes")]
@@ -58,7 +58,7 @@ impl Device {
         self.inner
             .set_sound_modes(sound_modes)
             .await
-            .map_err(|err| format!("{err:?}"))?;
+            .map_err(lib_error_to_js)?;
         Ok(())
     }
 
@@ -69,7 +69,7 @@ impl Device {
         self.inner
             .set_sound_modes_type_two(sound_modes)
             .await
-            .map_err(|err| format!("{err:?}"))?;
+            .map_err(lib_error_to_js)?;
         Ok(())
     }
 
@@ -83,7 +83,16 @@ impl Device {
         self.inner
             .set_equalizer_configuration(equalizer_configuration)
             .await
-            .map_err(|err| format!("{err:?}"))?;
+            .map_err(lib_error_to_js)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "setDynamicRangeCompression")]
+    pub async fn set_dynamic_range_compression(&self, is_enabled: bool) -> Result<(), JsValue> {
+        self.inner
+            .set_dynamic_range_compression(is_enabled)
+            .await
+            .map_err(lib_error_to_js)?;
         Ok(())
     }
 
@@ -97,7 +106,7 @@ impl Device {
         self.inner
             .set_custom_button_model(custom_button_model)
             .await
-            .map_err(|err| format!("{err:?}"))?;
+            .map_err(lib_error_to_js)?;
         Ok(())
     }
 
@@ -186,6 +195,20 @@ impl DeviceImplementation {
         }
     }
 
+    pub async fn set_dynamic_range_compression(
+        &self,
+        is_enabled: bool,
+    ) -> openscq30_lib::Result<()> {
+        match self {
+            DeviceImplementation::WebBluetooth(device) => {
+                device.set_dynamic_range_compression(is_enabled).await
+            }
+            DeviceImplementation::Demo(device) => {
+                device.set_dynamic_range_compression(is_enabled).await
+            }
+        }
+    }
+
     pub async fn set_custom_button_model(
         &self,
         custom_button_model: CustomButtonModel,
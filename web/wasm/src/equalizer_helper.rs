@@ -31,4 +31,34 @@ impl EqualizerHelper {
             .adjustments()
             .to_vec())
     }
+
+    #[wasm_bindgen(js_name = "getPeakGain")]
+    pub fn peak_gain(volume_adjustments: Vec<f64>) -> Result<f64, String> {
+        let volume_adjustments =
+            VolumeAdjustments::new(volume_adjustments).map_err(|err| format!("{err:?}"))?;
+        Ok(volume_adjustments.analyze().peak_gain)
+    }
+
+    #[wasm_bindgen(js_name = "isClippingLikely")]
+    pub fn is_clipping_likely(volume_adjustments: Vec<f64>) -> Result<bool, String> {
+        let volume_adjustments =
+            VolumeAdjustments::new(volume_adjustments).map_err(|err| format!("{err:?}"))?;
+        Ok(volume_adjustments.analyze().is_clipping_likely())
+    }
+
+    #[wasm_bindgen(js_name = "normalizeVolumeAdjustments")]
+    pub fn normalize_volume_adjustments(volume_adjustments: Vec<f64>) -> Result<Vec<f64>, String> {
+        let volume_adjustments =
+            VolumeAdjustments::new(volume_adjustments).map_err(|err| format!("{err:?}"))?;
+        Ok(volume_adjustments.normalize().adjustments().to_vec())
+    }
+
+    #[wasm_bindgen(js_name = "applyDynamicRangeCompression")]
+    pub fn apply_dynamic_range_compression(
+        volume_adjustments: Vec<f64>,
+    ) -> Result<Vec<f64>, String> {
+        let volume_adjustments =
+            VolumeAdjustments::new(volume_adjustments).map_err(|err| format!("{err:?}"))?;
+        Ok(volume_adjustments.apply_drc().adjustments().to_vec())
+    }
 }
@@ -1,16 +1,17 @@
 mod device;
 mod equalizer_helper;
 mod jsvalue_error;
+mod lib_error;
 mod soundcore_device_utils;
 pub mod web_bluetooth_connection;
 
 pub use device::*;
 pub use equalizer_helper::*;
 pub use jsvalue_error::*;
-use openscq30_lib::devices::standard::state::DeviceState;
+use openscq30_lib::devices::standard::{state::DeviceState, structures::Command};
 pub use soundcore_device_utils::*;
 
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 #[wasm_bindgen(start)]
 pub fn initialize() {
@@ -28,4 +29,22 @@ impl WasmTest {
             serde_json::from_str::<DeviceState>(&input).map_err(|err| format!("{err:?}"))?;
         serde_json::to_string(&state).map_err(|err| format!("{err:?}"))
     }
+
+    #[wasm_bindgen(js_name = "invalidValueErrorForTests")]
+    pub fn invalid_value_error_for_tests() -> JsValue {
+        lib_error::lib_error_to_js(openscq30_lib::Error::InvalidValue {
+            name: "test",
+            value: "1".to_owned(),
+        })
+    }
+
+    #[wasm_bindgen(js_name = "parseErrorForTests")]
+    pub fn parse_error_for_tests() -> JsValue {
+        lib_error::lib_error_to_js(openscq30_lib::Error::ParseError {
+            message: "test".to_owned(),
+            offset: Some(1),
+            context: vec!["test"],
+            command: Some(Command::new([1, 2, 3, 4, 5, 6, 7])),
+        })
+    }
 }
@@ -0,0 +1,21 @@
+use js_sys::Reflect;
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::JsValue;
+
+/// Converts a library error into a JS `Error` with a stable `code` property and a `details`
+/// property containing the structured fields, so that the frontend can react to specific errors
+/// without parsing the message. Missing fields are `null` rather than `undefined`, matching
+/// `LibError.ts`.
+pub fn lib_error_to_js(error: openscq30_lib::Error) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    let details = error
+        .details()
+        .serialize(&Serializer::json_compatible())
+        .expect("error details should always be serializable");
+    Reflect::set(&js_error, &"code".into(), &error.code().as_ref().into())
+        .expect("error object should be extensible");
+    Reflect::set(&js_error, &"details".into(), &details)
+        .expect("error object should be extensible");
+    js_error.into()
+}